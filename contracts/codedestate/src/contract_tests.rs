@@ -1,24 +1,48 @@
 #![cfg(test)]
-use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+};
 
 use cosmwasm_std::{
-    from_binary, to_binary, Addr, CosmosMsg, DepsMut, Empty, Response, StdError, WasmMsg,
+    coins, from_binary, to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, DepsMut, Empty, Env,
+    OwnedDeps, Response, StdError, Timestamp, Uint128, WasmMsg,
 };
+use serde::de::DeserializeOwned;
 
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 use cw721::{
-    Approval, ApprovalResponse, ContractInfoResponse, Cw721Query, Cw721ReceiveMsg, Expiration,
-    NftInfoResponse, OperatorResponse, OperatorsResponse, OwnerOfResponse,
+    civil_date, refund_percentage, refund_schedule, weekday, Approval, ApprovalResponse,
+    AuctionStatusResponse, AvailabilityCalendar, AvailabilityResponse, BidderBidsResponse,
+    BookingWindow, CancellationItem, ContractInfoResponse, CurrentPriceResponse, Cw721Query,
+    DamageDepositsResponse, DateRange, EscrowSaleResponse, Expiration,
+    ExpiredGroupReservationsResponse, ExpiredReservationsResponse, FeeCharge, FeeKind, FeeLine,
+    FeeRecipient, FeeRefund, GroupReservation, HostPolicy, HostRecordResponse, InstantBookRules,
+    LongTermQuote, NftInfoResponse, OffersResponse, OperatorResponse, OperatorsResponse,
+    OwnerOfResponse, PayerShare, PriceAdjustment, PriceCondition, PriceRule, PromoCode,
+    PromoDiscount, RatingResponse, RecurringClosure, RentalsResponse, ReviewsResponse,
+    RewardsResponse, Royalty, ShareHoldersResponse, ShortTermQuote, ShortTermRental, Trait,
+    TravelerProfile, REVIEW_WINDOW,
 };
 use cw_ownable::OwnershipError;
 
+use crate::msg::ReceiveMsg;
+use crate::state::Owner;
 use crate::{
-    ContractError, Cw721Contract, ExecuteMsg, Extension, InstantiateMsg, MinterResponse, QueryMsg,
+    ContractError, Cw721Contract, ExecuteMsg, Extension, InstantiateMsg, Metadata, MinterResponse,
+    QueryMsg,
 };
 
 const MINTER: &str = "merlin";
 const CONTRACT_NAME: &str = "Magic Power";
 const SYMBOL: &str = "MGK";
 
+fn token_owner(address: String) -> Owner {
+    Owner {
+        chain_type: "cosmos".to_string(),
+        address,
+    }
+}
+
 fn setup_contract(deps: DepsMut<'_>) -> Cw721Contract<'static, Extension, Empty, Empty, Empty> {
     let contract = Cw721Contract::default();
     let msg = InstantiateMsg {
@@ -32,6 +56,83 @@ fn setup_contract(deps: DepsMut<'_>) -> Cw721Contract<'static, Extension, Empty,
     contract
 }
 
+type Contract = Cw721Contract<'static, Extension, Empty, Empty, Empty>;
+type MockDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+/// Contract owned by `MINTER` with a 1% platform fee
+fn setup_market() -> (MockDeps, Contract) {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::SetFeeValue { fee: 100 },
+        )
+        .unwrap();
+    (deps, contract)
+}
+
+fn mint_token(deps: &mut MockDeps, contract: &Contract, token_id: &str, owner: &str) {
+    let mint_msg = ExecuteMsg::Mint {
+        token_id: token_id.to_string(),
+        owner: token_owner(owner.to_string()),
+        token_uri: None,
+        extension: None,
+        royalty: None,
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), mock_info(owner, &[]), mint_msg)
+        .unwrap();
+}
+
+fn env_at(seconds: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(seconds);
+    env
+}
+
+fn exec(
+    deps: &mut MockDeps,
+    contract: &Contract,
+    seconds: u64,
+    sender: &str,
+    funds: &[Coin],
+    msg: ExecuteMsg<Extension, Empty>,
+) -> Result<Response, ContractError> {
+    contract.execute(
+        deps.as_mut(),
+        env_at(seconds),
+        mock_info(sender, funds),
+        msg,
+    )
+}
+
+fn query<R: DeserializeOwned>(
+    deps: &MockDeps,
+    contract: &Contract,
+    seconds: u64,
+    msg: QueryMsg<Empty>,
+) -> R {
+    from_binary(&contract.query(deps.as_ref(), env_at(seconds), msg).unwrap()).unwrap()
+}
+
+/// (recipient, denom, amount) of every bank transfer in the response
+fn bank_sends(res: &Response) -> Vec<(String, String, u128)> {
+    res.messages
+        .iter()
+        .filter_map(|sub| match &sub.msg {
+            CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => Some((
+                to_address.clone(),
+                amount[0].denom.clone(),
+                amount[0].amount.u128(),
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
 #[test]
 fn proper_instantiation() {
     let mut deps = mock_dependencies();
@@ -66,7 +167,9 @@ fn proper_instantiation() {
     assert_eq!(0, count.count);
 
     // list the token_ids
-    let tokens = contract.all_tokens(deps.as_ref(), None, None).unwrap();
+    let tokens = contract
+        .all_tokens(deps.as_ref(), String::new(), None, None)
+        .unwrap();
     assert_eq!(0, tokens.tokens.len());
 }
 
@@ -80,22 +183,16 @@ fn minting() {
 
     let mint_msg = ExecuteMsg::Mint {
         token_id: token_id.clone(),
-        owner: String::from("medusa"),
+        owner: token_owner(String::from("medusa")),
         token_uri: Some(token_uri.clone()),
         extension: None,
+        royalty: None,
     };
 
-    // random cannot mint
+    // minting is open to anyone
     let random = mock_info("random", &[]);
-    let err = contract
-        .execute(deps.as_mut(), mock_env(), random, mint_msg.clone())
-        .unwrap_err();
-    assert_eq!(err, ContractError::Ownership(OwnershipError::NotOwner));
-
-    // minter can mint
-    let allowed = mock_info(MINTER, &[]);
     let _ = contract
-        .execute(deps.as_mut(), mock_env(), allowed, mint_msg)
+        .execute(deps.as_mut(), mock_env(), random, mint_msg)
         .unwrap();
 
     // ensure num tokens increases
@@ -132,9 +229,10 @@ fn minting() {
     // Cannot mint same token_id again
     let mint_msg2 = ExecuteMsg::Mint {
         token_id: token_id.clone(),
-        owner: String::from("hercules"),
+        owner: token_owner(String::from("hercules")),
         token_uri: None,
         extension: None,
+        royalty: None,
     };
 
     let allowed = mock_info(MINTER, &[]);
@@ -144,7 +242,9 @@ fn minting() {
     assert_eq!(err, ContractError::Claimed {});
 
     // list the token_ids
-    let tokens = contract.all_tokens(deps.as_ref(), None, None).unwrap();
+    let tokens = contract
+        .all_tokens(deps.as_ref(), String::new(), None, None)
+        .unwrap();
     assert_eq!(1, tokens.tokens.len());
     assert_eq!(vec![token_id], tokens.tokens);
 }
@@ -159,9 +259,10 @@ fn test_update_minter() {
 
     let mint_msg = ExecuteMsg::Mint {
         token_id,
-        owner: String::from("medusa"),
+        owner: token_owner(String::from("medusa")),
         token_uri: Some(token_uri.clone()),
         extension: None,
+        royalty: None,
     };
 
     // Minter can mint
@@ -232,20 +333,39 @@ fn test_update_minter() {
 
     let mint_msg = ExecuteMsg::Mint {
         token_id: "randoms_token".to_string(),
-        owner: String::from("medusa"),
+        owner: token_owner(String::from("medusa")),
         token_uri: Some(token_uri),
         extension: None,
+        royalty: None,
     };
 
-    // Old owner can not mint.
+    // Old owner can not set the fee any more, the new one can.
     let err: ContractError = contract
-        .execute(deps.as_mut(), mock_env(), minter_info, mint_msg.clone())
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter_info,
+            ExecuteMsg::SetFeeValue { fee: 100 },
+        )
         .unwrap_err();
     assert_eq!(err, ContractError::Ownership(OwnershipError::NotOwner));
+    let _ = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            random_info,
+            ExecuteMsg::SetFeeValue { fee: 100 },
+        )
+        .unwrap();
 
-    // New owner can mint.
+    // Minting stays open to anyone.
     let _ = contract
-        .execute(deps.as_mut(), mock_env(), random_info, mint_msg)
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("medusa", &[]),
+            mint_msg,
+        )
         .unwrap();
 }
 
@@ -259,9 +379,10 @@ fn burning() {
 
     let mint_msg = ExecuteMsg::Mint {
         token_id: token_id.clone(),
-        owner: MINTER.to_string(),
+        owner: token_owner(MINTER.to_string()),
         token_uri: Some(token_uri),
         extension: None,
+        royalty: None,
     };
 
     let burn_msg = ExecuteMsg::Burn { token_id };
@@ -294,7 +415,9 @@ fn burning() {
         .unwrap_err();
 
     // list the token_ids
-    let tokens = contract.all_tokens(deps.as_ref(), None, None).unwrap();
+    let tokens = contract
+        .all_tokens(deps.as_ref(), String::new(), None, None)
+        .unwrap();
     assert!(tokens.tokens.is_empty());
 }
 
@@ -309,9 +432,10 @@ fn transferring_nft() {
 
     let mint_msg = ExecuteMsg::Mint {
         token_id: token_id.clone(),
-        owner: String::from("venus"),
+        owner: token_owner(String::from("venus")),
         token_uri: Some(token_uri),
         extension: None,
+        royalty: None,
     };
 
     let minter = mock_info(MINTER, &[]);
@@ -347,73 +471,6 @@ fn transferring_nft() {
         Response::new()
             .add_attribute("action", "transfer_nft")
             .add_attribute("sender", "venus")
-            .add_attribute("recipient", "random")
-            .add_attribute("token_id", token_id)
-    );
-}
-
-#[test]
-fn sending_nft() {
-    let mut deps = mock_dependencies();
-    let contract = setup_contract(deps.as_mut());
-
-    // Mint a token
-    let token_id = "melt".to_string();
-    let token_uri = "https://www.merriam-webster.com/dictionary/melt".to_string();
-
-    let mint_msg = ExecuteMsg::Mint {
-        token_id: token_id.clone(),
-        owner: String::from("venus"),
-        token_uri: Some(token_uri),
-        extension: None,
-    };
-
-    let minter = mock_info(MINTER, &[]);
-    contract
-        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
-        .unwrap();
-
-    let msg = to_binary("You now have the melting power").unwrap();
-    let target = String::from("another_contract");
-    let send_msg = ExecuteMsg::SendNft {
-        contract: target.clone(),
-        token_id: token_id.clone(),
-        msg: msg.clone(),
-    };
-
-    let random = mock_info("random", &[]);
-    let err = contract
-        .execute(deps.as_mut(), mock_env(), random, send_msg.clone())
-        .unwrap_err();
-    assert_eq!(err, ContractError::Ownership(OwnershipError::NotOwner));
-
-    // but owner can
-    let random = mock_info("venus", &[]);
-    let res = contract
-        .execute(deps.as_mut(), mock_env(), random, send_msg)
-        .unwrap();
-
-    let payload = Cw721ReceiveMsg {
-        sender: String::from("venus"),
-        token_id: token_id.clone(),
-        msg,
-    };
-    let expected = payload.into_cosmos_msg(target.clone()).unwrap();
-    // ensure expected serializes as we think it should
-    match &expected {
-        CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) => {
-            assert_eq!(contract_addr, &target)
-        }
-        m => panic!("Unexpected message type: {m:?}"),
-    }
-    // and make sure this is the request sent by the contract
-    assert_eq!(
-        res,
-        Response::new()
-            .add_message(expected)
-            .add_attribute("action", "send_nft")
-            .add_attribute("sender", "venus")
-            .add_attribute("recipient", "another_contract")
             .add_attribute("token_id", token_id)
     );
 }
@@ -429,9 +486,10 @@ fn approving_revoking() {
 
     let mint_msg = ExecuteMsg::Mint {
         token_id: token_id.clone(),
-        owner: String::from("demeter"),
+        owner: token_owner(String::from("demeter")),
         token_uri: Some(token_uri),
         extension: None,
+        royalty: None,
     };
 
     let minter = mock_info(MINTER, &[]);
@@ -498,14 +556,19 @@ fn approving_revoking() {
         }
     );
 
-    // random can now transfer
+    // transfers need the owner or an operator, a token approval is not enough
     let random = mock_info("random", &[]);
     let transfer_msg = ExecuteMsg::TransferNft {
         recipient: String::from("person"),
         token_id: token_id.clone(),
     };
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), random, transfer_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, ContractError::Ownership(OwnershipError::NotOwner));
+    let owner = mock_info("demeter", &[]);
     contract
-        .execute(deps.as_mut(), mock_env(), random, transfer_msg)
+        .execute(deps.as_mut(), mock_env(), owner, transfer_msg)
         .unwrap();
 
     // Approvals are removed / cleared
@@ -576,9 +639,10 @@ fn approving_all_revoking_all() {
 
     let mint_msg1 = ExecuteMsg::Mint {
         token_id: token_id1.clone(),
-        owner: String::from("demeter"),
+        owner: token_owner(String::from("demeter")),
         token_uri: Some(token_uri1),
         extension: None,
+        royalty: None,
     };
 
     let minter = mock_info(MINTER, &[]);
@@ -588,9 +652,10 @@ fn approving_all_revoking_all() {
 
     let mint_msg2 = ExecuteMsg::Mint {
         token_id: token_id2.clone(),
-        owner: String::from("demeter"),
+        owner: token_owner(String::from("demeter")),
         token_uri: Some(token_uri2),
         extension: None,
+        royalty: None,
     };
 
     contract
@@ -598,11 +663,18 @@ fn approving_all_revoking_all() {
         .unwrap();
 
     // paginate the token_ids
-    let tokens = contract.all_tokens(deps.as_ref(), None, Some(1)).unwrap();
+    let tokens = contract
+        .all_tokens(deps.as_ref(), String::new(), None, Some(1))
+        .unwrap();
     assert_eq!(1, tokens.tokens.len());
     assert_eq!(vec![token_id1.clone()], tokens.tokens);
     let tokens = contract
-        .all_tokens(deps.as_ref(), Some(token_id1.clone()), Some(3))
+        .all_tokens(
+            deps.as_ref(),
+            String::new(),
+            Some(token_id1.clone()),
+            Some(3),
+        )
         .unwrap();
     assert_eq!(1, tokens.tokens.len());
     assert_eq!(vec![token_id2.clone()], tokens.tokens);
@@ -634,21 +706,12 @@ fn approving_all_revoking_all() {
         .execute(deps.as_mut(), mock_env(), random.clone(), transfer_msg)
         .unwrap();
 
-    // random can now send
-    let inner_msg = WasmMsg::Execute {
-        contract_addr: "another_contract".into(),
-        msg: to_binary("You now also have the growing power").unwrap(),
-        funds: vec![],
-    };
-    let msg: CosmosMsg = CosmosMsg::Wasm(inner_msg);
-
-    let send_msg = ExecuteMsg::SendNft {
-        contract: String::from("another_contract"),
+    let transfer_msg = ExecuteMsg::TransferNft {
+        recipient: String::from("person"),
         token_id: token_id2,
-        msg: to_binary(&msg).unwrap(),
     };
     contract
-        .execute(deps.as_mut(), mock_env(), random, send_msg)
+        .execute(deps.as_mut(), mock_env(), random, transfer_msg)
         .unwrap();
 
     // Approve_all, revoke_all, and check for empty, to test revoke_all
@@ -746,25 +809,6 @@ fn approving_all_revoking_all() {
             }]
         }
     );
-    let res = contract
-        .operators(
-            deps.as_ref(),
-            mock_env(),
-            String::from("person"),
-            true,
-            Some(String::from("buddy")),
-            Some(2),
-        )
-        .unwrap();
-    assert_eq!(
-        res,
-        OperatorsResponse {
-            operators: vec![cw721::Approval {
-                spender: String::from("operator"),
-                expires: Expiration::Never {}
-            }]
-        }
-    );
 
     let revoke_all_msg = ExecuteMsg::RevokeAll {
         operator: String::from("operator"),
@@ -852,9 +896,10 @@ fn query_tokens_by_owner() {
 
     let mint_msg = ExecuteMsg::Mint {
         token_id: token_id1.clone(),
-        owner: demeter.clone(),
+        owner: token_owner(demeter.clone()),
         token_uri: None,
         extension: None,
+        royalty: None,
     };
     contract
         .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
@@ -862,9 +907,10 @@ fn query_tokens_by_owner() {
 
     let mint_msg = ExecuteMsg::Mint {
         token_id: token_id2.clone(),
-        owner: ceres.clone(),
+        owner: token_owner(ceres.clone()),
         token_uri: None,
         extension: None,
+        royalty: None,
     };
     contract
         .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
@@ -872,9 +918,10 @@ fn query_tokens_by_owner() {
 
     let mint_msg = ExecuteMsg::Mint {
         token_id: token_id3.clone(),
-        owner: demeter.clone(),
+        owner: token_owner(demeter.clone()),
         token_uri: None,
         extension: None,
+        royalty: None,
     };
     contract
         .execute(deps.as_mut(), mock_env(), minter, mint_msg)
@@ -882,13 +929,22 @@ fn query_tokens_by_owner() {
 
     // get all tokens in order:
     let expected = vec![token_id1.clone(), token_id2.clone(), token_id3.clone()];
-    let tokens = contract.all_tokens(deps.as_ref(), None, None).unwrap();
+    let tokens = contract
+        .all_tokens(deps.as_ref(), String::new(), None, None)
+        .unwrap();
     assert_eq!(&expected, &tokens.tokens);
     // paginate
-    let tokens = contract.all_tokens(deps.as_ref(), None, Some(2)).unwrap();
+    let tokens = contract
+        .all_tokens(deps.as_ref(), String::new(), None, Some(2))
+        .unwrap();
     assert_eq!(&expected[..2], &tokens.tokens[..]);
     let tokens = contract
-        .all_tokens(deps.as_ref(), Some(expected[1].clone()), None)
+        .all_tokens(
+            deps.as_ref(),
+            String::new(),
+            Some(expected[1].clone()),
+            None,
+        )
         .unwrap();
     assert_eq!(&expected[2..], &tokens.tokens[..]);
 
@@ -913,3 +969,7153 @@ fn query_tokens_by_owner() {
         .unwrap();
     assert_eq!(&by_demeter[1..], &tokens.tokens[..]);
 }
+
+// English auctions
+
+#[test]
+fn english_auction_extends_and_settles() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        100,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForAuction {
+            token_id: "t".into(),
+            denom: "u".into(),
+            reserve_price: 1000,
+            start_time: 100,
+            end_time: 1000,
+            minimum_increment: 10,
+            extension_window: 60,
+        },
+    )
+    .unwrap();
+    assert!(exec(
+        &mut deps,
+        &contract,
+        200,
+        "bob",
+        &coins(999, "u"),
+        ExecuteMsg::SetBidForAuction {
+            token_id: "t".into()
+        }
+    )
+    .is_err());
+    exec(
+        &mut deps,
+        &contract,
+        200,
+        "bob",
+        &coins(1000, "u"),
+        ExecuteMsg::SetBidForAuction {
+            token_id: "t".into(),
+        },
+    )
+    .unwrap();
+    assert!(exec(
+        &mut deps,
+        &contract,
+        200,
+        "carol",
+        &coins(1005, "u"),
+        ExecuteMsg::SetBidForAuction {
+            token_id: "t".into()
+        }
+    )
+    .is_err());
+    let r = exec(
+        &mut deps,
+        &contract,
+        980,
+        "carol",
+        &coins(1010, "u"),
+        ExecuteMsg::SetBidForAuction {
+            token_id: "t".into(),
+        },
+    )
+    .unwrap();
+    assert_eq!(bank_sends(&r), vec![("bob".into(), "u".into(), 1000)]);
+    let st: AuctionStatusResponse = query(
+        &deps,
+        &contract,
+        980,
+        QueryMsg::AuctionStatus {
+            token_id: "t".into(),
+        },
+    );
+    assert_eq!(st.end_time, 1040);
+    assert_eq!(st.time_remaining, 60);
+    assert!(exec(
+        &mut deps,
+        &contract,
+        1039,
+        "x",
+        &[],
+        ExecuteMsg::SettleAuction {
+            token_id: "t".into()
+        }
+    )
+    .is_err());
+    assert!(exec(
+        &mut deps,
+        &contract,
+        1000,
+        "alice",
+        &[],
+        ExecuteMsg::TransferNft {
+            recipient: "x".into(),
+            token_id: "t".into()
+        }
+    )
+    .is_err());
+    let r = exec(
+        &mut deps,
+        &contract,
+        1040,
+        "x",
+        &[],
+        ExecuteMsg::SettleAuction {
+            token_id: "t".into(),
+        },
+    )
+    .unwrap();
+    assert_eq!(bank_sends(&r), vec![("alice".into(), "u".into(), 1000)]);
+    let o: OwnerOfResponse = query(
+        &deps,
+        &contract,
+        1040,
+        QueryMsg::OwnerOf {
+            token_id: "t".into(),
+            include_expired: None,
+        },
+    );
+    assert_eq!(o.owner, "carol");
+    let b: Uint128 = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::GetBalance { denom: "u".into() },
+    );
+    assert_eq!(b.u128(), 10);
+}
+
+// Dutch auctions
+
+#[test]
+fn dutch_auction_price_decays_to_floor() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        100,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForDutchAuction {
+            token_id: "t".into(),
+            denom: "u".into(),
+            start_price: 10000,
+            floor_price: 2000,
+            start_time: 100,
+            end_time: 900,
+            step_interval: 100,
+        },
+    )
+    .unwrap();
+    let p: CurrentPriceResponse = query(
+        &deps,
+        &contract,
+        350,
+        QueryMsg::CurrentPrice {
+            token_id: "t".into(),
+        },
+    );
+    assert_eq!(p.price.u128(), 8000);
+    let p: CurrentPriceResponse = query(
+        &deps,
+        &contract,
+        5000,
+        QueryMsg::CurrentPrice {
+            token_id: "t".into(),
+        },
+    );
+    assert_eq!(p.price.u128(), 2000);
+    assert!(exec(
+        &mut deps,
+        &contract,
+        350,
+        "bob",
+        &coins(7999, "u"),
+        ExecuteMsg::BuyDutchAuction {
+            token_id: "t".into()
+        }
+    )
+    .is_err());
+    let r = exec(
+        &mut deps,
+        &contract,
+        350,
+        "bob",
+        &coins(9000, "u"),
+        ExecuteMsg::BuyDutchAuction {
+            token_id: "t".into(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        bank_sends(&r),
+        vec![
+            ("alice".into(), "u".into(), 7920),
+            ("bob".into(), "u".into(), 1000)
+        ]
+    );
+    assert!(exec(
+        &mut deps,
+        &contract,
+        351,
+        "carol",
+        &coins(9000, "u"),
+        ExecuteMsg::BuyDutchAuction {
+            token_id: "t".into()
+        }
+    )
+    .is_err());
+}
+
+#[test]
+fn dutch_auction_needs_a_future_end_and_prices_large_amounts() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    let list = |start_price, end_time| ExecuteMsg::SetListForDutchAuction {
+        token_id: "t".into(),
+        denom: "u".into(),
+        start_price,
+        floor_price: 0,
+        start_time: 100,
+        end_time,
+        step_interval: 0,
+    };
+    assert_eq!(
+        exec(&mut deps, &contract, 900, "alice", &[], list(10000, 900)).unwrap_err(),
+        ContractError::InvalidInput {}
+    );
+    exec(
+        &mut deps,
+        &contract,
+        100,
+        "alice",
+        &[],
+        list(u128::MAX, 900),
+    )
+    .unwrap();
+    let p: CurrentPriceResponse = query(
+        &deps,
+        &contract,
+        500,
+        QueryMsg::CurrentPrice {
+            token_id: "t".into(),
+        },
+    );
+    assert_eq!(p.price.u128(), u128::MAX - u128::MAX / 2);
+}
+
+// Instant purchases
+
+#[test]
+fn auto_approved_bid_settles_sale_atomically() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        100,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForSell {
+            islisted: true,
+            token_id: "t".into(),
+            denom: "u".into(),
+            price: 5000,
+            auto_approve: false,
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        100,
+        "bob",
+        &coins(5000, "u"),
+        ExecuteMsg::SetBidToBuy {
+            token_id: "t".into(),
+            expires: None,
+        },
+    )
+    .unwrap();
+    // without auto_approve the bid waits for the seller
+    let owner: OwnerOfResponse = query(
+        &deps,
+        &contract,
+        100,
+        QueryMsg::OwnerOf {
+            token_id: "t".into(),
+            include_expired: None,
+        },
+    );
+    assert_eq!(owner.owner, "alice");
+
+    mint_token(&mut deps, &contract, "t2", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        100,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForSell {
+            islisted: true,
+            token_id: "t2".into(),
+            denom: "u".into(),
+            price: 5000,
+            auto_approve: true,
+        },
+    )
+    .unwrap();
+    let r = exec(
+        &mut deps,
+        &contract,
+        100,
+        "carol",
+        &coins(6000, "u"),
+        ExecuteMsg::SetBidToBuy {
+            token_id: "t2".into(),
+            expires: None,
+        },
+    )
+    .unwrap();
+    // the sale settles at the listed price and the overpayment goes back to the buyer
+    assert_eq!(
+        bank_sends(&r),
+        vec![
+            ("alice".into(), "u".into(), 4950),
+            ("carol".into(), "u".into(), 1000)
+        ]
+    );
+    let o: OwnerOfResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::OwnerOf {
+            token_id: "t2".into(),
+            include_expired: None,
+        },
+    );
+    assert_eq!(o.owner, "carol");
+    let s: cw721::Sell = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::NftInfoSell {
+            token_id: "t2".into(),
+        },
+    );
+    assert_eq!(s.islisted, None);
+}
+
+// Bid expiry and counter-offers
+
+#[test]
+fn bids_stored_without_expiry_never_expire() {
+    let b: cw721::Bid =
+        cosmwasm_std::from_slice(br#"{"address":"a","offer":"5","denom":"u"}"#).unwrap();
+    assert_eq!(b.expires, None);
+}
+
+#[test]
+fn expired_bids_reclaimed_and_counter_offers_accepted() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        100,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForSell {
+            islisted: true,
+            token_id: "t".into(),
+            denom: "u".into(),
+            price: 5000,
+            auto_approve: false,
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        100,
+        "bob",
+        &coins(5000, "u"),
+        ExecuteMsg::SetBidToBuy {
+            token_id: "t".into(),
+            expires: None,
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        100,
+        "dan",
+        &coins(5500, "u"),
+        ExecuteMsg::SetBidToBuy {
+            token_id: "t".into(),
+            expires: Some(Expiration::AtTime(Timestamp::from_seconds(200))),
+        },
+    )
+    .unwrap();
+    let r = exec(
+        &mut deps,
+        &contract,
+        300,
+        "anyone",
+        &[],
+        ExecuteMsg::ReclaimExpiredBids {
+            token_id: "t".into(),
+        },
+    )
+    .unwrap();
+    assert_eq!(bank_sends(&r), vec![("dan".into(), "u".into(), 5500)]);
+    assert!(exec(
+        &mut deps,
+        &contract,
+        300,
+        "bob",
+        &[],
+        ExecuteMsg::SetCounterOffer {
+            token_id: "t".into(),
+            bidder: "bob".into(),
+            amount: 6000
+        }
+    )
+    .is_err());
+    exec(
+        &mut deps,
+        &contract,
+        300,
+        "alice",
+        &[],
+        ExecuteMsg::SetCounterOffer {
+            token_id: "t".into(),
+            bidder: "bob".into(),
+            amount: 6000,
+        },
+    )
+    .unwrap();
+    assert!(exec(
+        &mut deps,
+        &contract,
+        300,
+        "bob",
+        &coins(999, "u"),
+        ExecuteMsg::AcceptCounterOffer {
+            token_id: "t".into()
+        }
+    )
+    .is_err());
+    let r = exec(
+        &mut deps,
+        &contract,
+        300,
+        "bob",
+        &coins(1100, "u"),
+        ExecuteMsg::AcceptCounterOffer {
+            token_id: "t".into(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        bank_sends(&r),
+        vec![
+            ("alice".into(), "u".into(), 5940),
+            ("bob".into(), "u".into(), 100)
+        ]
+    );
+    let b: cw721::BidsResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::NftBids {
+            token_id: "t".into(),
+        },
+    );
+    assert!(b.bids.is_empty());
+    let o: OwnerOfResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::OwnerOf {
+            token_id: "t".into(),
+            include_expired: None,
+        },
+    );
+    assert_eq!(o.owner, "bob");
+}
+
+#[test]
+fn transfer_to_non_bidder_refunds_bids_and_drops_counter_offers() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        100,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForSell {
+            islisted: true,
+            token_id: "t".into(),
+            denom: "u".into(),
+            price: 5000,
+            auto_approve: false,
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        100,
+        "bob",
+        &coins(5000, "u"),
+        ExecuteMsg::SetBidToBuy {
+            token_id: "t".into(),
+            expires: None,
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        100,
+        "alice",
+        &[],
+        ExecuteMsg::SetCounterOffer {
+            token_id: "t".into(),
+            bidder: "bob".into(),
+            amount: 5500,
+        },
+    )
+    .unwrap();
+
+    let r = exec(
+        &mut deps,
+        &contract,
+        100,
+        "alice",
+        &[],
+        ExecuteMsg::TransferNft {
+            recipient: "carol".into(),
+            token_id: "t".into(),
+        },
+    )
+    .unwrap();
+    assert_eq!(bank_sends(&r), vec![("bob".into(), "u".into(), 5000)]);
+    let b: cw721::BidsResponse = query(
+        &deps,
+        &contract,
+        100,
+        QueryMsg::NftBids {
+            token_id: "t".into(),
+        },
+    );
+    assert!(b.bids.is_empty());
+    let r: BidderBidsResponse = query(
+        &deps,
+        &contract,
+        100,
+        QueryMsg::BidsByBidder {
+            bidder: "bob".into(),
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert!(r.bids.is_empty());
+
+    // alice's counter-offer does not carry over to carol's property
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            100,
+            "bob",
+            &coins(500, "u"),
+            ExecuteMsg::AcceptCounterOffer {
+                token_id: "t".into(),
+            },
+        )
+        .unwrap_err(),
+        ContractError::NotReserved {}
+    );
+    let o: OwnerOfResponse = query(
+        &deps,
+        &contract,
+        100,
+        QueryMsg::OwnerOf {
+            token_id: "t".into(),
+            include_expired: None,
+        },
+    );
+    assert_eq!(o.owner, "carol");
+}
+
+// CW20 payments
+
+fn cw20_bid(sender: &str, amount: u128, token_id: &str) -> ExecuteMsg<Extension, Empty> {
+    ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: sender.to_string(),
+        amount: Uint128::new(amount),
+        msg: to_binary(&ReceiveMsg::SetBidToBuy {
+            token_id: token_id.to_string(),
+            expires: None,
+        })
+        .unwrap(),
+    })
+}
+
+#[test]
+fn cw20_payments_need_an_allowed_token() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    let list_msg = ExecuteMsg::SetListForSell {
+        islisted: true,
+        token_id: "t".into(),
+        denom: "token".into(),
+        price: 5000,
+        auto_approve: true,
+    };
+    exec(&mut deps, &contract, 100, "alice", &[], list_msg).unwrap();
+
+    // an account calling Receive directly is not a CW20 token
+    let err = exec(
+        &mut deps,
+        &contract,
+        100,
+        "token",
+        &[],
+        cw20_bid("bob", 5000, "t"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Cw20NotAllowed {});
+
+    let allow = |allowed| ExecuteMsg::SetCw20Token {
+        address: "token".into(),
+        allowed,
+    };
+    let err = exec(&mut deps, &contract, 100, "alice", &[], allow(true)).unwrap_err();
+    assert_eq!(err, ContractError::Ownership(OwnershipError::NotOwner));
+    exec(&mut deps, &contract, 100, MINTER, &[], allow(true)).unwrap();
+
+    let funds = coins(10, "unative");
+    let err = exec(
+        &mut deps,
+        &contract,
+        100,
+        "token",
+        &funds,
+        cw20_bid("bob", 5000, "t"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidDeposit {});
+
+    // the seller is paid with a CW20 transfer
+    let res = exec(
+        &mut deps,
+        &contract,
+        100,
+        "token",
+        &[],
+        cw20_bid("bob", 5000, "t"),
+    )
+    .unwrap();
+    match &res.messages[0].msg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr, msg, ..
+        }) => {
+            assert_eq!(contract_addr, "token");
+            assert_eq!(
+                from_binary::<Cw20ExecuteMsg>(msg).unwrap(),
+                Cw20ExecuteMsg::Transfer {
+                    recipient: "alice".into(),
+                    amount: Uint128::new(4950),
+                }
+            );
+        }
+        m => panic!("Unexpected message: {m:?}"),
+    }
+
+    // a disallowed token can't pay any more, fees held in it are still withdrawn as CW20
+    exec(&mut deps, &contract, 100, MINTER, &[], allow(false)).unwrap();
+    mint_token(&mut deps, &contract, "t2", "alice");
+    let list_msg = ExecuteMsg::SetListForSell {
+        islisted: true,
+        token_id: "t2".into(),
+        denom: "token".into(),
+        price: 5000,
+        auto_approve: true,
+    };
+    exec(&mut deps, &contract, 100, "alice", &[], list_msg).unwrap();
+    let err = exec(
+        &mut deps,
+        &contract,
+        100,
+        "token",
+        &[],
+        cw20_bid("bob", 5000, "t2"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Cw20NotAllowed {});
+    let withdraw_msg = ExecuteMsg::Withdraw {
+        target: MINTER.into(),
+        amount: Coin::new(50, "token"),
+    };
+    let res = exec(&mut deps, &contract, 100, MINTER, &[], withdraw_msg).unwrap();
+    assert!(matches!(
+        &res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) if contract_addr == "token"
+    ));
+}
+
+// Royalties
+
+#[test]
+fn royalties_paid_on_sales_and_transfers() {
+    let (mut deps, contract) = setup_market();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        MINTER,
+        &[],
+        ExecuteMsg::SetDefaultRoyalty {
+            royalty: Some(Royalty {
+                recipient: "artist".into(),
+                share: 500,
+                on_rentals: false,
+            }),
+        },
+    )
+    .unwrap();
+    assert!(exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &[],
+        ExecuteMsg::SetDefaultRoyalty { royalty: None }
+    )
+    .is_err());
+    mint_token(&mut deps, &contract, "t", "alice");
+    let r: cw721::RoyaltiesInfoResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::RoyaltyInfo {
+            token_id: "t".into(),
+            sale_price: Uint128::new(10000),
+        },
+    );
+    assert_eq!(r.address, "artist");
+    assert_eq!(r.royalty_amount.u128(), 500);
+    exec(
+        &mut deps,
+        &contract,
+        100,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForSell {
+            islisted: true,
+            token_id: "t".into(),
+            denom: "u".into(),
+            price: 5000,
+            auto_approve: true,
+        },
+    )
+    .unwrap();
+    let res = exec(
+        &mut deps,
+        &contract,
+        100,
+        "carol",
+        &coins(10000, "u"),
+        ExecuteMsg::SetBidToBuy {
+            token_id: "t".into(),
+            expires: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        bank_sends(&res),
+        vec![
+            ("artist".into(), "u".into(), 250),
+            ("alice".into(), "u".into(), 4700),
+            ("carol".into(), "u".into(), 5000)
+        ]
+    );
+    // manual transfer to a bidder
+    mint_token(&mut deps, &contract, "t2", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        100,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForSell {
+            islisted: true,
+            token_id: "t2".into(),
+            denom: "u".into(),
+            price: 5000,
+            auto_approve: false,
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        100,
+        "bob",
+        &coins(5000, "u"),
+        ExecuteMsg::SetBidToBuy {
+            token_id: "t2".into(),
+            expires: None,
+        },
+    )
+    .unwrap();
+    let res = exec(
+        &mut deps,
+        &contract,
+        100,
+        "alice",
+        &[],
+        ExecuteMsg::TransferNft {
+            recipient: "bob".into(),
+            token_id: "t2".into(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        bank_sends(&res),
+        vec![
+            ("artist".into(), "u".into(), 250),
+            ("alice".into(), "u".into(), 4700)
+        ]
+    );
+    let o: OwnerOfResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::OwnerOf {
+            token_id: "t2".into(),
+            include_expired: None,
+        },
+    );
+    assert_eq!(o.owner, "bob");
+}
+
+#[test]
+fn royalty_and_fee_never_exceed_the_price() {
+    let (mut deps, contract) = setup_market();
+    let royalty = |share| {
+        Some(Royalty {
+            recipient: "artist".into(),
+            share,
+            on_rentals: false,
+        })
+    };
+    let mint_msg = |token_id: &str, share| ExecuteMsg::Mint {
+        token_id: token_id.into(),
+        owner: token_owner("alice".into()),
+        token_uri: None,
+        extension: None,
+        royalty: royalty(share),
+    };
+    // 1% platform fee leaves at most 99% for the royalty
+    let err = exec(&mut deps, &contract, 1, "alice", &[], mint_msg("t", 9901)).unwrap_err();
+    assert_eq!(err, ContractError::InvalidInput {});
+    exec(&mut deps, &contract, 1, "alice", &[], mint_msg("t", 9900)).unwrap();
+
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        MINTER,
+        &[],
+        ExecuteMsg::SetDefaultRoyalty {
+            royalty: royalty(500),
+        },
+    )
+    .unwrap();
+    let err = exec(
+        &mut deps,
+        &contract,
+        1,
+        MINTER,
+        &[],
+        ExecuteMsg::SetFeeValue { fee: 9600 },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidInput {});
+
+    // raising the fee later caps the token's royalty at what is left
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        MINTER,
+        &[],
+        ExecuteMsg::SetFeeValue { fee: 200 },
+    )
+    .unwrap();
+    let list_msg = ExecuteMsg::SetListForSell {
+        islisted: true,
+        token_id: "t".into(),
+        denom: "u".into(),
+        price: 10000,
+        auto_approve: true,
+    };
+    exec(&mut deps, &contract, 1, "alice", &[], list_msg).unwrap();
+    let bid_msg = ExecuteMsg::SetBidToBuy {
+        token_id: "t".into(),
+        expires: None,
+    };
+    let res = exec(&mut deps, &contract, 1, "bob", &coins(10000, "u"), bid_msg).unwrap();
+    assert_eq!(bank_sends(&res), vec![("artist".into(), "u".into(), 9800)]);
+    let balance: Uint128 = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::GetBalance { denom: "u".into() },
+    );
+    assert_eq!(balance.u128(), 200);
+}
+
+// Fractional ownership
+
+#[test]
+fn fractional_rental_income_credited_per_share() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForShortTermRental {
+            token_id: "t".into(),
+            denom: "u".into(),
+            price_per_day: 1000,
+            auto_approve: true,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::Fractionalize {
+            token_id: "t".into(),
+            shares: Uint128::new(100),
+        },
+    )
+    .unwrap();
+    assert!(exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::TransferNft {
+            recipient: "x".into(),
+            token_id: "t".into()
+        }
+    )
+    .is_err());
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::TransferShares {
+            token_id: "t".into(),
+            recipient: "bob".into(),
+            amount: Uint128::new(30),
+        },
+    )
+    .unwrap();
+    assert!(exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &[],
+        ExecuteMsg::TransferShares {
+            token_id: "t".into(),
+            recipient: "x".into(),
+            amount: Uint128::new(31)
+        }
+    )
+    .is_err());
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "carol",
+        &coins(2020, "u"),
+        ExecuteMsg::SetReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: vec!["86400".into(), "259200".into()],
+            guests: 1,
+            promo_code: None,
+        },
+    )
+    .unwrap();
+    let r = exec(
+        &mut deps,
+        &contract,
+        300000,
+        "alice",
+        &[],
+        ExecuteMsg::FinalizeShortTermRental {
+            token_id: "t".into(),
+            traveler: "carol".into(),
+            renting_period: vec!["86400".into(), "259200".into()],
+        },
+    )
+    .unwrap();
+    assert!(bank_sends(&r).is_empty());
+    let a: RewardsResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::Rewards {
+            holder: "alice".into(),
+            token_id: "t".into(),
+        },
+    );
+    let b: RewardsResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::Rewards {
+            holder: "bob".into(),
+            token_id: "t".into(),
+        },
+    );
+    assert_eq!(
+        a.rewards[0].amount.u128() + b.rewards[0].amount.u128(),
+        1980
+    );
+    assert_eq!(b.rewards[0].amount.u128(), 594);
+    // income earned before a transfer stays with the sender
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &[],
+        ExecuteMsg::TransferShares {
+            token_id: "t".into(),
+            recipient: "dan".into(),
+            amount: Uint128::new(10),
+        },
+    )
+    .unwrap();
+    let d: RewardsResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::Rewards {
+            holder: "dan".into(),
+            token_id: "t".into(),
+        },
+    );
+    assert!(d.rewards.is_empty());
+    let r = exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &[],
+        ExecuteMsg::ClaimRewards {
+            token_id: "t".into(),
+        },
+    )
+    .unwrap();
+    assert_eq!(bank_sends(&r), vec![("bob".into(), "u".into(), 594)]);
+    assert!(exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &[],
+        ExecuteMsg::ClaimRewards {
+            token_id: "t".into()
+        }
+    )
+    .is_err());
+    let h: ShareHoldersResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::ShareHolders {
+            token_id: "t".into(),
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert_eq!(h.holders.len(), 3);
+    assert!(exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::RedeemShares {
+            token_id: "t".into()
+        }
+    )
+    .is_err());
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &[],
+        ExecuteMsg::TransferShares {
+            token_id: "t".into(),
+            recipient: "alice".into(),
+            amount: Uint128::new(20),
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "dan",
+        &[],
+        ExecuteMsg::TransferShares {
+            token_id: "t".into(),
+            recipient: "alice".into(),
+            amount: Uint128::new(10),
+        },
+    )
+    .unwrap();
+    let r = exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::ClaimRewards {
+            token_id: "t".into(),
+        },
+    )
+    .unwrap();
+    assert_eq!(bank_sends(&r), vec![("alice".into(), "u".into(), 1386)]);
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::RedeemShares {
+            token_id: "t".into(),
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::TransferNft {
+            recipient: "x".into(),
+            token_id: "t".into(),
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn fractionalized_income_stays_with_share_holders() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForShortTermRental {
+            token_id: "t".into(),
+            denom: "u".into(),
+            price_per_day: 100,
+            auto_approve: true,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    // an 18-decimal denom
+    let deposit = 1_000_000_000_000_000_000_000u128;
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetDamageDepositForShortTerm {
+            token_id: "t".into(),
+            damage_deposit: deposit,
+            claim_window: 3 * DAY,
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::Fractionalize {
+            token_id: "t".into(),
+            shares: Uint128::new(1),
+        },
+    )
+    .unwrap();
+    let own_line = fee_line(
+        FeeKind::Service,
+        FeeCharge::Percent { bps: 5000 },
+        FeeRecipient::Address {
+            address: "alice".into(),
+        },
+        FeeRefund::NonRefundable,
+    );
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            1,
+            "alice",
+            &[],
+            ExecuteMsg::SetFeeLinesForShortTerm {
+                token_id: "t".into(),
+                fee_lines: vec![own_line],
+            },
+        )
+        .unwrap_err(),
+        ContractError::Fractionalized {}
+    );
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::TransferShares {
+            token_id: "t".into(),
+            recipient: "bob".into(),
+            amount: Uint128::new(1),
+        },
+    )
+    .unwrap();
+
+    let rp = period(10, 12);
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "carol",
+        &coins(202 + deposit, "u"),
+        ExecuteMsg::SetReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: rp.clone(),
+            guests: 1,
+            promo_code: None,
+        },
+    )
+    .unwrap();
+    let r = exec(
+        &mut deps,
+        &contract,
+        12 * DAY,
+        "alice",
+        &[],
+        ExecuteMsg::FinalizeShortTermRental {
+            token_id: "t".into(),
+            traveler: "carol".into(),
+            renting_period: rp.clone(),
+        },
+    )
+    .unwrap();
+    assert!(bank_sends(&r).is_empty());
+    exec(
+        &mut deps,
+        &contract,
+        12 * DAY,
+        "alice",
+        &[],
+        ExecuteMsg::ClaimDamageDeposit {
+            token_id: "t".into(),
+            renting_period: rp.clone(),
+            amount: deposit,
+            reason: "flood".into(),
+        },
+    )
+    .unwrap();
+    let r = exec(
+        &mut deps,
+        &contract,
+        16 * DAY,
+        "carol",
+        &[],
+        ExecuteMsg::ReleaseDamageDeposit {
+            token_id: "t".into(),
+            renting_period: rp,
+        },
+    )
+    .unwrap();
+    // the claim goes to the share holder rather than the token owner
+    assert!(bank_sends(&r).is_empty());
+    let b: RewardsResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::Rewards {
+            holder: "bob".into(),
+            token_id: "t".into(),
+        },
+    );
+    assert_eq!(b.rewards[0].amount.u128(), deposit + 198);
+}
+
+// Escrow sales
+
+/// Escrow offer of 10000 with a 1000 deposit
+fn escrow_offer() -> ExecuteMsg<Extension, Empty> {
+    ExecuteMsg::OfferEscrowSale {
+        token_id: "t".into(),
+        price: 10000,
+        inspection_period: 100,
+        closing_period: 100,
+        penalty: 5000,
+    }
+}
+
+#[test]
+fn escrow_sale_closes_after_inspection() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            10,
+            "bob",
+            &coins(1000, "u"),
+            ExecuteMsg::OfferEscrowSale {
+                token_id: "t".into(),
+                price: 10000,
+                inspection_period: u64::MAX,
+                closing_period: 100,
+                penalty: 5000,
+            },
+        )
+        .unwrap_err(),
+        ContractError::InvalidInput {}
+    );
+    exec(
+        &mut deps,
+        &contract,
+        10,
+        "bob",
+        &coins(1000, "u"),
+        escrow_offer(),
+    )
+    .unwrap();
+    assert!(exec(
+        &mut deps,
+        &contract,
+        10,
+        "carol",
+        &coins(1000, "u"),
+        escrow_offer()
+    )
+    .is_err());
+    exec(
+        &mut deps,
+        &contract,
+        10,
+        "alice",
+        &[],
+        ExecuteMsg::AcceptEscrowSale {
+            token_id: "t".into(),
+        },
+    )
+    .unwrap();
+    assert!(exec(
+        &mut deps,
+        &contract,
+        10,
+        "alice",
+        &[],
+        ExecuteMsg::TransferNft {
+            recipient: "x".into(),
+            token_id: "t".into()
+        }
+    )
+    .is_err());
+    assert!(exec(
+        &mut deps,
+        &contract,
+        20,
+        "alice",
+        &[],
+        ExecuteMsg::CancelEscrowSale {
+            token_id: "t".into()
+        }
+    )
+    .is_err());
+    let e: EscrowSaleResponse = query(
+        &deps,
+        &contract,
+        150,
+        QueryMsg::NftInfoEscrow {
+            token_id: "t".into(),
+        },
+    );
+    assert_eq!(
+        (
+            e.balance_due.u128(),
+            e.cancellation_penalty.u128(),
+            e.inspection_end,
+            e.closing_deadline
+        ),
+        (9000, 500, Some(110), Some(210))
+    );
+    assert!(exec(
+        &mut deps,
+        &contract,
+        150,
+        "bob",
+        &coins(8999, "u"),
+        ExecuteMsg::CloseEscrowSale {
+            token_id: "t".into()
+        }
+    )
+    .is_err());
+    let r = exec(
+        &mut deps,
+        &contract,
+        150,
+        "bob",
+        &coins(9500, "u"),
+        ExecuteMsg::CloseEscrowSale {
+            token_id: "t".into(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        bank_sends(&r),
+        vec![
+            ("alice".into(), "u".into(), 9900),
+            ("bob".into(), "u".into(), 500)
+        ]
+    );
+    let o: OwnerOfResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::OwnerOf {
+            token_id: "t".into(),
+            include_expired: None,
+        },
+    );
+    assert_eq!(o.owner, "bob");
+}
+
+#[test]
+fn escrow_cancel_penalty_after_inspection() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        10,
+        "bob",
+        &coins(1000, "u"),
+        escrow_offer(),
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        10,
+        "alice",
+        &[],
+        ExecuteMsg::AcceptEscrowSale {
+            token_id: "t".into(),
+        },
+    )
+    .unwrap();
+    let r = exec(
+        &mut deps,
+        &contract,
+        50,
+        "bob",
+        &[],
+        ExecuteMsg::CancelEscrowSale {
+            token_id: "t".into(),
+        },
+    )
+    .unwrap();
+    assert_eq!(bank_sends(&r), vec![("bob".into(), "u".into(), 1000)]);
+    exec(
+        &mut deps,
+        &contract,
+        60,
+        "bob",
+        &coins(1000, "u"),
+        escrow_offer(),
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        60,
+        "alice",
+        &[],
+        ExecuteMsg::AcceptEscrowSale {
+            token_id: "t".into(),
+        },
+    )
+    .unwrap();
+    let r = exec(
+        &mut deps,
+        &contract,
+        300,
+        "alice",
+        &[],
+        ExecuteMsg::CancelEscrowSale {
+            token_id: "t".into(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        bank_sends(&r),
+        vec![
+            ("alice".into(), "u".into(), 500),
+            ("bob".into(), "u".into(), 500)
+        ]
+    );
+}
+
+#[test]
+fn escrow_offer_requires_minimum_deposit() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    let err = exec(
+        &mut deps,
+        &contract,
+        10,
+        "bob",
+        &coins(1, "u"),
+        escrow_offer(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InsufficientDeposit {});
+
+    // a low offered price does not lower the deposit below a share of the listed price
+    exec(
+        &mut deps,
+        &contract,
+        10,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForSell {
+            islisted: true,
+            token_id: "t".into(),
+            denom: "u".into(),
+            price: 50000,
+            auto_approve: false,
+        },
+    )
+    .unwrap();
+    let err = exec(
+        &mut deps,
+        &contract,
+        10,
+        "bob",
+        &coins(1000, "u"),
+        escrow_offer(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InsufficientDeposit {});
+}
+
+#[test]
+fn pending_escrow_refunded_on_burn_and_sale() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        10,
+        "bob",
+        &coins(1000, "u"),
+        escrow_offer(),
+    )
+    .unwrap();
+    // a pending offer is declined by the burn
+    let r = exec(
+        &mut deps,
+        &contract,
+        20,
+        "alice",
+        &[],
+        ExecuteMsg::Burn {
+            token_id: "t".into(),
+        },
+    )
+    .unwrap();
+    assert_eq!(bank_sends(&r), vec![("bob".into(), "u".into(), 1000)]);
+
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        30,
+        "bob",
+        &coins(1000, "u"),
+        escrow_offer(),
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        30,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForSell {
+            islisted: true,
+            token_id: "t".into(),
+            denom: "u".into(),
+            price: 5000,
+            auto_approve: true,
+        },
+    )
+    .unwrap();
+    // and by a sale to someone else
+    let r = exec(
+        &mut deps,
+        &contract,
+        40,
+        "carol",
+        &coins(5000, "u"),
+        ExecuteMsg::SetBidToBuy {
+            token_id: "t".into(),
+            expires: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        bank_sends(&r),
+        vec![
+            ("bob".into(), "u".into(), 1000),
+            ("alice".into(), "u".into(), 4950)
+        ]
+    );
+    let e: EscrowSaleResponse = query(
+        &deps,
+        &contract,
+        40,
+        QueryMsg::NftInfoEscrow {
+            token_id: "t".into(),
+        },
+    );
+    assert!(e.escrow.is_none());
+
+    // an accepted sale still blocks both
+    exec(
+        &mut deps,
+        &contract,
+        50,
+        "bob",
+        &coins(1000, "u"),
+        escrow_offer(),
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        50,
+        "carol",
+        &[],
+        ExecuteMsg::AcceptEscrowSale {
+            token_id: "t".into(),
+        },
+    )
+    .unwrap();
+    assert!(exec(
+        &mut deps,
+        &contract,
+        60,
+        "carol",
+        &[],
+        ExecuteMsg::Burn {
+            token_id: "t".into()
+        }
+    )
+    .is_err());
+}
+
+// Bids by bidder
+
+#[test]
+fn bids_by_bidder_follow_bid_lifecycle() {
+    let (mut deps, contract) = setup_market();
+    for id in ["a", "b", "c"] {
+        mint_token(&mut deps, &contract, id, "alice");
+        exec(
+            &mut deps,
+            &contract,
+            1,
+            "alice",
+            &[],
+            ExecuteMsg::SetListForSell {
+                islisted: true,
+                token_id: id.into(),
+                denom: "u".into(),
+                price: 100,
+                auto_approve: false,
+            },
+        )
+        .unwrap();
+        exec(
+            &mut deps,
+            &contract,
+            1,
+            "bob",
+            &coins(100, "u"),
+            ExecuteMsg::SetBidToBuy {
+                token_id: id.into(),
+                expires: None,
+            },
+        )
+        .unwrap();
+    }
+    let r: BidderBidsResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::BidsByBidder {
+            bidder: "bob".into(),
+            start_after: Some("a".into()),
+            limit: Some(5),
+        },
+    );
+    assert_eq!(
+        r.bids
+            .iter()
+            .map(|b| b.token_id.as_str())
+            .collect::<Vec<_>>(),
+        vec!["b", "c"]
+    );
+    assert_eq!(r.bids[0].islisted, Some(true));
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::RejectBid {
+            token_id: "a".into(),
+            bidder: "bob".into(),
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::TransferNft {
+            recipient: "bob".into(),
+            token_id: "b".into(),
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &[],
+        ExecuteMsg::SetBidToBuy {
+            token_id: "c".into(),
+            expires: None,
+        },
+    )
+    .unwrap();
+    let r: BidderBidsResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::BidsByBidder {
+            bidder: "bob".into(),
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert!(r.bids.is_empty());
+}
+
+#[test]
+fn index_bids_in_batches() {
+    let (mut deps, contract) = setup_market();
+    for id in ["a", "b", "c"] {
+        mint_token(&mut deps, &contract, id, "alice");
+        exec(
+            &mut deps,
+            &contract,
+            1,
+            "alice",
+            &[],
+            ExecuteMsg::SetListForSell {
+                islisted: true,
+                token_id: id.into(),
+                denom: "u".into(),
+                price: 100,
+                auto_approve: false,
+            },
+        )
+        .unwrap();
+        exec(
+            &mut deps,
+            &contract,
+            1,
+            "bob",
+            &coins(100, "u"),
+            ExecuteMsg::SetBidToBuy {
+                token_id: id.into(),
+                expires: None,
+            },
+        )
+        .unwrap();
+        // as if the bid was placed before the index existed
+        contract
+            .bids_by_bidder
+            .remove(&mut deps.storage, ("bob", id));
+    }
+    let msg = ExecuteMsg::IndexBids {
+        start_after: None,
+        limit: 2,
+    };
+    assert!(exec(&mut deps, &contract, 1, "bob", &[], msg.clone()).is_err());
+    let r = exec(&mut deps, &contract, 1, MINTER, &[], msg).unwrap();
+    assert!(r
+        .attributes
+        .iter()
+        .any(|a| a.key == "last_token_id" && a.value == "b"));
+    let bids: BidderBidsResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::BidsByBidder {
+            bidder: "bob".into(),
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert_eq!(bids.bids.len(), 2);
+    let r = exec(
+        &mut deps,
+        &contract,
+        1,
+        MINTER,
+        &[],
+        ExecuteMsg::IndexBids {
+            start_after: Some("b".into()),
+            limit: 2,
+        },
+    )
+    .unwrap();
+    assert!(r.attributes.iter().all(|a| a.key != "last_token_id"));
+    let bids: BidderBidsResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::BidsByBidder {
+            bidder: "bob".into(),
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert_eq!(bids.bids.len(), 3);
+}
+
+// Offers
+
+fn attribute(key: &str, value: &str) -> Trait {
+    Trait {
+        key: key.into(),
+        value: value.into(),
+    }
+}
+
+#[test]
+fn offers_on_tokens_and_traits() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::Mint {
+            token_id: "v".into(),
+            owner: Owner {
+                chain_type: "c".into(),
+                address: "alice".into(),
+            },
+            token_uri: None,
+            extension: Some(Metadata {
+                attributes: Some(vec![attribute("city", "dubai"), attribute("beds", "3")]),
+                ..Default::default()
+            }),
+            royalty: None,
+        },
+    )
+    .unwrap();
+    let r = exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(1000, "u"),
+        ExecuteMsg::MakeOffer {
+            token_id: Some("t".into()),
+            traits: vec![],
+            expires: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(r.attributes[2].value, "1");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "carol",
+        &coins(2000, "u"),
+        ExecuteMsg::MakeOffer {
+            token_id: None,
+            traits: vec![attribute("city", "dubai")],
+            expires: Some(Expiration::AtTime(Timestamp::from_seconds(100))),
+        },
+    )
+    .unwrap();
+    let o: OffersResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::Offers {
+            token_id: None,
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert_eq!(o.offers.len(), 1);
+    assert!(exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::AcceptOffer {
+            offer_id: 2,
+            token_id: "t".into()
+        }
+    )
+    .is_err());
+    assert!(exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::AcceptOffer {
+            offer_id: 1,
+            token_id: "v".into()
+        }
+    )
+    .is_err());
+    let r = exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::AcceptOffer {
+            offer_id: 2,
+            token_id: "v".into(),
+        },
+    )
+    .unwrap();
+    assert_eq!(bank_sends(&r), vec![("alice".into(), "u".into(), 1980)]);
+    let r = exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::AcceptOffer {
+            offer_id: 1,
+            token_id: "t".into(),
+        },
+    )
+    .unwrap();
+    assert_eq!(bank_sends(&r), vec![("alice".into(), "u".into(), 990)]);
+    let ow: OwnerOfResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::OwnerOf {
+            token_id: "t".into(),
+            include_expired: None,
+        },
+    );
+    assert_eq!(ow.owner, "bob");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "dan",
+        &coins(5, "u"),
+        ExecuteMsg::MakeOffer {
+            token_id: None,
+            traits: vec![attribute("a", "b")],
+            expires: Some(Expiration::AtTime(Timestamp::from_seconds(100))),
+        },
+    )
+    .unwrap();
+    assert!(exec(
+        &mut deps,
+        &contract,
+        50,
+        "x",
+        &[],
+        ExecuteMsg::CancelOffer { offer_id: 3 }
+    )
+    .is_err());
+    let r = exec(
+        &mut deps,
+        &contract,
+        150,
+        "x",
+        &[],
+        ExecuteMsg::CancelOffer { offer_id: 3 },
+    )
+    .unwrap();
+    assert_eq!(bank_sends(&r), vec![("dan".into(), "u".into(), 5)]);
+}
+
+// Availability calendar
+
+const DAY: u64 = 86400;
+
+#[test]
+fn civil_dates_and_recurring_closures() {
+    // 2024-02-29 is a Thursday
+    let t = 1709164800;
+    assert_eq!(civil_date(t), (2024, 2, 29));
+    assert_eq!(weekday(t), 3);
+    assert_eq!(civil_date(0), (1970, 1, 1));
+    let c = RecurringClosure::Yearly {
+        from_month: 12,
+        from_day: 20,
+        to_month: 1,
+        to_day: 5,
+    };
+    assert!(c.is_closed(1704067200)); // 2024-01-01
+    assert!(!c.is_closed(t));
+}
+
+/// Short-term reservation of "t" paid with more than enough funds
+fn book_short(
+    deps: &mut MockDeps,
+    contract: &Contract,
+    traveler: &str,
+    check_in: u64,
+    check_out: u64,
+) -> Result<Response, ContractError> {
+    exec(
+        deps,
+        contract,
+        1,
+        traveler,
+        &coins(100000, "u"),
+        ExecuteMsg::SetReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: vec![check_in.to_string(), check_out.to_string()],
+            guests: 1,
+            promo_code: None,
+        },
+    )
+}
+
+#[test]
+fn availability_calendar_limits_bookings() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    assert!(exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForShortTermRental {
+            token_id: "t".into(),
+            denom: "u".into(),
+            price_per_day: 10,
+            auto_approve: true,
+            available_period: vec![(10 * DAY).to_string()],
+            minimum_stay: 1,
+            cancellation: vec![]
+        }
+    )
+    .is_err());
+    // free-form text is not read as a property open at all times
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            1,
+            "alice",
+            &[],
+            ExecuteMsg::SetListForShortTermRental {
+                token_id: "t".into(),
+                denom: "u".into(),
+                price_per_day: 10,
+                auto_approve: true,
+                available_period: vec!["all year".into()],
+                minimum_stay: 1,
+                cancellation: vec![],
+            },
+        )
+        .unwrap_err(),
+        ContractError::InvalidInput {}
+    );
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForShortTermRental {
+            token_id: "t".into(),
+            denom: "u".into(),
+            price_per_day: 10,
+            auto_approve: true,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    let a: AvailabilityResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::Availability {
+            token_id: "t".into(),
+            from: 8 * DAY,
+            to: 23 * DAY,
+        },
+    );
+    assert_eq!(
+        a.free
+            .iter()
+            .map(|r| (r.start / DAY, r.end / DAY))
+            .collect::<Vec<_>>(),
+        vec![(8, 23)]
+    );
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForShortTermRental {
+            token_id: "t".into(),
+            denom: "u".into(),
+            price_per_day: 10,
+            auto_approve: true,
+            available_period: vec![(10 * DAY).to_string(), (40 * DAY).to_string()],
+            minimum_stay: 1,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        book_short(&mut deps, &contract, "bob", 5 * DAY, 12 * DAY).unwrap_err(),
+        ContractError::NotAvailable {}
+    );
+    book_short(&mut deps, &contract, "bob", 10 * DAY, 12 * DAY).unwrap();
+    // day 14 = 1970-01-15 Thursday
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetAvailabilityForShortTerm {
+            token_id: "t".into(),
+            availability: AvailabilityCalendar {
+                open: vec![DateRange {
+                    start: 10 * DAY,
+                    end: 40 * DAY,
+                }],
+                blocked: vec![DateRange {
+                    start: 20 * DAY,
+                    end: 22 * DAY,
+                }],
+                closures: vec![RecurringClosure::Weekly { weekdays: vec![3] }],
+            },
+        },
+    )
+    .unwrap();
+    assert!(book_short(&mut deps, &contract, "carol", 13 * DAY, 15 * DAY).is_err());
+    assert!(book_short(&mut deps, &contract, "carol", 19 * DAY, 21 * DAY).is_err());
+    book_short(&mut deps, &contract, "carol", 15 * DAY, 17 * DAY).unwrap();
+    let a: AvailabilityResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::Availability {
+            token_id: "t".into(),
+            from: 8 * DAY,
+            to: 23 * DAY,
+        },
+    );
+    let f: Vec<(u64, u64)> = a
+        .free
+        .iter()
+        .map(|r| (r.start / DAY, r.end / DAY))
+        .collect();
+    assert_eq!(f, vec![(12, 14), (17, 20), (22, 23)]);
+    assert_eq!(a.blocked.len(), 3);
+}
+
+// Nightly price rules
+
+#[test]
+fn nightly_price_rules_apply_by_priority() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForShortTermRental {
+            token_id: "t".into(),
+            denom: "u".into(),
+            price_per_day: 100,
+            auto_approve: true,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    let rules = vec![
+        PriceRule {
+            priority: 1,
+            condition: PriceCondition::Weekdays {
+                weekdays: vec![4, 5],
+            },
+            adjustment: PriceAdjustment::Percent { bps: 15000 },
+        },
+        PriceRule {
+            priority: 5,
+            condition: PriceCondition::Dates {
+                range: DateRange {
+                    start: 10 * DAY,
+                    end: 11 * DAY,
+                },
+            },
+            adjustment: PriceAdjustment::Fixed { price: 500 },
+        },
+        PriceRule {
+            priority: 0,
+            condition: PriceCondition::EarlyBird { days: 5 },
+            adjustment: PriceAdjustment::Percent { bps: 9000 },
+        },
+    ];
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetPriceRulesForShortTerm {
+            token_id: "t".into(),
+            price_rules: rules,
+        },
+    )
+    .unwrap();
+    // day 8 Fri(4) day 9 Sat(5) day 10 Sun -> fixed, day 11 Mon -> early bird, +half day
+    let q1: ShortTermQuote = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::QuoteShortTerm {
+            token_id: "t".into(),
+            renting_period: vec![(8 * DAY).to_string(), (12 * DAY + DAY / 2).to_string()],
+            guests: 1,
+            promo_code: None,
+        },
+    );
+    let p: Vec<u128> = q1.nights.iter().map(|n| n.price.u128()).collect();
+    assert_eq!(p, vec![150, 150, 500, 90, 45]);
+    assert_eq!(q1.rent_amount.u128(), 935);
+    assert_eq!(q1.total.u128(), 944);
+    assert!(exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(943, "u"),
+        ExecuteMsg::SetReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: vec![(8 * DAY).to_string(), (12 * DAY + DAY / 2).to_string()],
+            guests: 1,
+            promo_code: None,
+        }
+    )
+    .is_err());
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(944, "u"),
+        ExecuteMsg::SetReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: vec![(8 * DAY).to_string(), (12 * DAY + DAY / 2).to_string()],
+            guests: 1,
+            promo_code: None,
+        },
+    )
+    .unwrap();
+    let r: RentalsResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::NftRentals {
+            token_id: "t".into(),
+        },
+    );
+    assert_eq!(r.rentals[0].deposit_amount.u128(), 935);
+}
+
+#[test]
+fn lead_time_price_rules_saturate() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForShortTermRental {
+            token_id: "t".into(),
+            denom: "u".into(),
+            price_per_day: 100,
+            auto_approve: true,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    let rules = vec![
+        PriceRule {
+            priority: 1,
+            condition: PriceCondition::LastMinute { days: u64::MAX },
+            adjustment: PriceAdjustment::Percent { bps: 8000 },
+        },
+        PriceRule {
+            priority: 0,
+            condition: PriceCondition::EarlyBird { days: u64::MAX },
+            adjustment: PriceAdjustment::Fixed { price: 1 },
+        },
+    ];
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetPriceRulesForShortTerm {
+            token_id: "t".into(),
+            price_rules: rules,
+        },
+    )
+    .unwrap();
+    let q1: ShortTermQuote = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::QuoteShortTerm {
+            token_id: "t".into(),
+            renting_period: vec![(8 * DAY).to_string(), (10 * DAY).to_string()],
+            guests: 1,
+            promo_code: None,
+        },
+    );
+    assert_eq!(q1.rent_amount.u128(), 160);
+}
+
+// Guest capacity
+
+#[test]
+fn guest_capacity_and_extra_guest_fees() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForShortTermRental {
+            token_id: "t".into(),
+            denom: "u".into(),
+            price_per_day: 100,
+            auto_approve: true,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetGuestPolicyForShortTerm {
+            token_id: "t".into(),
+            max_guests: Some(4),
+            included_guests: 2,
+            extra_guest_fee: 10,
+        },
+    )
+    .unwrap();
+    let rp = vec![(10 * DAY).to_string(), (12 * DAY).to_string()];
+    let qt: ShortTermQuote = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::QuoteShortTerm {
+            token_id: "t".into(),
+            renting_period: rp.clone(),
+            guests: 4,
+            promo_code: None,
+        },
+    );
+    assert_eq!(qt.rent_amount.u128(), 240);
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            1,
+            "bob",
+            &coins(1000, "u"),
+            ExecuteMsg::SetReservationForShortTerm {
+                token_id: "t".into(),
+                renting_period: rp.clone(),
+                guests: 5,
+                promo_code: None,
+            }
+        )
+        .unwrap_err(),
+        ContractError::TooManyGuests {}
+    );
+    assert!(exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(241, "u"),
+        ExecuteMsg::SetReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: rp.clone(),
+            guests: 4,
+            promo_code: None,
+        }
+    )
+    .is_err());
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(242, "u"),
+        ExecuteMsg::SetReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: rp,
+            guests: 4,
+            promo_code: None,
+        },
+    )
+    .unwrap();
+
+    mint_token(&mut deps, &contract, "l", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForLongTermRental {
+            token_id: "l".into(),
+            denom: "u".into(),
+            price_per_month: 1000,
+            auto_approve: true,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            1,
+            "alice",
+            &[],
+            ExecuteMsg::SetGuestPolicyForLongTerm {
+                token_id: "l".into(),
+                max_guests: Some(2),
+                included_guests: 3,
+                extra_guest_fee: 100,
+            },
+        )
+        .unwrap_err(),
+        ContractError::InvalidInput {}
+    );
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetGuestPolicyForLongTerm {
+            token_id: "l".into(),
+            max_guests: None,
+            included_guests: 1,
+            extra_guest_fee: 100,
+        },
+    )
+    .unwrap();
+    let rp = vec![(10 * DAY).to_string(), (100 * DAY).to_string()];
+    assert!(exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(1199, "u"),
+        ExecuteMsg::SetReservationForLongTerm {
+            token_id: "l".into(),
+            renting_period: rp.clone(),
+            guests: 3
+        }
+    )
+    .is_err());
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(1500, "u"),
+        ExecuteMsg::SetReservationForLongTerm {
+            token_id: "l".into(),
+            renting_period: rp.clone(),
+            guests: 3,
+        },
+    )
+    .unwrap();
+    // the guest surcharge stays in the first-month reserve
+    let withdraw = |amount| ExecuteMsg::WithdrawToLandlord {
+        token_id: "l".into(),
+        tenant: "bob".into(),
+        renting_period: rp.clone(),
+        amount,
+        address: "alice".into(),
+    };
+    assert_eq!(
+        exec(&mut deps, &contract, 11 * DAY, "alice", &[], withdraw(301)).unwrap_err(),
+        ContractError::UnavailableAmount {}
+    );
+    exec(&mut deps, &contract, 11 * DAY, "alice", &[], withdraw(300)).unwrap();
+}
+
+// Damage deposits
+
+#[test]
+fn damage_deposit_claim_dispute_and_release() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForShortTermRental {
+            token_id: "t".into(),
+            denom: "u".into(),
+            price_per_day: 100,
+            auto_approve: true,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetDamageDepositForShortTerm {
+            token_id: "t".into(),
+            damage_deposit: 500,
+            claim_window: 3 * DAY,
+        },
+    )
+    .unwrap();
+    let rp = vec![(10 * DAY).to_string(), (12 * DAY).to_string()];
+    let qt: ShortTermQuote = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::QuoteShortTerm {
+            token_id: "t".into(),
+            renting_period: rp.clone(),
+            guests: 1,
+            promo_code: None,
+        },
+    );
+    assert_eq!(qt.total.u128(), 702);
+    assert!(exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(701, "u"),
+        ExecuteMsg::SetReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: rp.clone(),
+            guests: 1,
+            promo_code: None,
+        }
+    )
+    .is_err());
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(702, "u"),
+        ExecuteMsg::SetReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: rp.clone(),
+            guests: 1,
+            promo_code: None,
+        },
+    )
+    .unwrap();
+    // too early
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            11 * DAY,
+            "alice",
+            &[],
+            ExecuteMsg::ClaimDamageDeposit {
+                token_id: "t".into(),
+                renting_period: rp.clone(),
+                amount: 100,
+                reason: "x".into()
+            }
+        )
+        .unwrap_err(),
+        ContractError::RentalActive {}
+    );
+    let r = exec(
+        &mut deps,
+        &contract,
+        12 * DAY,
+        "alice",
+        &[],
+        ExecuteMsg::FinalizeShortTermRental {
+            token_id: "t".into(),
+            traveler: "bob".into(),
+            renting_period: rp.clone(),
+        },
+    )
+    .unwrap();
+    assert_eq!(bank_sends(&r), vec![("alice".into(), "u".into(), 198)]);
+    exec(
+        &mut deps,
+        &contract,
+        12 * DAY,
+        "alice",
+        &[],
+        ExecuteMsg::ClaimDamageDeposit {
+            token_id: "t".into(),
+            renting_period: rp.clone(),
+            amount: 200,
+            reason: "broken lamp".into(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            13 * DAY,
+            "carol",
+            &[],
+            ExecuteMsg::ReleaseDamageDeposit {
+                token_id: "t".into(),
+                renting_period: rp.clone()
+            }
+        )
+        .unwrap_err(),
+        ContractError::ClaimWindowOpen {}
+    );
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            13 * DAY,
+            "carol",
+            &[],
+            ExecuteMsg::DisputeDamageClaim {
+                token_id: "t".into(),
+                renting_period: rp.clone()
+            }
+        )
+        .unwrap_err(),
+        ContractError::NotTraveler {}
+    );
+    exec(
+        &mut deps,
+        &contract,
+        13 * DAY,
+        "bob",
+        &[],
+        ExecuteMsg::DisputeDamageClaim {
+            token_id: "t".into(),
+            renting_period: rp.clone(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            20 * DAY,
+            "carol",
+            &[],
+            ExecuteMsg::ReleaseDamageDeposit {
+                token_id: "t".into(),
+                renting_period: rp.clone()
+            }
+        )
+        .unwrap_err(),
+        ContractError::DamageDisputed {}
+    );
+    let d: DamageDepositsResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::DamageDeposits {
+            token_id: "t".into(),
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert!(d.deposits[0].disputed);
+    assert!(exec(
+        &mut deps,
+        &contract,
+        20 * DAY,
+        "alice",
+        &[],
+        ExecuteMsg::ResolveDamageDispute {
+            token_id: "t".into(),
+            renting_period: rp.clone(),
+            amount: 50
+        }
+    )
+    .is_err());
+    let r = exec(
+        &mut deps,
+        &contract,
+        20 * DAY,
+        MINTER,
+        &[],
+        ExecuteMsg::ResolveDamageDispute {
+            token_id: "t".into(),
+            renting_period: rp.clone(),
+            amount: 50,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        bank_sends(&r),
+        vec![
+            ("alice".into(), "u".into(), 50),
+            ("bob".into(), "u".into(), 450)
+        ]
+    );
+
+    // undisputed, released by anyone after the window; rejected stays refund the deposit
+    let rp2 = vec![(30 * DAY).to_string(), (31 * DAY).to_string()];
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(601, "u"),
+        ExecuteMsg::SetReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: rp2.clone(),
+            guests: 1,
+            promo_code: None,
+        },
+    )
+    .unwrap();
+    let r = exec(
+        &mut deps,
+        &contract,
+        34 * DAY,
+        "carol",
+        &[],
+        ExecuteMsg::ReleaseDamageDeposit {
+            token_id: "t".into(),
+            renting_period: rp2.clone(),
+        },
+    )
+    .unwrap();
+    assert_eq!(bank_sends(&r), vec![("bob".into(), "u".into(), 500)]);
+    let rp3 = vec![(40 * DAY).to_string(), (41 * DAY).to_string()];
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(601, "u"),
+        ExecuteMsg::SetReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: rp3.clone(),
+            guests: 1,
+            promo_code: None,
+        },
+    )
+    .unwrap();
+    let r = exec(
+        &mut deps,
+        &contract,
+        2,
+        "alice",
+        &[],
+        ExecuteMsg::RejectReservationForShortterm {
+            token_id: "t".into(),
+            traveler: "bob".into(),
+            renting_period: rp3,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        bank_sends(&r),
+        vec![
+            ("bob".into(), "u".into(), 100),
+            ("bob".into(), "u".into(), 500)
+        ]
+    );
+}
+
+#[test]
+fn damage_deposit_claim_window_saturates() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForShortTermRental {
+            token_id: "t".into(),
+            denom: "u".into(),
+            price_per_day: 100,
+            auto_approve: true,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetDamageDepositForShortTerm {
+            token_id: "t".into(),
+            damage_deposit: 500,
+            claim_window: u64::MAX,
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(702, "u"),
+        ExecuteMsg::SetReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: period(10, 12),
+            guests: 1,
+            promo_code: None,
+        },
+    )
+    .unwrap();
+    let d: DamageDepositsResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::DamageDeposits {
+            token_id: "t".into(),
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert_eq!(d.deposits[0].claim_deadline, u64::MAX);
+    assert_eq!(d.deposits[0].dispute_deadline(), None);
+}
+
+#[test]
+fn late_damage_claims_stay_disputable() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForShortTermRental {
+            token_id: "t".into(),
+            denom: "u".into(),
+            price_per_day: 100,
+            auto_approve: true,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetDamageDepositForShortTerm {
+            token_id: "t".into(),
+            damage_deposit: 500,
+            claim_window: 3 * DAY,
+        },
+    )
+    .unwrap();
+    let rp = vec![(10 * DAY).to_string(), (12 * DAY).to_string()];
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(702, "u"),
+        ExecuteMsg::SetReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: rp.clone(),
+            guests: 1,
+            promo_code: None,
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        12 * DAY,
+        "alice",
+        &[],
+        ExecuteMsg::FinalizeShortTermRental {
+            token_id: "t".into(),
+            traveler: "bob".into(),
+            renting_period: rp.clone(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            13 * DAY,
+            "alice",
+            &[],
+            ExecuteMsg::Burn {
+                token_id: "t".into()
+            }
+        )
+        .unwrap_err(),
+        ContractError::DamageDepositHeld {}
+    );
+
+    // claimed just before the claim window closes
+    exec(
+        &mut deps,
+        &contract,
+        15 * DAY - 1,
+        "alice",
+        &[],
+        ExecuteMsg::ClaimDamageDeposit {
+            token_id: "t".into(),
+            renting_period: rp.clone(),
+            amount: 200,
+            reason: "broken lamp".into(),
+        },
+    )
+    .unwrap();
+    // not even the host can release it while the claim is open to dispute
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            15 * DAY,
+            "alice",
+            &[],
+            ExecuteMsg::ReleaseDamageDeposit {
+                token_id: "t".into(),
+                renting_period: rp.clone()
+            }
+        )
+        .unwrap_err(),
+        ContractError::ClaimWindowOpen {}
+    );
+    exec(
+        &mut deps,
+        &contract,
+        17 * DAY,
+        "bob",
+        &[],
+        ExecuteMsg::DisputeDamageClaim {
+            token_id: "t".into(),
+            renting_period: rp.clone(),
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        20 * DAY,
+        MINTER,
+        &[],
+        ExecuteMsg::ResolveDamageDispute {
+            token_id: "t".into(),
+            renting_period: rp.clone(),
+            amount: 200,
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        20 * DAY,
+        "alice",
+        &[],
+        ExecuteMsg::Burn {
+            token_id: "t".into(),
+        },
+    )
+    .unwrap();
+}
+
+// Group reservations
+
+fn share(payer: &str, amount: u128) -> PayerShare {
+    PayerShare {
+        payer: payer.into(),
+        amount: Uint128::new(amount),
+        paid: false,
+    }
+}
+
+#[test]
+fn group_reservation_funding_and_refund() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForShortTermRental {
+            token_id: "t".into(),
+            denom: "u".into(),
+            price_per_day: 100,
+            auto_approve: false,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    let rp = vec![(10 * DAY).to_string(), (12 * DAY).to_string()];
+    // total 202
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            1,
+            "bob",
+            &[],
+            ExecuteMsg::CreateGroupReservationForShortTerm {
+                token_id: "t".into(),
+                renting_period: rp.clone(),
+                guests: 2,
+                shares: vec![share("bob", 101), share("carl", 100)],
+                deadline: 5 * DAY
+            }
+        )
+        .unwrap_err(),
+        ContractError::InsufficientDeposit {}
+    );
+    let r = exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(101, "u"),
+        ExecuteMsg::CreateGroupReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: rp.clone(),
+            guests: 2,
+            shares: vec![share("bob", 101), share("carl", 101)],
+            deadline: 5 * DAY,
+        },
+    )
+    .unwrap();
+    assert!(r
+        .attributes
+        .iter()
+        .any(|a| a.key == "booked" && a.value == "false"));
+    let g: GroupReservation = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::GroupReservation { group_id: 1 },
+    );
+    assert!(g.shares[0].paid && !g.shares[1].paid);
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            2,
+            "dan",
+            &coins(101, "u"),
+            ExecuteMsg::FundGroupReservation { group_id: 1 }
+        )
+        .unwrap_err(),
+        ContractError::NotPayer {}
+    );
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            2,
+            "carl",
+            &coins(100, "u"),
+            ExecuteMsg::FundGroupReservation { group_id: 1 }
+        )
+        .unwrap_err(),
+        ContractError::InsufficientDeposit {}
+    );
+    let r = exec(
+        &mut deps,
+        &contract,
+        2,
+        "carl",
+        &coins(101, "u"),
+        ExecuteMsg::FundGroupReservation { group_id: 1 },
+    )
+    .unwrap();
+    assert!(r
+        .attributes
+        .iter()
+        .any(|a| a.key == "booked" && a.value == "true"));
+    // booked for bob, host can approve
+    exec(
+        &mut deps,
+        &contract,
+        3,
+        "alice",
+        &[],
+        ExecuteMsg::SetApproveForShortTerm {
+            token_id: "t".into(),
+            traveler: "bob".into(),
+            renting_period: rp.clone(),
+            access_instructions: None,
+        },
+    )
+    .unwrap();
+
+    // expiry refund
+    let rp2 = vec![(20 * DAY).to_string(), (21 * DAY).to_string()];
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(60, "u"),
+        ExecuteMsg::CreateGroupReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: rp2.clone(),
+            guests: 2,
+            shares: vec![share("bob", 60), share("carl", 60)],
+            deadline: 5 * DAY,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            2,
+            "zed",
+            &[],
+            ExecuteMsg::RefundGroupReservation { group_id: 2 }
+        )
+        .unwrap_err(),
+        ContractError::FundingOpen {}
+    );
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            5 * DAY,
+            "carl",
+            &coins(60, "u"),
+            ExecuteMsg::FundGroupReservation { group_id: 2 }
+        )
+        .unwrap_err(),
+        ContractError::DeadlinePassed {}
+    );
+    let expired = |deps: &MockDeps, time| {
+        let e: ExpiredGroupReservationsResponse = query(
+            deps,
+            &contract,
+            time,
+            QueryMsg::ExpiredGroupReservations {
+                start_after: None,
+                limit: None,
+            },
+        );
+        e.groups.iter().map(|group| group.id).collect::<Vec<_>>()
+    };
+    assert!(expired(&deps, 2).is_empty());
+    assert_eq!(expired(&deps, 5 * DAY), vec![2]);
+    let r = exec(
+        &mut deps,
+        &contract,
+        5 * DAY,
+        "zed",
+        &[],
+        ExecuteMsg::RefundGroupReservation { group_id: 2 },
+    )
+    .unwrap();
+    assert_eq!(bank_sends(&r), vec![("bob".into(), "u".into(), 60)]);
+    assert!(expired(&deps, 5 * DAY).is_empty());
+}
+
+#[test]
+fn group_reservation_refunds_split_among_payers() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForShortTermRental {
+            token_id: "t".into(),
+            denom: "u".into(),
+            price_per_day: 100,
+            auto_approve: false,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    let rp = vec![(10 * DAY).to_string(), (13 * DAY).to_string()];
+    // 303 paid one to two
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(101, "u"),
+        ExecuteMsg::CreateGroupReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: rp.clone(),
+            guests: 2,
+            shares: vec![share("bob", 101), share("carl", 202)],
+            deadline: 5 * DAY,
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        2,
+        "carl",
+        &coins(202, "u"),
+        ExecuteMsg::FundGroupReservation { group_id: 1 },
+    )
+    .unwrap();
+    let rentals: RentalsResponse = query(
+        &deps,
+        &contract,
+        2,
+        QueryMsg::NftRentals {
+            token_id: "t".into(),
+        },
+    );
+    assert_eq!(rentals.rentals[0].payers.len(), 2);
+    let r = exec(
+        &mut deps,
+        &contract,
+        3,
+        "alice",
+        &[],
+        ExecuteMsg::RejectReservationForShortterm {
+            token_id: "t".into(),
+            traveler: "bob".into(),
+            renting_period: rp,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        bank_sends(&r),
+        vec![
+            ("bob".into(), "u".into(), 100),
+            ("carl".into(), "u".into(), 200)
+        ]
+    );
+}
+
+#[test]
+fn group_reservation_refunded_when_dates_are_taken() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForShortTermRental {
+            token_id: "t".into(),
+            denom: "u".into(),
+            price_per_day: 100,
+            auto_approve: false,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    let rp = vec![(10 * DAY).to_string(), (12 * DAY).to_string()];
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(101, "u"),
+        ExecuteMsg::CreateGroupReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: rp.clone(),
+            guests: 2,
+            shares: vec![share("bob", 101), share("carl", 101)],
+            deadline: 5 * DAY,
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        2,
+        "dan",
+        &coins(202, "u"),
+        ExecuteMsg::SetReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: rp.clone(),
+            guests: 1,
+            promo_code: None,
+        },
+    )
+    .unwrap();
+
+    // the last payment goes through and every share is refunded
+    let r = exec(
+        &mut deps,
+        &contract,
+        3,
+        "carl",
+        &coins(101, "u"),
+        ExecuteMsg::FundGroupReservation { group_id: 1 },
+    )
+    .unwrap();
+    assert!(r
+        .attributes
+        .iter()
+        .any(|a| a.key == "booked" && a.value == "false"));
+    assert_eq!(
+        bank_sends(&r),
+        vec![
+            ("bob".into(), "u".into(), 101),
+            ("carl".into(), "u".into(), 101)
+        ]
+    );
+    let rentals: RentalsResponse = query(
+        &deps,
+        &contract,
+        3,
+        QueryMsg::NftRentals {
+            token_id: "t".into(),
+        },
+    );
+    assert_eq!(rentals.rentals.len(), 1);
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            6 * DAY,
+            "bob",
+            &[],
+            ExecuteMsg::RefundGroupReservation { group_id: 1 }
+        )
+        .unwrap_err(),
+        ContractError::NoGroupReservation {}
+    );
+}
+
+// Reservation changes
+
+/// Renting period from the start of one day to the start of another
+fn period(check_in: u64, check_out: u64) -> Vec<String> {
+    vec![(check_in * DAY).to_string(), (check_out * DAY).to_string()]
+}
+
+#[test]
+fn modify_reservation_moves_and_reprices() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForShortTermRental {
+            token_id: "t".into(),
+            denom: "u".into(),
+            price_per_day: 100,
+            auto_approve: false,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(202, "u"),
+        ExecuteMsg::SetReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: period(10, 12),
+            guests: 1,
+            promo_code: None,
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "carl",
+        &coins(101, "u"),
+        ExecuteMsg::SetReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: period(20, 21),
+            guests: 1,
+            promo_code: None,
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetApproveForShortTerm {
+            token_id: "t".into(),
+            traveler: "bob".into(),
+            renting_period: period(10, 12),
+            access_instructions: None,
+        },
+    )
+    .unwrap();
+    // conflict with carl
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            2,
+            "bob",
+            &coins(200, "u"),
+            ExecuteMsg::ModifyReservation {
+                token_id: "t".into(),
+                renting_period: period(10, 12),
+                new_renting_period: period(19, 21)
+            }
+        )
+        .unwrap_err(),
+        ContractError::UnavailablePeriod {}
+    );
+    // overlapping itself is fine; needs 1 more night => 101
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            2,
+            "bob",
+            &coins(100, "u"),
+            ExecuteMsg::ModifyReservation {
+                token_id: "t".into(),
+                renting_period: period(10, 12),
+                new_renting_period: period(11, 14)
+            }
+        )
+        .unwrap_err(),
+        ContractError::InsufficientDeposit {}
+    );
+    let r = exec(
+        &mut deps,
+        &contract,
+        2,
+        "bob",
+        &coins(101, "u"),
+        ExecuteMsg::ModifyReservation {
+            token_id: "t".into(),
+            renting_period: period(10, 12),
+            new_renting_period: period(11, 14),
+        },
+    )
+    .unwrap();
+    assert!(bank_sends(&r).is_empty());
+    let rs: RentalsResponse = query(
+        &deps,
+        &contract,
+        2,
+        QueryMsg::NftRentals {
+            token_id: "t".into(),
+        },
+    );
+    assert_eq!(rs.rentals[0].renting_period, vec![11 * DAY, 14 * DAY]);
+    assert_eq!(rs.rentals[0].deposit_amount.u128(), 300);
+    assert!(!rs.rentals[0].approved);
+    // shorten: refund 2 nights + fee 2
+    let r = exec(
+        &mut deps,
+        &contract,
+        3,
+        "bob",
+        &[],
+        ExecuteMsg::ModifyReservation {
+            token_id: "t".into(),
+            renting_period: period(11, 14),
+            new_renting_period: period(11, 12),
+        },
+    )
+    .unwrap();
+    assert_eq!(bank_sends(&r), vec![("bob".into(), "u".into(), 202)]);
+
+    // long-term
+    mint_token(&mut deps, &contract, "l", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForLongTermRental {
+            token_id: "l".into(),
+            denom: "u".into(),
+            price_per_month: 1000,
+            auto_approve: true,
+            available_period: vec![],
+            minimum_stay: 30,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(1000, "u"),
+        ExecuteMsg::SetReservationForLongTerm {
+            token_id: "l".into(),
+            renting_period: period(10, 100),
+            guests: 1,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            2,
+            "bob",
+            &[],
+            ExecuteMsg::ModifyReservation {
+                token_id: "l".into(),
+                renting_period: period(10, 100),
+                new_renting_period: period(10, 20)
+            }
+        )
+        .unwrap_err(),
+        ContractError::LessThanMinimum {}
+    );
+    exec(
+        &mut deps,
+        &contract,
+        2,
+        "bob",
+        &[],
+        ExecuteMsg::ModifyReservation {
+            token_id: "l".into(),
+            renting_period: period(10, 100),
+            new_renting_period: period(20, 120),
+        },
+    )
+    .unwrap();
+    let rs: RentalsResponse = query(
+        &deps,
+        &contract,
+        2,
+        QueryMsg::NftRentals {
+            token_id: "l".into(),
+        },
+    );
+    assert_eq!(rs.rentals[0].renting_period, vec![20 * DAY, 120 * DAY]);
+    assert!(rs.rentals[0].approved);
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            21 * DAY,
+            "bob",
+            &[],
+            ExecuteMsg::ModifyReservation {
+                token_id: "l".into(),
+                renting_period: period(20, 120),
+                new_renting_period: period(30, 120)
+            }
+        )
+        .unwrap_err(),
+        ContractError::RentalAlreadyStarted {}
+    );
+}
+
+#[test]
+fn modify_reservation_refunds_the_fee_charged_at_booking() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForShortTermRental {
+            token_id: "t".into(),
+            denom: "u".into(),
+            price_per_day: 100,
+            auto_approve: false,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(202, "u"),
+        ExecuteMsg::SetReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: period(10, 12),
+            guests: 1,
+            promo_code: None,
+        },
+    )
+    .unwrap();
+    let rs: RentalsResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::NftRentals {
+            token_id: "t".into(),
+        },
+    );
+    assert_eq!(rs.rentals[0].platform_fee, Some(Uint128::new(2)));
+    // the fee rises to 5% after booking, one night now costs 105 of the 202 paid
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        MINTER,
+        &[],
+        ExecuteMsg::SetFeeValue { fee: 500 },
+    )
+    .unwrap();
+    let r = exec(
+        &mut deps,
+        &contract,
+        2,
+        "bob",
+        &[],
+        ExecuteMsg::ModifyReservation {
+            token_id: "t".into(),
+            renting_period: period(10, 12),
+            new_renting_period: period(10, 11),
+        },
+    )
+    .unwrap();
+    assert_eq!(bank_sends(&r), vec![("bob".into(), "u".into(), 97)]);
+    // the held fee only reaches the platform once the stay can't be refunded anymore
+    let balance: Uint128 = query(
+        &deps,
+        &contract,
+        2,
+        QueryMsg::GetBalance { denom: "u".into() },
+    );
+    assert!(balance.is_zero());
+    exec(
+        &mut deps,
+        &contract,
+        3,
+        "alice",
+        &[],
+        ExecuteMsg::RejectReservationForShortterm {
+            token_id: "t".into(),
+            traveler: "bob".into(),
+            renting_period: period(10, 11),
+        },
+    )
+    .unwrap();
+    let balance: Uint128 = query(
+        &deps,
+        &contract,
+        3,
+        QueryMsg::GetBalance { denom: "u".into() },
+    );
+    assert_eq!(balance.u128(), 5);
+}
+
+#[test]
+fn modify_long_term_reprices_fee_lines() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "l", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForLongTermRental {
+            token_id: "l".into(),
+            denom: "u".into(),
+            price_per_month: 1000,
+            auto_approve: true,
+            available_period: vec![],
+            minimum_stay: 30,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    let line = FeeLine {
+        kind: FeeKind::Cleaning,
+        name: "cleaning".into(),
+        charge: FeeCharge::PerNight {
+            amount: Uint128::new(1),
+        },
+        recipient: FeeRecipient::Host,
+        refund: FeeRefund::Refundable,
+    };
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetFeeLinesForLongTerm {
+            token_id: "l".into(),
+            fee_lines: vec![line],
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(1090, "u"),
+        ExecuteMsg::SetReservationForLongTerm {
+            token_id: "l".into(),
+            renting_period: period(10, 100),
+            guests: 1,
+        },
+    )
+    .unwrap();
+    let r = exec(
+        &mut deps,
+        &contract,
+        2,
+        "bob",
+        &[],
+        ExecuteMsg::ModifyReservation {
+            token_id: "l".into(),
+            renting_period: period(10, 100),
+            new_renting_period: period(10, 50),
+        },
+    )
+    .unwrap();
+    assert_eq!(bank_sends(&r), vec![("bob".into(), "u".into(), 50)]);
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            2,
+            "bob",
+            &[],
+            ExecuteMsg::ModifyReservation {
+                token_id: "l".into(),
+                renting_period: period(10, 50),
+                new_renting_period: period(10, 120)
+            }
+        )
+        .unwrap_err(),
+        ContractError::InsufficientDeposit {}
+    );
+    let r = exec(
+        &mut deps,
+        &contract,
+        2,
+        "bob",
+        &coins(70, "u"),
+        ExecuteMsg::ModifyReservation {
+            token_id: "l".into(),
+            renting_period: period(10, 50),
+            new_renting_period: period(10, 120),
+        },
+    )
+    .unwrap();
+    assert!(bank_sends(&r).is_empty());
+}
+
+#[test]
+fn unanswered_reservations_expire_with_a_full_refund() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForShortTermRental {
+            token_id: "t".into(),
+            denom: "u".into(),
+            price_per_day: 100,
+            auto_approve: false,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetResponseWindowForShortTerm {
+            token_id: "t".into(),
+            response_window: Some(DAY),
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(202, "u"),
+        ExecuteMsg::SetReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: period(10, 12),
+            guests: 1,
+            promo_code: None,
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "carl",
+        &coins(101, "u"),
+        ExecuteMsg::SetReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: period(20, 21),
+            guests: 1,
+            promo_code: None,
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        2,
+        "alice",
+        &[],
+        ExecuteMsg::SetApproveForShortTerm {
+            token_id: "t".into(),
+            traveler: "carl".into(),
+            renting_period: period(20, 21),
+            access_instructions: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            100,
+            "zed",
+            &[],
+            ExecuteMsg::ExpireReservation {
+                token_id: "t".into(),
+                traveler: "bob".into(),
+                renting_period: period(10, 12)
+            }
+        )
+        .unwrap_err(),
+        ContractError::NotExpired {}
+    );
+
+    mint_token(&mut deps, &contract, "l", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForLongTermRental {
+            token_id: "l".into(),
+            denom: "u".into(),
+            price_per_month: 1000,
+            auto_approve: false,
+            available_period: vec![],
+            minimum_stay: 30,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetResponseWindowForLongTerm {
+            token_id: "l".into(),
+            response_window: Some(DAY),
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(1000, "u"),
+        ExecuteMsg::SetReservationForLongTerm {
+            token_id: "l".into(),
+            renting_period: period(10, 100),
+            guests: 1,
+        },
+    )
+    .unwrap();
+
+    let e: ExpiredReservationsResponse = query(
+        &deps,
+        &contract,
+        DAY + 1,
+        QueryMsg::ExpiredReservations {
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert_eq!(e.reservations.len(), 2);
+    assert_eq!(e.reservations[0].token_id, "l");
+    assert_eq!(e.reservations[1].address, "bob");
+    // pages count tokens looked at
+    let e: ExpiredReservationsResponse = query(
+        &deps,
+        &contract,
+        DAY + 1,
+        QueryMsg::ExpiredReservations {
+            start_after: None,
+            limit: Some(1),
+        },
+    );
+    assert_eq!(e.reservations.len(), 1);
+    assert_eq!(e.last_token_id, Some("l".to_string()));
+    let e: ExpiredReservationsResponse = query(
+        &deps,
+        &contract,
+        DAY + 1,
+        QueryMsg::ExpiredReservations {
+            start_after: e.last_token_id,
+            limit: Some(1),
+        },
+    );
+    assert_eq!(
+        (e.reservations[0].token_id.as_str(), e.last_token_id),
+        ("t", Some("t".to_string()))
+    );
+
+    // the fee refunded is the one charged at booking
+    exec(
+        &mut deps,
+        &contract,
+        2,
+        MINTER,
+        &[],
+        ExecuteMsg::SetFeeValue { fee: 500 },
+    )
+    .unwrap();
+
+    let r = exec(
+        &mut deps,
+        &contract,
+        DAY + 1,
+        "zed",
+        &[],
+        ExecuteMsg::ExpireReservation {
+            token_id: "t".into(),
+            traveler: "bob".into(),
+            renting_period: period(10, 12),
+        },
+    )
+    .unwrap();
+    assert_eq!(bank_sends(&r), vec![("bob".into(), "u".into(), 202)]);
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            DAY + 1,
+            "zed",
+            &[],
+            ExecuteMsg::ExpireReservation {
+                token_id: "t".into(),
+                traveler: "carl".into(),
+                renting_period: period(20, 21)
+            }
+        )
+        .unwrap_err(),
+        ContractError::ApprovedAlready {}
+    );
+    let r = exec(
+        &mut deps,
+        &contract,
+        DAY + 1,
+        "zed",
+        &[],
+        ExecuteMsg::ExpireReservation {
+            token_id: "l".into(),
+            traveler: "bob".into(),
+            renting_period: period(10, 100),
+        },
+    )
+    .unwrap();
+    assert_eq!(bank_sends(&r), vec![("bob".into(), "u".into(), 1000)]);
+    let e: ExpiredReservationsResponse = query(
+        &deps,
+        &contract,
+        DAY + 1,
+        QueryMsg::ExpiredReservations {
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert!(e.reservations.is_empty());
+}
+
+// Host cancellations
+
+/// Lists "t" for short-term rental, auto-approved at 100 a day
+fn list_short(
+    deps: &mut MockDeps,
+    contract: &Contract,
+    funds: &[Coin],
+) -> Result<Response, ContractError> {
+    exec(
+        deps,
+        contract,
+        1,
+        "alice",
+        funds,
+        ExecuteMsg::SetListForShortTermRental {
+            token_id: "t".into(),
+            denom: "u".into(),
+            price_per_day: 100,
+            auto_approve: true,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![],
+        },
+    )
+}
+
+#[test]
+fn host_cancellations_penalize_and_suspend() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        MINTER,
+        &[],
+        ExecuteMsg::SetHostPolicy {
+            policy: HostPolicy {
+                cancellation_penalty: 1000,
+                max_cancellations_for_auto_approve: Some(0),
+                max_cancellations_for_listing: Some(1),
+                min_bond: None,
+            },
+        },
+    )
+    .unwrap();
+    list_short(&mut deps, &contract, &coins(15, "u")).unwrap();
+    mint_token(&mut deps, &contract, "o", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForShortTermRental {
+            token_id: "o".into(),
+            denom: "u".into(),
+            price_per_day: 100,
+            auto_approve: true,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(202, "u"),
+        ExecuteMsg::SetReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: period(10, 12),
+            guests: 1,
+            promo_code: None,
+        },
+    )
+    .unwrap();
+    assert!(exec(
+        &mut deps,
+        &contract,
+        2,
+        "carl",
+        &[],
+        ExecuteMsg::CancelReservationByHost {
+            token_id: "t".into(),
+            traveler: "bob".into(),
+            renting_period: period(10, 12)
+        }
+    )
+    .is_err());
+    let r = exec(
+        &mut deps,
+        &contract,
+        2,
+        "alice",
+        &[],
+        ExecuteMsg::CancelReservationByHost {
+            token_id: "t".into(),
+            traveler: "bob".into(),
+            renting_period: period(10, 12),
+        },
+    )
+    .unwrap();
+    // 200 rent + 2 fee + penalty capped at the 15 bond
+    assert_eq!(bank_sends(&r), vec![("bob".into(), "u".into(), 217)]);
+    let h: HostRecordResponse = query(
+        &deps,
+        &contract,
+        2,
+        QueryMsg::HostRecord {
+            host: "alice".into(),
+        },
+    );
+    assert_eq!(h.record.cancellations, 1);
+    assert_eq!(h.bond.unwrap().amount.u128(), 0);
+    assert!(!h.auto_approve_eligible && h.listing_eligible);
+
+    // no longer auto-approves
+    exec(
+        &mut deps,
+        &contract,
+        3,
+        "bob",
+        &coins(202, "u"),
+        ExecuteMsg::SetReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: period(10, 12),
+            guests: 1,
+            promo_code: None,
+        },
+    )
+    .unwrap();
+    let rs: RentalsResponse = query(
+        &deps,
+        &contract,
+        3,
+        QueryMsg::NftRentals {
+            token_id: "t".into(),
+        },
+    );
+    assert!(!rs.rentals[0].approved);
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            3,
+            "alice",
+            &[],
+            ExecuteMsg::CancelReservationByHost {
+                token_id: "t".into(),
+                traveler: "bob".into(),
+                renting_period: period(10, 12)
+            }
+        )
+        .unwrap_err(),
+        ContractError::NotApproved {}
+    );
+    exec(
+        &mut deps,
+        &contract,
+        3,
+        "alice",
+        &[],
+        ExecuteMsg::SetApproveForShortTerm {
+            token_id: "t".into(),
+            traveler: "bob".into(),
+            renting_period: period(10, 12),
+            access_instructions: None,
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        3,
+        "alice",
+        &[],
+        ExecuteMsg::CancelReservationByHost {
+            token_id: "t".into(),
+            traveler: "bob".into(),
+            renting_period: period(10, 12),
+        },
+    )
+    .unwrap();
+    let h: HostRecordResponse = query(
+        &deps,
+        &contract,
+        3,
+        QueryMsg::HostRecord {
+            host: "alice".into(),
+        },
+    );
+    assert!(!h.listing_eligible);
+    let info: ShortTermRental = query(
+        &deps,
+        &contract,
+        3,
+        QueryMsg::NftInfoShortTermRental {
+            token_id: "t".into(),
+        },
+    );
+    assert!(info.islisted.is_none());
+    assert_eq!(
+        list_short(&mut deps, &contract, &[]).unwrap_err(),
+        ContractError::HostSuspended {}
+    );
+    // the host's other listings stop taking reservations too
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            3,
+            "carl",
+            &coins(202, "u"),
+            ExecuteMsg::SetReservationForShortTerm {
+                token_id: "o".into(),
+                renting_period: period(10, 12),
+                guests: 1,
+                promo_code: None,
+            },
+        )
+        .unwrap_err(),
+        ContractError::HostSuspended {}
+    );
+
+    // cancelled stays keep the bond locked until their check-out
+    let r = exec(
+        &mut deps,
+        &contract,
+        12 * DAY + 1,
+        "alice",
+        &[],
+        ExecuteMsg::WithdrawHostBond {},
+    )
+    .unwrap();
+    assert!(bank_sends(&r).is_empty());
+}
+
+#[test]
+fn host_bond_minimum_and_lock() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        MINTER,
+        &[],
+        ExecuteMsg::SetHostPolicy {
+            policy: HostPolicy {
+                cancellation_penalty: 0,
+                max_cancellations_for_auto_approve: None,
+                max_cancellations_for_listing: None,
+                min_bond: Some(Coin::new(50, "u")),
+            },
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        list_short(&mut deps, &contract, &coins(15, "u")).unwrap_err(),
+        ContractError::HostBondTooLow {}
+    );
+    list_short(&mut deps, &contract, &coins(50, "u")).unwrap();
+
+    // a withdrawn bond stops new reservations
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::WithdrawHostBond {},
+    )
+    .unwrap();
+    assert_eq!(
+        book_short(&mut deps, &contract, "bob", 10 * DAY, 12 * DAY).unwrap_err(),
+        ContractError::HostBondTooLow {}
+    );
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &coins(50, "u"),
+        ExecuteMsg::PostHostBond {},
+    )
+    .unwrap();
+    book_short(&mut deps, &contract, "bob", 10 * DAY, 12 * DAY).unwrap();
+
+    // the bond stays locked until the check-out, and moves with the property
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "dan",
+        &coins(50, "u"),
+        ExecuteMsg::PostHostBond {},
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        2,
+        "alice",
+        &[],
+        ExecuteMsg::TransferNft {
+            recipient: "dan".into(),
+            token_id: "t".into(),
+        },
+    )
+    .unwrap();
+    for host in ["alice", "dan"] {
+        assert_eq!(
+            exec(
+                &mut deps,
+                &contract,
+                12 * DAY,
+                host,
+                &[],
+                ExecuteMsg::WithdrawHostBond {}
+            )
+            .unwrap_err(),
+            ContractError::RentalActive {}
+        );
+        let r = exec(
+            &mut deps,
+            &contract,
+            12 * DAY + 1,
+            host,
+            &[],
+            ExecuteMsg::WithdrawHostBond {},
+        )
+        .unwrap();
+        assert_eq!(bank_sends(&r), vec![(host.into(), "u".into(), 50)]);
+    }
+}
+
+#[test]
+fn modify_reservation_needs_host_bond() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "l", "alice");
+    let policy = |min_bond| ExecuteMsg::SetHostPolicy {
+        policy: HostPolicy {
+            cancellation_penalty: 0,
+            max_cancellations_for_auto_approve: None,
+            max_cancellations_for_listing: None,
+            min_bond: Some(Coin::new(min_bond, "u")),
+        },
+    };
+    exec(&mut deps, &contract, 1, MINTER, &[], policy(50)).unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &coins(50, "u"),
+        ExecuteMsg::SetListForLongTermRental {
+            token_id: "l".into(),
+            denom: "u".into(),
+            price_per_month: 1000,
+            auto_approve: true,
+            available_period: vec![],
+            minimum_stay: 30,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(1000, "u"),
+        ExecuteMsg::SetReservationForLongTerm {
+            token_id: "l".into(),
+            renting_period: period(10, 100),
+            guests: 1,
+        },
+    )
+    .unwrap();
+
+    // moving a stay is held to the same bond as booking one
+    exec(&mut deps, &contract, 2, MINTER, &[], policy(60)).unwrap();
+    let modify = ExecuteMsg::ModifyReservation {
+        token_id: "l".into(),
+        renting_period: period(10, 100),
+        new_renting_period: period(20, 110),
+    };
+    assert_eq!(
+        exec(&mut deps, &contract, 2, "bob", &[], modify.clone()).unwrap_err(),
+        ContractError::HostBondTooLow {}
+    );
+    exec(
+        &mut deps,
+        &contract,
+        2,
+        "alice",
+        &coins(10, "u"),
+        ExecuteMsg::PostHostBond {},
+    )
+    .unwrap();
+    exec(&mut deps, &contract, 2, "bob", &[], modify).unwrap();
+}
+
+#[test]
+fn host_bond_covers_withdrawn_legacy_fee() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    list_short(&mut deps, &contract, &coins(15, "u")).unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(202, "u"),
+        ExecuteMsg::SetReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: period(10, 12),
+            guests: 1,
+            promo_code: None,
+        },
+    )
+    .unwrap();
+    // reservations booked before the fee was held paid it to the platform, which may
+    // have withdrawn it since
+    let mut token = contract.tokens.load(&deps.storage, "t").unwrap();
+    token.rentals[0].platform_fee = None;
+    contract
+        .tokens
+        .save(&mut deps.storage, "t", &token)
+        .unwrap();
+
+    let r = exec(
+        &mut deps,
+        &contract,
+        2,
+        "alice",
+        &[],
+        ExecuteMsg::CancelReservationByHost {
+            token_id: "t".into(),
+            traveler: "bob".into(),
+            renting_period: period(10, 12),
+        },
+    )
+    .unwrap();
+    assert_eq!(bank_sends(&r), vec![("bob".into(), "u".into(), 202)]);
+    let h: HostRecordResponse = query(
+        &deps,
+        &contract,
+        2,
+        QueryMsg::HostRecord {
+            host: "alice".into(),
+        },
+    );
+    assert_eq!(h.bond.unwrap().amount.u128(), 13);
+}
+
+#[test]
+fn host_cancel_needs_bond_for_withdrawn_legacy_fee() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    list_short(&mut deps, &contract, &[]).unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(202, "u"),
+        ExecuteMsg::SetReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: period(10, 12),
+            guests: 1,
+            promo_code: None,
+        },
+    )
+    .unwrap();
+    let mut token = contract.tokens.load(&deps.storage, "t").unwrap();
+    token.rentals[0].platform_fee = None;
+    contract
+        .tokens
+        .save(&mut deps.storage, "t", &token)
+        .unwrap();
+
+    let cancel = ExecuteMsg::CancelReservationByHost {
+        token_id: "t".into(),
+        traveler: "bob".into(),
+        renting_period: period(10, 12),
+    };
+    assert_eq!(
+        exec(&mut deps, &contract, 2, "alice", &[], cancel.clone()).unwrap_err(),
+        ContractError::FeeNotCovered {}
+    );
+    exec(
+        &mut deps,
+        &contract,
+        2,
+        "alice",
+        &coins(1, "u"),
+        ExecuteMsg::PostHostBond {},
+    )
+    .unwrap();
+    assert_eq!(
+        exec(&mut deps, &contract, 2, "alice", &[], cancel.clone()).unwrap_err(),
+        ContractError::FeeNotCovered {}
+    );
+    exec(
+        &mut deps,
+        &contract,
+        2,
+        "alice",
+        &coins(1, "u"),
+        ExecuteMsg::PostHostBond {},
+    )
+    .unwrap();
+    let r = exec(&mut deps, &contract, 2, "alice", &[], cancel).unwrap();
+    assert_eq!(bank_sends(&r), vec![("bob".into(), "u".into(), 202)]);
+}
+
+// Reviews
+
+#[test]
+fn reviews_after_finalized_stays() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForShortTermRental {
+            token_id: "t".into(),
+            denom: "u".into(),
+            price_per_day: 100,
+            auto_approve: true,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(202, "u"),
+        ExecuteMsg::SetReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: period(10, 12),
+            guests: 1,
+            promo_code: None,
+        },
+    )
+    .unwrap();
+    let review = |rating| ExecuteMsg::ReviewStay {
+        token_id: "t".into(),
+        renting_period: period(10, 12),
+        rating,
+        review_hash: "h".into(),
+    };
+    assert_eq!(
+        exec(&mut deps, &contract, 11 * DAY, "bob", &[], review(5)).unwrap_err(),
+        ContractError::NoStay {}
+    );
+    exec(
+        &mut deps,
+        &contract,
+        12 * DAY,
+        "alice",
+        &[],
+        ExecuteMsg::FinalizeShortTermRental {
+            token_id: "t".into(),
+            traveler: "bob".into(),
+            renting_period: period(10, 12),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        exec(&mut deps, &contract, 12 * DAY, "bob", &[], review(6)).unwrap_err(),
+        ContractError::InvalidInput {}
+    );
+    assert!(exec(&mut deps, &contract, 12 * DAY, "zed", &[], review(3)).is_err());
+    exec(&mut deps, &contract, 12 * DAY, "bob", &[], review(4)).unwrap();
+    assert_eq!(
+        exec(&mut deps, &contract, 12 * DAY, "bob", &[], review(4)).unwrap_err(),
+        ContractError::AlreadyReviewed {}
+    );
+    exec(&mut deps, &contract, 12 * DAY, "alice", &[], review(2)).unwrap();
+    assert_eq!(
+        exec(&mut deps, &contract, 12 * DAY, "alice", &[], review(2)).unwrap_err(),
+        ContractError::NoStay {}
+    );
+
+    let r: RatingResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::TokenRating {
+            token_id: "t".into(),
+        },
+    );
+    assert_eq!((r.count, r.average.to_string()), (1, "4".to_string()));
+    let r: RatingResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::HostRating {
+            host: "alice".into(),
+        },
+    );
+    assert_eq!(r.count, 1);
+    let r: RatingResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::TravelerRating {
+            traveler: "bob".into(),
+        },
+    );
+    assert_eq!(r.average.to_string(), "2");
+    let rv: ReviewsResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::Reviews {
+            token_id: "t".into(),
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert_eq!(rv.reviews.len(), 2);
+    let rv: ReviewsResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::Reviews {
+            token_id: "t".into(),
+            start_after: Some(1),
+            limit: None,
+        },
+    );
+    assert_eq!(rv.reviews.len(), 1);
+    let rv: ReviewsResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::ReviewsOf {
+            subject: "bob".into(),
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert!(rv.reviews[0].by_host);
+
+    // cancelled / never approved stays grant no review
+    let p2 = period(20, 21);
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "carl",
+        &coins(101, "u"),
+        ExecuteMsg::SetReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: p2.clone(),
+            guests: 1,
+            promo_code: None,
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        2,
+        "carl",
+        &[],
+        ExecuteMsg::CancelRentalForShortterm {
+            token_id: "t".into(),
+            renting_period: p2.clone(),
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        3,
+        "alice",
+        &[],
+        ExecuteMsg::FinalizeShortTermRental {
+            token_id: "t".into(),
+            traveler: "carl".into(),
+            renting_period: p2.clone(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            30 * DAY,
+            "carl",
+            &[],
+            ExecuteMsg::ReviewStay {
+                token_id: "t".into(),
+                renting_period: p2,
+                rating: 1,
+                review_hash: "h".into()
+            }
+        )
+        .unwrap_err(),
+        ContractError::NoStay {}
+    );
+}
+
+#[test]
+fn reviews_close_and_skip_own_stays() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForShortTermRental {
+            token_id: "t".into(),
+            denom: "u".into(),
+            price_per_day: 100,
+            auto_approve: true,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    book_short(&mut deps, &contract, "bob", 10 * DAY, 12 * DAY).unwrap();
+    book_short(&mut deps, &contract, "alice", 20 * DAY, 21 * DAY).unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        12 * DAY,
+        "alice",
+        &[],
+        ExecuteMsg::FinalizeShortTermRental {
+            token_id: "t".into(),
+            traveler: "bob".into(),
+            renting_period: period(10, 12),
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        21 * DAY,
+        "alice",
+        &[],
+        ExecuteMsg::FinalizeShortTermRental {
+            token_id: "t".into(),
+            traveler: "alice".into(),
+            renting_period: period(20, 21),
+        },
+    )
+    .unwrap();
+    let review = |renting_period| ExecuteMsg::ReviewStay {
+        token_id: "t".into(),
+        renting_period,
+        rating: 5,
+        review_hash: "h".into(),
+    };
+
+    // the host staying at their own property cannot rate it
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            21 * DAY,
+            "alice",
+            &[],
+            review(period(20, 21))
+        )
+        .unwrap_err(),
+        ContractError::NoStay {}
+    );
+    exec(
+        &mut deps,
+        &contract,
+        12 * DAY + REVIEW_WINDOW,
+        "bob",
+        &[],
+        review(period(10, 12)),
+    )
+    .unwrap();
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            12 * DAY + REVIEW_WINDOW + 1,
+            "alice",
+            &[],
+            review(period(10, 12))
+        )
+        .unwrap_err(),
+        ContractError::ReviewWindowClosed {}
+    );
+}
+
+// Discounts and promo codes
+
+#[test]
+fn stay_discounts_and_promo_codes() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForShortTermRental {
+            token_id: "t".into(),
+            denom: "u".into(),
+            price_per_day: 100,
+            auto_approve: true,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            1,
+            "alice",
+            &[],
+            ExecuteMsg::SetStayDiscountsForShortTerm {
+                token_id: "t".into(),
+                weekly_discount: 10001,
+                monthly_discount: 0
+            }
+        )
+        .unwrap_err(),
+        ContractError::InvalidInput {}
+    );
+    assert!(exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &[],
+        ExecuteMsg::SetStayDiscountsForShortTerm {
+            token_id: "t".into(),
+            weekly_discount: 1000,
+            monthly_discount: 2000
+        }
+    )
+    .is_err());
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetStayDiscountsForShortTerm {
+            token_id: "t".into(),
+            weekly_discount: 1000,
+            monthly_discount: 2000,
+        },
+    )
+    .unwrap();
+
+    let quote = |deps: &MockDeps, a, b, promo: Option<&str>| -> ShortTermQuote {
+        query(
+            deps,
+            &contract,
+            1,
+            QueryMsg::QuoteShortTerm {
+                token_id: "t".into(),
+                renting_period: period(a, b),
+                guests: 1,
+                promo_code: promo.map(String::from),
+            },
+        )
+    };
+    let qq = quote(&deps, 10, 13, None);
+    assert_eq!((qq.rent_amount.u128(), qq.discount.u128()), (300, 0));
+    let qq = quote(&deps, 10, 17, None);
+    assert_eq!(
+        (
+            qq.rent_amount.u128(),
+            qq.discount.u128(),
+            qq.fee.u128(),
+            qq.total.u128()
+        ),
+        (630, 70, 6, 636)
+    );
+    let qq = quote(&deps, 10, 40, None);
+    assert_eq!((qq.rent_amount.u128(), qq.discount.u128()), (2400, 600));
+
+    let create = |code: &str, token: Option<&str>, discount, max_uses, expires| {
+        ExecuteMsg::CreatePromoCode {
+            code: code.into(),
+            token_id: token.map(String::from),
+            discount,
+            max_uses,
+            expires,
+        }
+    };
+    assert!(exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &[],
+        create(
+            "HOST10",
+            Some("t"),
+            PromoDiscount::Percent { bps: 1000 },
+            Some(1),
+            None
+        )
+    )
+    .is_err());
+    assert!(exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        create(
+            "ANY",
+            None,
+            PromoDiscount::Percent { bps: 1000 },
+            None,
+            None
+        )
+    )
+    .is_err());
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            1,
+            "alice",
+            &[],
+            create(
+                "BAD",
+                Some("t"),
+                PromoDiscount::Percent { bps: 0 },
+                None,
+                None
+            )
+        )
+        .unwrap_err(),
+        ContractError::InvalidInput {}
+    );
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        create(
+            "HOST10",
+            Some("t"),
+            PromoDiscount::Percent { bps: 1000 },
+            Some(1),
+            None,
+        ),
+    )
+    .unwrap();
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            1,
+            "alice",
+            &[],
+            create(
+                "HOST10",
+                Some("t"),
+                PromoDiscount::Percent { bps: 500 },
+                None,
+                None
+            )
+        )
+        .unwrap_err(),
+        ContractError::InvalidInput {}
+    );
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        MINTER,
+        &[],
+        create(
+            "PLAT",
+            None,
+            PromoDiscount::Fixed {
+                amount: Uint128::new(50),
+            },
+            None,
+            Some(Expiration::AtTime(Timestamp::from_seconds(100))),
+        ),
+    )
+    .unwrap();
+
+    // platform codes need the host to accept them, as the host bears the discount
+    assert!(contract
+        .query(
+            deps.as_ref(),
+            env_at(1),
+            QueryMsg::QuoteShortTerm {
+                token_id: "t".into(),
+                renting_period: period(10, 13),
+                guests: 1,
+                promo_code: Some("PLAT".into())
+            }
+        )
+        .is_err());
+    assert!(exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &[],
+        ExecuteMsg::SetPlatformPromosForShortTerm {
+            token_id: "t".into(),
+            accept: true
+        }
+    )
+    .is_err());
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetPlatformPromosForShortTerm {
+            token_id: "t".into(),
+            accept: true,
+        },
+    )
+    .unwrap();
+    let qq = quote(&deps, 10, 13, Some("PLAT"));
+    assert_eq!((qq.rent_amount.u128(), qq.discount.u128()), (250, 50));
+    assert!(contract
+        .query(
+            deps.as_ref(),
+            env_at(1),
+            QueryMsg::QuoteShortTerm {
+                token_id: "t".into(),
+                renting_period: period(10, 13),
+                guests: 1,
+                promo_code: Some("NOPE".into())
+            }
+        )
+        .is_err());
+
+    // 700 - 70 weekly - 63 promo = 567, fee 5
+    let reserve = |code: Option<&str>, a, b| ExecuteMsg::SetReservationForShortTerm {
+        token_id: "t".into(),
+        renting_period: period(a, b),
+        guests: 1,
+        promo_code: code.map(String::from),
+    };
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            1,
+            "bob",
+            &coins(571, "u"),
+            reserve(Some("HOST10"), 10, 17)
+        )
+        .unwrap_err(),
+        ContractError::InsufficientDeposit {}
+    );
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(572, "u"),
+        reserve(Some("HOST10"), 10, 17),
+    )
+    .unwrap();
+    let r: RentalsResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::NftRentals {
+            token_id: "t".into(),
+        },
+    );
+    assert_eq!(
+        (
+            r.rentals[0].deposit_amount.u128(),
+            r.rentals[0].discount.u128(),
+            r.rentals[0].promo_code.clone()
+        ),
+        (567, 133, Some("HOST10".to_string()))
+    );
+    let p: PromoCode = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::PromoCode {
+            code: "HOST10".into(),
+        },
+    );
+    assert_eq!(p.uses, 1);
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            1,
+            "carl",
+            &coins(1000, "u"),
+            reserve(Some("HOST10"), 20, 22)
+        )
+        .unwrap_err(),
+        ContractError::InvalidPromoCode {}
+    );
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            200,
+            "carl",
+            &coins(1000, "u"),
+            reserve(Some("PLAT"), 20, 22)
+        )
+        .unwrap_err(),
+        ContractError::InvalidPromoCode {}
+    );
+
+    // modifying keeps the redeemed code although it is used up
+    let res = exec(
+        &mut deps,
+        &contract,
+        2,
+        "bob",
+        &[],
+        ExecuteMsg::ModifyReservation {
+            token_id: "t".into(),
+            renting_period: period(10, 17),
+            new_renting_period: period(10, 16),
+        },
+    )
+    .unwrap();
+    // 600 - 60 promo = 540, fee 5 -> refund 572 - 545
+    assert_eq!(
+        bank_sends(&res),
+        vec![("bob".to_string(), "u".to_string(), 27)]
+    );
+
+    assert!(exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &[],
+        ExecuteMsg::RevokePromoCode {
+            code: "PLAT".into()
+        }
+    )
+    .is_err());
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        MINTER,
+        &[],
+        ExecuteMsg::RevokePromoCode {
+            code: "PLAT".into(),
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::RevokePromoCode {
+            code: "HOST10".into(),
+        },
+    )
+    .unwrap();
+    // and keeps its terms once revoked, 500 - 50 promo = 450, fee 4 -> refund 545 - 454
+    let res = exec(
+        &mut deps,
+        &contract,
+        2,
+        "bob",
+        &[],
+        ExecuteMsg::ModifyReservation {
+            token_id: "t".into(),
+            renting_period: period(10, 16),
+            new_renting_period: period(10, 15),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        bank_sends(&res),
+        vec![("bob".to_string(), "u".to_string(), 91)]
+    );
+    assert!(contract
+        .query(
+            deps.as_ref(),
+            env_at(1),
+            QueryMsg::PromoCode {
+                code: "PLAT".into()
+            }
+        )
+        .is_err());
+}
+
+#[test]
+fn unused_reservations_give_the_promo_code_back() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    list_short(&mut deps, &contract, &[]).unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::CreatePromoCode {
+            code: "ONCE".into(),
+            token_id: Some("t".into()),
+            discount: PromoDiscount::Percent { bps: 1000 },
+            max_uses: Some(1),
+            expires: None,
+        },
+    )
+    .unwrap();
+    let reserve = |a, b| ExecuteMsg::SetReservationForShortTerm {
+        token_id: "t".into(),
+        renting_period: period(a, b),
+        guests: 1,
+        promo_code: Some("ONCE".into()),
+    };
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(1000, "u"),
+        reserve(10, 12),
+    )
+    .unwrap();
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            1,
+            "carl",
+            &coins(1000, "u"),
+            reserve(20, 22)
+        )
+        .unwrap_err(),
+        ContractError::InvalidPromoCode {}
+    );
+
+    exec(
+        &mut deps,
+        &contract,
+        2,
+        "alice",
+        &[],
+        ExecuteMsg::CancelReservationByHost {
+            token_id: "t".into(),
+            traveler: "bob".into(),
+            renting_period: period(10, 12),
+        },
+    )
+    .unwrap();
+    let p: PromoCode = query(
+        &deps,
+        &contract,
+        2,
+        QueryMsg::PromoCode {
+            code: "ONCE".into(),
+        },
+    );
+    assert_eq!(p.uses, 0);
+    exec(
+        &mut deps,
+        &contract,
+        2,
+        "carl",
+        &coins(1000, "u"),
+        reserve(20, 22),
+    )
+    .unwrap();
+
+    // a rejected request or a cancelled stay gives it back too
+    exec(
+        &mut deps,
+        &contract,
+        3,
+        "alice",
+        &[],
+        ExecuteMsg::RejectReservationForShortterm {
+            token_id: "t".into(),
+            traveler: "carl".into(),
+            renting_period: period(20, 22),
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        3,
+        "dave",
+        &coins(1000, "u"),
+        reserve(30, 32),
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        4,
+        "dave",
+        &[],
+        ExecuteMsg::CancelRentalForShortterm {
+            token_id: "t".into(),
+            renting_period: period(30, 32),
+        },
+    )
+    .unwrap();
+    let p: PromoCode = query(
+        &deps,
+        &contract,
+        4,
+        QueryMsg::PromoCode {
+            code: "ONCE".into(),
+        },
+    );
+    assert_eq!(p.uses, 0);
+}
+
+// Fee lines
+
+fn fee_line(
+    kind: FeeKind,
+    charge: FeeCharge,
+    recipient: FeeRecipient,
+    refund: FeeRefund,
+) -> FeeLine {
+    FeeLine {
+        kind,
+        name: "x".into(),
+        charge,
+        recipient,
+        refund,
+    }
+}
+fn platform_balance(deps: &MockDeps, contract: &Contract) -> u128 {
+    let b: Uint128 = query(
+        deps,
+        contract,
+        1,
+        QueryMsg::GetBalance { denom: "u".into() },
+    );
+    b.u128()
+}
+
+#[test]
+fn fee_lines_on_short_term_stays() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForShortTermRental {
+            token_id: "t".into(),
+            denom: "u".into(),
+            price_per_day: 100,
+            auto_approve: true,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![CancellationItem {
+                deadline: 5,
+                percentage: 50,
+            }],
+        },
+    )
+    .unwrap();
+    let lines = vec![
+        fee_line(
+            FeeKind::Cleaning,
+            FeeCharge::Flat {
+                amount: Uint128::new(30),
+            },
+            FeeRecipient::Host,
+            FeeRefund::NonRefundable,
+        ),
+        fee_line(
+            FeeKind::Service,
+            FeeCharge::Percent { bps: 1000 },
+            FeeRecipient::Platform,
+            FeeRefund::AsRent,
+        ),
+        fee_line(
+            FeeKind::Tax,
+            FeeCharge::PerNight {
+                amount: Uint128::new(5),
+            },
+            FeeRecipient::Address {
+                address: "tax".into(),
+            },
+            FeeRefund::Refundable,
+        ),
+    ];
+    let mut bad = lines.clone();
+    bad[1].charge = FeeCharge::Percent { bps: 10001 };
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            1,
+            "alice",
+            &[],
+            ExecuteMsg::SetFeeLinesForShortTerm {
+                token_id: "t".into(),
+                fee_lines: bad
+            }
+        )
+        .unwrap_err(),
+        ContractError::InvalidInput {}
+    );
+    assert!(exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &[],
+        ExecuteMsg::SetFeeLinesForShortTerm {
+            token_id: "t".into(),
+            fee_lines: lines.clone()
+        }
+    )
+    .is_err());
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetFeeLinesForShortTerm {
+            token_id: "t".into(),
+            fee_lines: lines,
+        },
+    )
+    .unwrap();
+
+    let quote: ShortTermQuote = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::QuoteShortTerm {
+            token_id: "t".into(),
+            renting_period: period(10, 12),
+            guests: 1,
+            promo_code: None,
+        },
+    );
+    assert_eq!(quote.total.u128(), 262);
+    let reserve = |a, b| ExecuteMsg::SetReservationForShortTerm {
+        token_id: "t".into(),
+        renting_period: period(a, b),
+        guests: 1,
+        promo_code: None,
+    };
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            1,
+            "bob",
+            &coins(261, "u"),
+            reserve(10, 12)
+        )
+        .unwrap_err(),
+        ContractError::InsufficientDeposit {}
+    );
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(262, "u"),
+        reserve(10, 12),
+    )
+    .unwrap();
+    // the platform fee is held until the stay is finalized
+    assert_eq!(platform_balance(&deps, &contract), 0);
+
+    let res = exec(
+        &mut deps,
+        &contract,
+        12 * DAY,
+        "alice",
+        &[],
+        ExecuteMsg::FinalizeShortTermRental {
+            token_id: "t".into(),
+            traveler: "bob".into(),
+            renting_period: period(10, 12),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        bank_sends(&res),
+        vec![
+            ("alice".to_string(), "u".to_string(), 228),
+            ("tax".to_string(), "u".to_string(), 10)
+        ]
+    );
+    assert_eq!(platform_balance(&deps, &contract), 24);
+
+    // half the rent back, the cleaning fee kept, the service fee like the rent, the tax in full
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "carl",
+        &coins(262, "u"),
+        reserve(20, 22),
+    )
+    .unwrap();
+    let res = exec(
+        &mut deps,
+        &contract,
+        1,
+        "carl",
+        &[],
+        ExecuteMsg::CancelRentalForShortterm {
+            token_id: "t".into(),
+            renting_period: period(20, 22),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        bank_sends(&res),
+        vec![("carl".to_string(), "u".to_string(), 120)]
+    );
+    let res = exec(
+        &mut deps,
+        &contract,
+        2,
+        "alice",
+        &[],
+        ExecuteMsg::FinalizeShortTermRental {
+            token_id: "t".into(),
+            traveler: "carl".into(),
+            renting_period: period(20, 22),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        bank_sends(&res),
+        vec![("alice".to_string(), "u".to_string(), 130)]
+    );
+    assert_eq!(platform_balance(&deps, &contract), 24 + 2 + 10);
+
+    let before = platform_balance(&deps, &contract);
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "dan",
+        &coins(262, "u"),
+        reserve(30, 32),
+    )
+    .unwrap();
+    let res = exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::RejectReservationForShortterm {
+            token_id: "t".into(),
+            traveler: "dan".into(),
+            renting_period: period(30, 32),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        bank_sends(&res),
+        vec![("dan".to_string(), "u".to_string(), 260)]
+    );
+    assert_eq!(platform_balance(&deps, &contract), before + 2);
+}
+
+#[test]
+fn fee_lines_on_long_term_stays() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "l", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForLongTermRental {
+            token_id: "l".into(),
+            denom: "u".into(),
+            price_per_month: 1000,
+            auto_approve: false,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    let lines = vec![
+        fee_line(
+            FeeKind::Cleaning,
+            FeeCharge::Flat {
+                amount: Uint128::new(50),
+            },
+            FeeRecipient::Host,
+            FeeRefund::NonRefundable,
+        ),
+        fee_line(
+            FeeKind::Tax,
+            FeeCharge::Percent { bps: 500 },
+            FeeRecipient::Address {
+                address: "tax".into(),
+            },
+            FeeRefund::Refundable,
+        ),
+    ];
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetFeeLinesForLongTerm {
+            token_id: "l".into(),
+            fee_lines: lines,
+        },
+    )
+    .unwrap();
+    let reserve = || ExecuteMsg::SetReservationForLongTerm {
+        token_id: "l".into(),
+        renting_period: period(40, 70),
+        guests: 1,
+    };
+    assert_eq!(
+        exec(&mut deps, &contract, 1, "bob", &coins(1099, "u"), reserve()).unwrap_err(),
+        ContractError::InsufficientDeposit {}
+    );
+    exec(&mut deps, &contract, 1, "bob", &coins(1100, "u"), reserve()).unwrap();
+    let r: RentalsResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::NftRentals {
+            token_id: "l".into(),
+        },
+    );
+    assert_eq!(r.rentals[0].deposit_amount.u128(), 1000);
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetApproveForLongTerm {
+            token_id: "l".into(),
+            tenant: "bob".into(),
+            renting_period: period(40, 70),
+            approved_date: "d".into(),
+        },
+    )
+    .unwrap();
+    let res = exec(
+        &mut deps,
+        &contract,
+        2,
+        "bob",
+        &[],
+        ExecuteMsg::CancelRentalForLongterm {
+            token_id: "l".into(),
+            renting_period: period(40, 70),
+        },
+    )
+    .unwrap();
+    // the rent stays with the host, the refundable tax does not
+    assert_eq!(
+        bank_sends(&res),
+        vec![("bob".to_string(), "u".to_string(), 50)]
+    );
+    let res = exec(
+        &mut deps,
+        &contract,
+        3,
+        "alice",
+        &[],
+        ExecuteMsg::FinalizeLongTermRental {
+            token_id: "l".into(),
+            tenant: "bob".into(),
+            renting_period: period(40, 70),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        bank_sends(&res),
+        vec![("alice".to_string(), "u".to_string(), 1040)]
+    );
+
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "carl",
+        &coins(1100, "u"),
+        reserve(),
+    )
+    .unwrap();
+    let res = exec(
+        &mut deps,
+        &contract,
+        2,
+        "alice",
+        &[],
+        ExecuteMsg::RejectReservationForLongterm {
+            token_id: "l".into(),
+            tenant: "carl".into(),
+            renting_period: period(40, 70),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        bank_sends(&res),
+        vec![("carl".to_string(), "u".to_string(), 1100)]
+    );
+}
+
+// Quotes and refund schedules
+
+fn cancel_item(deadline: u64, percentage: u64) -> CancellationItem {
+    CancellationItem {
+        deadline,
+        percentage,
+    }
+}
+
+#[test]
+fn short_term_quote_refund_schedule() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForShortTermRental {
+            token_id: "t".into(),
+            denom: "u".into(),
+            price_per_day: 100,
+            auto_approve: true,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![
+                cancel_item(2, 50),
+                cancel_item(10, 10),
+                cancel_item(7, 100),
+                cancel_item(1, 20),
+            ],
+        },
+    )
+    .unwrap();
+    let quote: ShortTermQuote = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::QuoteShortTerm {
+            token_id: "t".into(),
+            renting_period: period(20, 22),
+            guests: 1,
+            promo_code: None,
+        },
+    );
+    assert_eq!(quote.denom, "u");
+    let steps: Vec<_> = quote
+        .refund_schedule
+        .iter()
+        .map(|s| (s.cancel_by, s.percentage, s.refund.u128()))
+        .collect();
+    assert_eq!(
+        steps,
+        vec![
+            (12 * DAY, 100, 200),
+            (17 * DAY, 50, 100),
+            (18 * DAY, 20, 40)
+        ]
+    );
+
+    let reserve = |who: &str| {
+        (
+            who.to_string(),
+            ExecuteMsg::SetReservationForShortTerm {
+                token_id: "t".into(),
+                renting_period: period(20, 22),
+                guests: 1,
+                promo_code: None,
+            },
+        )
+    };
+    let cancel = || ExecuteMsg::CancelRentalForShortterm {
+        token_id: "t".into(),
+        renting_period: period(20, 22),
+    };
+    for (t, refund) in [
+        (12 * DAY, 200),
+        (12 * DAY + 1, 100),
+        (17 * DAY, 100),
+        (17 * DAY + 1, 40),
+        (18 * DAY + 1, 0),
+    ] {
+        let (who, msg) = reserve("bob");
+        exec(
+            &mut deps,
+            &contract,
+            1,
+            &who,
+            &coins(quote.total.u128(), "u"),
+            msg,
+        )
+        .unwrap();
+        let res = exec(&mut deps, &contract, t, "bob", &[], cancel()).unwrap();
+        let paid: u128 = bank_sends(&res).iter().map(|m| m.2).sum();
+        assert_eq!(paid, refund, "at {}", t);
+        exec(
+            &mut deps,
+            &contract,
+            t,
+            "alice",
+            &[],
+            ExecuteMsg::FinalizeShortTermRental {
+                token_id: "t".into(),
+                traveler: "bob".into(),
+                renting_period: period(20, 22),
+            },
+        )
+        .unwrap();
+    }
+}
+
+#[test]
+fn long_term_quote_refund_schedule() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "l", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForLongTermRental {
+            token_id: "l".into(),
+            denom: "u".into(),
+            price_per_month: 1000,
+            auto_approve: false,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![cancel_item(30, 80)],
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetFeeLinesForLongTerm {
+            token_id: "l".into(),
+            fee_lines: vec![fee_line(
+                FeeKind::Service,
+                FeeCharge::Flat {
+                    amount: Uint128::new(30),
+                },
+                FeeRecipient::Host,
+                FeeRefund::Refundable,
+            )],
+        },
+    )
+    .unwrap();
+    let quote: LongTermQuote = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::QuoteLongTerm {
+            token_id: "l".into(),
+            renting_period: period(100, 160),
+            guests: 1,
+        },
+    );
+    assert_eq!(
+        (
+            quote.rent_amount.u128(),
+            quote.fee.u128(),
+            quote.total.u128(),
+            quote.denom.as_str()
+        ),
+        (1000, 10, 1030, "u")
+    );
+    let steps: Vec<_> = quote
+        .refund_schedule
+        .iter()
+        .map(|s| (s.cancel_by, s.percentage, s.refund.u128()))
+        .collect();
+    // the rent is never refunded, whatever the cancellation policy says
+    assert_eq!(steps, vec![(160 * DAY - 1, 0, 30)]);
+
+    assert!(exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(1029, "u"),
+        ExecuteMsg::SetReservationForLongTerm {
+            token_id: "l".into(),
+            renting_period: period(100, 160),
+            guests: 1
+        }
+    )
+    .is_err());
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(1030, "u"),
+        ExecuteMsg::SetReservationForLongTerm {
+            token_id: "l".into(),
+            renting_period: period(100, 160),
+            guests: 1,
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetApproveForLongTerm {
+            token_id: "l".into(),
+            tenant: "bob".into(),
+            renting_period: period(100, 160),
+            approved_date: "d".into(),
+        },
+    )
+    .unwrap();
+    let res = exec(
+        &mut deps,
+        &contract,
+        69 * DAY,
+        "bob",
+        &[],
+        ExecuteMsg::CancelRentalForLongterm {
+            token_id: "l".into(),
+            renting_period: period(100, 160),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        bank_sends(&res),
+        vec![("bob".to_string(), "u".to_string(), 30)]
+    );
+    let res = exec(
+        &mut deps,
+        &contract,
+        70 * DAY,
+        "alice",
+        &[],
+        ExecuteMsg::FinalizeLongTermRental {
+            token_id: "l".into(),
+            tenant: "bob".into(),
+            renting_period: period(100, 160),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        bank_sends(&res),
+        vec![("alice".to_string(), "u".to_string(), 990)]
+    );
+}
+
+#[test]
+fn refund_schedule_saturates_far_deadlines() {
+    let steps = refund_schedule(
+        &[cancel_item(u64::MAX, 100)],
+        20 * DAY,
+        Uint128::new(200),
+        &[],
+    );
+    assert_eq!((steps[0].cancel_by, steps[0].refund.u128()), (0, 200));
+    assert_eq!(
+        refund_percentage(&[cancel_item(u64::MAX - 1, 50)], 20 * DAY, 1),
+        0
+    );
+}
+
+// Access instructions
+
+#[test]
+fn access_instructions_follow_the_stay() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForShortTermRental {
+            token_id: "t".into(),
+            denom: "u".into(),
+            price_per_day: 100,
+            auto_approve: false,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(202, "u"),
+        ExecuteMsg::SetReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: period(10, 12),
+            guests: 1,
+            promo_code: None,
+        },
+    )
+    .unwrap();
+    let secret = Binary::from(b"enc:1234".to_vec());
+    let approve = |data: Option<Binary>| ExecuteMsg::SetApproveForShortTerm {
+        token_id: "t".into(),
+        traveler: "bob".into(),
+        renting_period: period(10, 12),
+        access_instructions: data,
+    };
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            1,
+            "alice",
+            &[],
+            approve(Some(Binary::from(vec![0u8; 4097])))
+        )
+        .unwrap_err(),
+        ContractError::InvalidInput {}
+    );
+    assert!(exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &[],
+        approve(Some(secret.clone()))
+    )
+    .is_err());
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        approve(Some(secret.clone())),
+    )
+    .unwrap();
+    let get = |deps: &MockDeps, who: &str| -> Option<Binary> {
+        query(
+            deps,
+            &contract,
+            1,
+            QueryMsg::AccessInstructions {
+                token_id: "t".into(),
+                traveler: who.into(),
+                renting_period: period(10, 12),
+            },
+        )
+    };
+    assert_eq!(get(&deps, "bob"), Some(secret));
+    assert_eq!(get(&deps, "carl"), None);
+    let r: RentalsResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::NftRentals {
+            token_id: "t".into(),
+        },
+    );
+    assert!(r.rentals[0].approved && r.rentals[0].access_instructions.is_none());
+
+    // approving again replaces, without instructions it is rejected as before
+    assert_eq!(
+        exec(&mut deps, &contract, 1, "alice", &[], approve(None)).unwrap_err(),
+        ContractError::ApprovedAlready {}
+    );
+    let updated = Binary::from(b"enc:5678".to_vec());
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        approve(Some(updated.clone())),
+    )
+    .unwrap();
+    assert_eq!(get(&deps, "bob"), Some(updated));
+
+    exec(
+        &mut deps,
+        &contract,
+        12 * DAY,
+        "alice",
+        &[],
+        ExecuteMsg::FinalizeShortTermRental {
+            token_id: "t".into(),
+            traveler: "bob".into(),
+            renting_period: period(10, 12),
+        },
+    )
+    .unwrap();
+    assert_eq!(get(&deps, "bob"), None);
+
+    // cancelling wipes them right away
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "carl",
+        &coins(202, "u"),
+        ExecuteMsg::SetReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: period(20, 22),
+            guests: 1,
+            promo_code: None,
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetApproveForShortTerm {
+            token_id: "t".into(),
+            traveler: "carl".into(),
+            renting_period: period(20, 22),
+            access_instructions: Some(Binary::from(b"x".to_vec())),
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        2,
+        "carl",
+        &[],
+        ExecuteMsg::CancelRentalForShortterm {
+            token_id: "t".into(),
+            renting_period: period(20, 22),
+        },
+    )
+    .unwrap();
+    let v: Option<Binary> = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::AccessInstructions {
+            token_id: "t".into(),
+            traveler: "carl".into(),
+            renting_period: period(20, 22),
+        },
+    );
+    assert_eq!(v, None);
+}
+
+// Instant booking
+
+/// Whether the reservation of `who` on "t" is approved
+fn approved(deps: &MockDeps, contract: &Contract, who: &str) -> bool {
+    let r: RentalsResponse = query(
+        deps,
+        contract,
+        1,
+        QueryMsg::NftRentals {
+            token_id: "t".into(),
+        },
+    );
+    r.rentals
+        .iter()
+        .find(|r| r.address.as_ref().unwrap().as_str() == who)
+        .unwrap()
+        .approved
+}
+
+#[test]
+fn instant_book_rules_gate_auto_approval() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForShortTermRental {
+            token_id: "t".into(),
+            denom: "u".into(),
+            price_per_day: 100,
+            auto_approve: true,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    let rules = InstantBookRules {
+        require_kyc: true,
+        min_completed_stays: 1,
+        min_nights: None,
+        max_nights: Some(3),
+        max_guests: Some(2),
+    };
+    assert!(exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &[],
+        ExecuteMsg::SetInstantBookRulesForShortTerm {
+            token_id: "t".into(),
+            rules: Some(rules.clone())
+        }
+    )
+    .is_err());
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetInstantBookRulesForShortTerm {
+            token_id: "t".into(),
+            rules: Some(rules),
+        },
+    )
+    .unwrap();
+    let reserve = |a, b, guests| ExecuteMsg::SetReservationForShortTerm {
+        token_id: "t".into(),
+        renting_period: period(a, b),
+        guests,
+        promo_code: None,
+    };
+
+    // no attestation, no stays
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(202, "u"),
+        reserve(10, 12, 1),
+    )
+    .unwrap();
+    assert!(!approved(&deps, &contract, "bob"));
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetApproveForShortTerm {
+            token_id: "t".into(),
+            traveler: "bob".into(),
+            renting_period: period(10, 12),
+            access_instructions: None,
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        12 * DAY,
+        "alice",
+        &[],
+        ExecuteMsg::FinalizeShortTermRental {
+            token_id: "t".into(),
+            traveler: "bob".into(),
+            renting_period: period(10, 12),
+        },
+    )
+    .unwrap();
+
+    assert!(exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &[],
+        ExecuteMsg::SetKycAttestation {
+            traveler: "bob".into(),
+            attested: true
+        }
+    )
+    .is_err());
+    exec(
+        &mut deps,
+        &contract,
+        5,
+        MINTER,
+        &[],
+        ExecuteMsg::SetKycAttestation {
+            traveler: "bob".into(),
+            attested: true,
+        },
+    )
+    .unwrap();
+    let p: TravelerProfile = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::TravelerProfile {
+            traveler: "bob".into(),
+        },
+    );
+    assert_eq!((p.kyc_attested_at, p.completed_stays), (Some(5), 1));
+
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(505, "u"),
+        reserve(20, 25, 1),
+    )
+    .unwrap();
+    assert!(!approved(&deps, &contract, "bob"), "too long");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &[],
+        ExecuteMsg::CancelRentalForShortterm {
+            token_id: "t".into(),
+            renting_period: period(20, 25),
+        },
+    )
+    .unwrap_err();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(2000, "u"),
+        reserve(30, 32, 3),
+    )
+    .unwrap();
+    let r: RentalsResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::NftRentals {
+            token_id: "t".into(),
+        },
+    );
+    assert!(r.rentals.iter().all(|r| !r.approved), "too many guests");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(2000, "u"),
+        reserve(40, 42, 2),
+    )
+    .unwrap();
+    let r: RentalsResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::NftRentals {
+            token_id: "t".into(),
+        },
+    );
+    assert!(
+        r.rentals
+            .iter()
+            .find(|r| r.renting_period[0] == 40 * DAY)
+            .unwrap()
+            .approved
+    );
+
+    // modify re-evaluates against the new length
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(1000, "u"),
+        ExecuteMsg::ModifyReservation {
+            token_id: "t".into(),
+            renting_period: period(40, 42),
+            new_renting_period: period(40, 46),
+        },
+    )
+    .unwrap();
+    let r: RentalsResponse = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::NftRentals {
+            token_id: "t".into(),
+        },
+    );
+    assert!(
+        !r.rentals
+            .iter()
+            .find(|r| r.renting_period[0] == 40 * DAY)
+            .unwrap()
+            .approved
+    );
+
+    exec(
+        &mut deps,
+        &contract,
+        5,
+        MINTER,
+        &[],
+        ExecuteMsg::SetKycAttestation {
+            traveler: "bob".into(),
+            attested: false,
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetInstantBookRulesForShortTerm {
+            token_id: "t".into(),
+            rules: None,
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "carl",
+        &coins(202, "u"),
+        reserve(50, 52, 1),
+    )
+    .unwrap();
+    assert!(approved(&deps, &contract, "carl"));
+}
+
+#[test]
+fn auto_approved_long_term_stays_pay_the_landlord() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "l", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForLongTermRental {
+            token_id: "l".into(),
+            denom: "u".into(),
+            price_per_month: 1000,
+            auto_approve: true,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        5,
+        "bob",
+        &coins(1000, "u"),
+        ExecuteMsg::SetReservationForLongTerm {
+            token_id: "l".into(),
+            renting_period: period(40, 70),
+            guests: 1,
+        },
+    )
+    .unwrap();
+    let r: RentalsResponse = query(
+        &deps,
+        &contract,
+        5,
+        QueryMsg::NftRentals {
+            token_id: "l".into(),
+        },
+    );
+    assert!(r.rentals[0].approved);
+    assert_eq!(r.rentals[0].approved_date, Some("5".to_string()));
+
+    let res = exec(
+        &mut deps,
+        &contract,
+        70 * DAY,
+        "alice",
+        &[],
+        ExecuteMsg::FinalizeLongTermRental {
+            token_id: "l".into(),
+            tenant: "bob".into(),
+            renting_period: period(40, 70),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        bank_sends(&res),
+        vec![("alice".to_string(), "u".to_string(), 990)]
+    );
+}
+
+#[test]
+fn only_paid_stays_with_distinct_other_hosts_count() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForShortTermRental {
+            token_id: "t".into(),
+            denom: "u".into(),
+            price_per_day: 100,
+            auto_approve: true,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::CreatePromoCode {
+            code: "FREE".into(),
+            token_id: Some("t".into()),
+            discount: PromoDiscount::Percent { bps: 10000 },
+            max_uses: None,
+            expires: None,
+        },
+    )
+    .unwrap();
+    book_short(&mut deps, &contract, "alice", 10 * DAY, 12 * DAY).unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(1, "u"),
+        ExecuteMsg::SetReservationForShortTerm {
+            token_id: "t".into(),
+            renting_period: period(20, 22),
+            guests: 1,
+            promo_code: Some("FREE".into()),
+        },
+    )
+    .unwrap();
+    for (traveler, check_in, check_out) in [("alice", 10, 12), ("bob", 20, 22)] {
+        exec(
+            &mut deps,
+            &contract,
+            check_out * DAY,
+            "alice",
+            &[],
+            ExecuteMsg::FinalizeShortTermRental {
+                token_id: "t".into(),
+                traveler: traveler.into(),
+                renting_period: period(check_in, check_out),
+            },
+        )
+        .unwrap();
+        let p: TravelerProfile = query(
+            &deps,
+            &contract,
+            1,
+            QueryMsg::TravelerProfile {
+                traveler: traveler.into(),
+            },
+        );
+        assert_eq!(p.completed_stays, 0);
+        assert_eq!(
+            exec(
+                &mut deps,
+                &contract,
+                check_out * DAY,
+                traveler,
+                &[],
+                ExecuteMsg::ReviewStay {
+                    token_id: "t".into(),
+                    renting_period: period(check_in, check_out),
+                    rating: 5,
+                    review_hash: "h".into()
+                }
+            )
+            .unwrap_err(),
+            ContractError::NoStay {}
+        );
+    }
+
+    // repeat stays with the same host count once
+    for (check_in, check_out) in [(30, 32), (40, 42)] {
+        book_short(
+            &mut deps,
+            &contract,
+            "carl",
+            check_in * DAY,
+            check_out * DAY,
+        )
+        .unwrap();
+        exec(
+            &mut deps,
+            &contract,
+            check_out * DAY,
+            "alice",
+            &[],
+            ExecuteMsg::FinalizeShortTermRental {
+                token_id: "t".into(),
+                traveler: "carl".into(),
+                renting_period: period(check_in, check_out),
+            },
+        )
+        .unwrap();
+    }
+    let p: TravelerProfile = query(
+        &deps,
+        &contract,
+        1,
+        QueryMsg::TravelerProfile {
+            traveler: "carl".into(),
+        },
+    );
+    assert_eq!(p.completed_stays, 1);
+}
+
+// Booking windows
+
+#[test]
+fn booking_window_limits_short_term_stays() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "t", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForShortTermRental {
+            token_id: "t".into(),
+            denom: "u".into(),
+            price_per_day: 100,
+            auto_approve: true,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    let window = BookingWindow {
+        advance_notice: 2 * DAY,
+        booking_horizon: Some(100 * DAY),
+        buffer_days: 2,
+    };
+    assert!(exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &[],
+        ExecuteMsg::SetBookingWindowForShortTerm {
+            token_id: "t".into(),
+            booking_window: window.clone()
+        }
+    )
+    .is_err());
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetBookingWindowForShortTerm {
+            token_id: "t".into(),
+            booking_window: window,
+        },
+    )
+    .unwrap();
+    let reserve = |a, b| ExecuteMsg::SetReservationForShortTerm {
+        token_id: "t".into(),
+        renting_period: period(a, b),
+        guests: 1,
+        promo_code: None,
+    };
+
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            9 * DAY,
+            "bob",
+            &coins(202, "u"),
+            reserve(10, 12)
+        )
+        .unwrap_err(),
+        ContractError::AdvanceNoticeRequired {}
+    );
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            1,
+            "bob",
+            &coins(202, "u"),
+            reserve(200, 202)
+        )
+        .unwrap_err(),
+        ContractError::BeyondBookingHorizon {}
+    );
+    exec(
+        &mut deps,
+        &contract,
+        8 * DAY,
+        "bob",
+        &coins(202, "u"),
+        reserve(10, 12),
+    )
+    .unwrap();
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            1,
+            "carl",
+            &coins(202, "u"),
+            reserve(13, 15)
+        )
+        .unwrap_err(),
+        ContractError::BufferDaysRequired {}
+    );
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            1,
+            "carl",
+            &coins(202, "u"),
+            reserve(7, 9)
+        )
+        .unwrap_err(),
+        ContractError::BufferDaysRequired {}
+    );
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            1,
+            "carl",
+            &coins(202, "u"),
+            reserve(11, 13)
+        )
+        .unwrap_err(),
+        ContractError::UnavailablePeriod {}
+    );
+    // stays may start and end right at the edge of the buffer
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "carl",
+        &coins(202, "u"),
+        reserve(14, 16),
+    )
+    .unwrap();
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "dan",
+        &coins(202, "u"),
+        reserve(6, 8),
+    )
+    .unwrap();
+
+    // moving a stay is held to the same rules
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            1,
+            "carl",
+            &coins(1000, "u"),
+            ExecuteMsg::ModifyReservation {
+                token_id: "t".into(),
+                renting_period: period(14, 16),
+                new_renting_period: period(13, 16)
+            }
+        )
+        .unwrap_err(),
+        ContractError::BufferDaysRequired {}
+    );
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "carl",
+        &coins(1000, "u"),
+        ExecuteMsg::ModifyReservation {
+            token_id: "t".into(),
+            renting_period: period(14, 16),
+            new_renting_period: period(14, 17),
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn booking_window_limits_long_term_stays() {
+    let (mut deps, contract) = setup_market();
+    mint_token(&mut deps, &contract, "l", "alice");
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetListForLongTermRental {
+            token_id: "l".into(),
+            denom: "u".into(),
+            price_per_month: 1000,
+            auto_approve: true,
+            available_period: vec![],
+            minimum_stay: 1,
+            cancellation: vec![],
+        },
+    )
+    .unwrap();
+    let window = BookingWindow {
+        advance_notice: 2 * DAY,
+        booking_horizon: Some(150 * DAY),
+        buffer_days: 0,
+    };
+    assert!(exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &[],
+        ExecuteMsg::SetBookingWindowForLongTerm {
+            token_id: "l".into(),
+            booking_window: window.clone()
+        }
+    )
+    .is_err());
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetBookingWindowForLongTerm {
+            token_id: "l".into(),
+            booking_window: window,
+        },
+    )
+    .unwrap();
+    let reserve = |a, b| ExecuteMsg::SetReservationForLongTerm {
+        token_id: "l".into(),
+        renting_period: period(a, b),
+        guests: 1,
+    };
+
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            99 * DAY,
+            "bob",
+            &coins(1000, "u"),
+            reserve(100, 160)
+        )
+        .unwrap_err(),
+        ContractError::AdvanceNoticeRequired {}
+    );
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "bob",
+        &coins(1000, "u"),
+        reserve(100, 160),
+    )
+    .unwrap();
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            1,
+            "bob",
+            &[],
+            ExecuteMsg::ModifyReservation {
+                token_id: "l".into(),
+                renting_period: period(100, 160),
+                new_renting_period: period(200, 260)
+            }
+        )
+        .unwrap_err(),
+        ContractError::BeyondBookingHorizon {}
+    );
+
+    // windows too large to add up do not overflow
+    let window = BookingWindow {
+        advance_notice: u64::MAX,
+        booking_horizon: Some(u64::MAX),
+        buffer_days: u64::MAX,
+    };
+    exec(
+        &mut deps,
+        &contract,
+        1,
+        "alice",
+        &[],
+        ExecuteMsg::SetBookingWindowForLongTerm {
+            token_id: "l".into(),
+            booking_window: window,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        exec(
+            &mut deps,
+            &contract,
+            1,
+            "carl",
+            &coins(1000, "u"),
+            reserve(200, 260)
+        )
+        .unwrap_err(),
+        ContractError::AdvanceNoticeRequired {}
+    );
+}
@@ -40,12 +40,54 @@ pub enum ContractError {
     #[error("Active bids exists")]
     BidsActive {},
 
+    #[error("No counter-offer for this bid")]
+    NoCounterOffer {},
+
+    #[error("Auction is in progress")]
+    AuctionActive {},
+
+    #[error("Auction has not started yet")]
+    AuctionNotStarted {},
+
+    #[error("Auction has ended")]
+    AuctionEnded {},
+
+    #[error("Auction has not ended yet")]
+    AuctionNotEnded {},
+
+    #[error("Offer not found")]
+    NoOffer {},
+
+    #[error("Offer does not match this property")]
+    OfferMismatch {},
+
+    #[error("Escrowed sale is in progress")]
+    EscrowActive {},
+
+    #[error("No escrowed sale for this property")]
+    NoEscrow {},
+
+    #[error("Not the buyer of this escrowed sale")]
+    NotBuyer {},
+
+    #[error("Property is fractionalized")]
+    Fractionalized {},
+
+    #[error("Property is not fractionalized")]
+    NotFractionalized {},
+
+    #[error("Insufficient shares")]
+    InsufficientShares {},
+
     #[error("Approved already")]
     ApprovedAlready {},
 
     #[error("Invalid deposit denom")]
     InvalidDeposit {},
 
+    #[error("CW20 token not accepted")]
+    Cw20NotAllowed {},
+
     #[error("Someone is renting this property already")]
     AlreadyReserved {},
 
@@ -61,6 +103,81 @@ pub enum ContractError {
     #[error("Someone reserved this period already")]
     UnavailablePeriod {},
 
+    #[error("Property is not available for these dates")]
+    NotAvailable {},
+
+    #[error("Too many guests for this property")]
+    TooManyGuests {},
+
+    #[error("Check-in is sooner than the listing's advance notice")]
+    AdvanceNoticeRequired {},
+
+    #[error("Check-in is beyond the listing's booking horizon")]
+    BeyondBookingHorizon {},
+
+    #[error("Not enough buffer days to the neighbouring stays")]
+    BufferDaysRequired {},
+
+    #[error("No damage deposit held for this stay")]
+    NoDamageDeposit {},
+
+    #[error("No damage claimed against this deposit")]
+    NoDamageClaim {},
+
+    #[error("Claim window is closed")]
+    ClaimWindowClosed {},
+
+    #[error("Claim window is still open")]
+    ClaimWindowOpen {},
+
+    #[error("Damage claim is disputed")]
+    DamageDisputed {},
+
+    #[error("Damage claim is not disputed")]
+    NotDisputed {},
+
+    #[error("Damage deposits are still held for this property")]
+    DamageDepositHeld {},
+
+    #[error("Not the traveler of this stay")]
+    NotTraveler {},
+
+    #[error("No such group reservation")]
+    NoGroupReservation {},
+
+    #[error("No unpaid share for this payer")]
+    NotPayer {},
+
+    #[error("Funding deadline has passed")]
+    DeadlinePassed {},
+
+    #[error("Group reservation is still open for funding")]
+    FundingOpen {},
+
+    #[error("Host response deadline has not passed")]
+    NotExpired {},
+
+    #[error("Host has too many cancellations to list")]
+    HostSuspended {},
+
+    #[error("Host bond is below the minimum")]
+    HostBondTooLow {},
+
+    #[error("Host bond does not cover the platform fee to refund")]
+    FeeNotCovered {},
+
+    #[error("No completed stay to review")]
+    NoStay {},
+
+    #[error("Stay reviewed already")]
+    AlreadyReviewed {},
+
+    #[error("Review window has closed")]
+    ReviewWindowClosed {},
+
+    #[error("Promo code is unknown, expired, used up or not valid for this token")]
+    InvalidPromoCode {},
+
     #[error("Invalid input")]
     InvalidInput {},
 
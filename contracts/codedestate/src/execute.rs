@@ -4,25 +4,65 @@ use serde::de::DeserializeOwned;
 use serde::Serialize;
 
 use cosmwasm_std::{
-    Addr, BankMsg, Coin, CustomMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128,
+    from_binary, to_binary, Addr, BankMsg, Binary, BlockInfo, Coin, CosmosMsg, CustomMsg,
+    Decimal256, Deps, DepsMut, Empty, Env, MessageInfo, Order, Response, StdResult, Storage,
+    Uint128, Uint256, WasmMsg,
 };
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_storage_plus::Bound;
 
 use cw721::{
+    fees_total,
+    refund_percentage,
+    refund_schedule,
+    Auction,
+    AvailabilityCalendar,
     Bid,
+    BookingWindow,
     CancellationItem,
+    ChargedFee,
+    CompletedStay,
     ContractInfoResponse,
     Cw721Execute,
     // Cw721ReceiveMsg,
+    DamageDeposit,
+    DateRange,
+    DutchAuction,
+    EscrowSale,
     Expiration,
+    FeeCharge,
+    FeeLine,
+    FeeRecipient,
+    GroupReservation,
+    HostPolicy,
+    InstantBookRules,
+    LongTermQuote,
     LongTermRental,
+    Offer,
+    PayerShare,
+    PriceCondition,
+    PriceRule,
+    PromoCode,
+    PromoDiscount,
+    RefundStep,
     Rental,
+    Review,
+    Royalty,
     Sell,
+    ShortTermQuote,
     ShortTermRental,
+    REVIEW_WINDOW,
 };
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg};
+use crate::msg::{ExecuteMsg, InstantiateMsg, ReceiveMsg};
 use crate::state::{Approval, Cw721Contract, Owner, TokenInfo};
+use crate::{Extension, Trait};
+
+/// Smallest earnest deposit of an escrowed sale, in basis points of the sale price
+const MIN_ESCROW_DEPOSIT: u64 = 1000;
+/// Longest inspection or closing period a buyer can ask for, in seconds
+const MAX_ESCROW_PERIOD: u64 = 365 * 86400;
 
 impl<'a, T, C, E, Q> Cw721Contract<'a, T, C, E, Q>
 where
@@ -61,7 +101,18 @@ where
                 owner,
                 token_uri,
                 extension,
-            } => self.mint(deps, info, token_id, owner, token_uri, extension),
+                royalty,
+            } => self.mint(deps, info, token_id, owner, token_uri, extension, royalty),
+
+            ExecuteMsg::SetDefaultRoyalty { royalty } => {
+                self.set_default_royalty(deps, info, royalty)
+            }
+            ExecuteMsg::SetCw20Token { address, allowed } => {
+                self.set_cw20_token(deps, info, address, allowed)
+            }
+            ExecuteMsg::IndexBids { start_after, limit } => {
+                self.index_bids(deps, info, start_after, limit)
+            }
 
             ExecuteMsg::SetMetadata {
                 token_id,
@@ -90,12 +141,124 @@ where
                 auto_approve,
             ),
 
-            ExecuteMsg::SetBidToBuy { token_id } => self.setbidtobuy(deps, env, info, token_id),
+            ExecuteMsg::SetBidToBuy { token_id, expires } => {
+                self.setbidtobuy(deps, env, info, token_id, expires)
+            }
 
             ExecuteMsg::RejectBid { token_id, bidder } => {
                 self.rejectbid(deps, env, info, token_id, bidder)
             }
 
+            ExecuteMsg::SetCounterOffer {
+                token_id,
+                bidder,
+                amount,
+            } => self.setcounteroffer(deps, env, info, token_id, bidder, amount),
+
+            ExecuteMsg::AcceptCounterOffer { token_id } => {
+                self.acceptcounteroffer(deps, env, info, token_id)
+            }
+
+            ExecuteMsg::ReclaimExpiredBids { token_id } => {
+                self.reclaimexpiredbids(deps, env, info, token_id)
+            }
+
+            ExecuteMsg::SetListForAuction {
+                token_id,
+                denom,
+                reserve_price,
+                start_time,
+                end_time,
+                minimum_increment,
+                extension_window,
+            } => self.setlistforauction(
+                deps,
+                env,
+                info,
+                token_id,
+                denom,
+                reserve_price,
+                start_time,
+                end_time,
+                minimum_increment,
+                extension_window,
+            ),
+
+            ExecuteMsg::SetUnlistForAuction { token_id } => {
+                self.setunlistforauction(deps, env, info, token_id)
+            }
+
+            ExecuteMsg::SetBidForAuction { token_id } => {
+                self.setbidforauction(deps, env, info, token_id)
+            }
+
+            ExecuteMsg::SettleAuction { token_id } => self.settleauction(deps, env, info, token_id),
+
+            ExecuteMsg::SetListForDutchAuction {
+                token_id,
+                denom,
+                start_price,
+                floor_price,
+                start_time,
+                end_time,
+                step_interval,
+            } => self.setlistfordutchauction(
+                deps,
+                env,
+                info,
+                token_id,
+                denom,
+                start_price,
+                floor_price,
+                start_time,
+                end_time,
+                step_interval,
+            ),
+
+            ExecuteMsg::SetUnlistForDutchAuction { token_id } => {
+                self.setunlistfordutchauction(deps, env, info, token_id)
+            }
+
+            ExecuteMsg::BuyDutchAuction { token_id } => {
+                self.buydutchauction(deps, env, info, token_id)
+            }
+
+            ExecuteMsg::MakeOffer {
+                token_id,
+                traits,
+                expires,
+            } => self.makeoffer(deps, env, info, token_id, traits, expires),
+            ExecuteMsg::CancelOffer { offer_id } => self.canceloffer(deps, env, info, offer_id),
+            ExecuteMsg::AcceptOffer { offer_id, token_id } => {
+                self.acceptoffer(deps, env, info, offer_id, token_id)
+            }
+
+            ExecuteMsg::OfferEscrowSale {
+                token_id,
+                price,
+                inspection_period,
+                closing_period,
+                penalty,
+            } => self.offerescrowsale(
+                deps,
+                env,
+                info,
+                token_id,
+                price,
+                inspection_period,
+                closing_period,
+                penalty,
+            ),
+            ExecuteMsg::AcceptEscrowSale { token_id } => {
+                self.acceptescrowsale(deps, env, info, token_id)
+            }
+            ExecuteMsg::CloseEscrowSale { token_id } => {
+                self.closeescrowsale(deps, env, info, token_id)
+            }
+            ExecuteMsg::CancelEscrowSale { token_id } => {
+                self.cancelescrowsale(deps, env, info, token_id)
+            }
+
             ExecuteMsg::SetListForShortTermRental {
                 token_id,
                 denom,
@@ -117,6 +280,28 @@ where
                 cancellation,
             ),
 
+            ExecuteMsg::SetAvailabilityForShortTerm {
+                token_id,
+                availability,
+            } => self.setavailabilityforshortterm(deps, env, info, token_id, availability),
+            ExecuteMsg::SetPriceRulesForShortTerm {
+                token_id,
+                price_rules,
+            } => self.setpricerulesforshortterm(deps, env, info, token_id, price_rules),
+            ExecuteMsg::SetGuestPolicyForShortTerm {
+                token_id,
+                max_guests,
+                included_guests,
+                extra_guest_fee,
+            } => self.setguestpolicyforshortterm(
+                deps,
+                env,
+                info,
+                token_id,
+                max_guests,
+                included_guests,
+                extra_guest_fee,
+            ),
             ExecuteMsg::SetUnlistForShorttermRental { token_id } => {
                 self.setunlistforshorttermrental(deps, env, info, token_id)
             }
@@ -124,7 +309,16 @@ where
                 token_id,
                 renting_period,
                 guests,
-            } => self.setreservationforshortterm(deps, info, token_id, renting_period, guests),
+                promo_code,
+            } => self.setreservationforshortterm(
+                deps,
+                env,
+                info,
+                token_id,
+                renting_period,
+                guests,
+                promo_code,
+            ),
             ExecuteMsg::RejectReservationForShortterm {
                 token_id,
                 traveler,
@@ -148,6 +342,100 @@ where
                 renting_period,
             ),
 
+            ExecuteMsg::ReviewStay {
+                token_id,
+                renting_period,
+                rating,
+                review_hash,
+            } => self.reviewstay(
+                deps,
+                env,
+                info,
+                token_id,
+                renting_period,
+                rating,
+                review_hash,
+            ),
+            ExecuteMsg::CancelReservationByHost {
+                token_id,
+                traveler,
+                renting_period,
+            } => self.cancelreservationbyhost(deps, env, info, token_id, traveler, renting_period),
+            ExecuteMsg::PostHostBond {} => self.posthostbond(deps, info),
+            ExecuteMsg::WithdrawHostBond {} => self.withdrawhostbond(deps, env, info),
+            ExecuteMsg::SetHostPolicy { policy } => self.sethostpolicy(deps, info, policy),
+            ExecuteMsg::SetResponseWindowForShortTerm {
+                token_id,
+                response_window,
+            } => self.setresponsewindowforshortterm(deps, env, info, token_id, response_window),
+            ExecuteMsg::SetResponseWindowForLongTerm {
+                token_id,
+                response_window,
+            } => self.setresponsewindowforlongterm(deps, env, info, token_id, response_window),
+            ExecuteMsg::SetFeeLinesForShortTerm {
+                token_id,
+                fee_lines,
+            } => self.setfeelinesforshortterm(deps, env, info, token_id, fee_lines),
+            ExecuteMsg::SetFeeLinesForLongTerm {
+                token_id,
+                fee_lines,
+            } => self.setfeelinesforlongterm(deps, env, info, token_id, fee_lines),
+            ExecuteMsg::SetInstantBookRulesForShortTerm { token_id, rules } => {
+                self.setinstantbookrulesforshortterm(deps, env, info, token_id, rules)
+            }
+            ExecuteMsg::SetInstantBookRulesForLongTerm { token_id, rules } => {
+                self.setinstantbookrulesforlongterm(deps, env, info, token_id, rules)
+            }
+            ExecuteMsg::SetBookingWindowForShortTerm {
+                token_id,
+                booking_window,
+            } => self.setbookingwindowforshortterm(deps, env, info, token_id, booking_window),
+            ExecuteMsg::SetBookingWindowForLongTerm {
+                token_id,
+                booking_window,
+            } => self.setbookingwindowforlongterm(deps, env, info, token_id, booking_window),
+            ExecuteMsg::SetKycAttestation { traveler, attested } => {
+                self.setkycattestation(deps, env, info, traveler, attested)
+            }
+            ExecuteMsg::ExpireReservation {
+                token_id,
+                traveler,
+                renting_period,
+            } => self.expirereservation(deps, env, info, token_id, traveler, renting_period),
+            ExecuteMsg::ModifyReservation {
+                token_id,
+                renting_period,
+                new_renting_period,
+            } => self.modifyreservation(
+                deps,
+                env,
+                info,
+                token_id,
+                renting_period,
+                new_renting_period,
+            ),
+            ExecuteMsg::CreateGroupReservationForShortTerm {
+                token_id,
+                renting_period,
+                guests,
+                shares,
+                deadline,
+            } => self.creategroupreservationforshortterm(
+                deps,
+                env,
+                info,
+                token_id,
+                renting_period,
+                guests,
+                shares,
+                deadline,
+            ),
+            ExecuteMsg::FundGroupReservation { group_id } => {
+                self.fundgroupreservation(deps, env, info, group_id)
+            }
+            ExecuteMsg::RefundGroupReservation { group_id } => {
+                self.refundgroupreservation(deps, env, info, group_id)
+            }
             ExecuteMsg::CancelReservationForShortterm {
                 token_id,
                 renting_period,
@@ -162,13 +450,76 @@ where
                 token_id,
                 traveler,
                 renting_period,
-            } => self.setapproveforshortterm(deps, info, env, token_id, renting_period, traveler),
+                access_instructions,
+            } => self.setapproveforshortterm(
+                deps,
+                info,
+                env,
+                token_id,
+                renting_period,
+                traveler,
+                access_instructions,
+            ),
 
             ExecuteMsg::FinalizeShortTermRental {
                 token_id,
                 traveler,
                 renting_period,
             } => self.finalizeshorttermrental(deps, env, info, token_id, traveler, renting_period),
+            ExecuteMsg::SetDamageDepositForShortTerm {
+                token_id,
+                damage_deposit,
+                claim_window,
+            } => self.setdamagedepositforshortterm(
+                deps,
+                env,
+                info,
+                token_id,
+                damage_deposit,
+                claim_window,
+            ),
+            ExecuteMsg::SetStayDiscountsForShortTerm {
+                token_id,
+                weekly_discount,
+                monthly_discount,
+            } => self.setstaydiscountsforshortterm(
+                deps,
+                env,
+                info,
+                token_id,
+                weekly_discount,
+                monthly_discount,
+            ),
+            ExecuteMsg::SetPlatformPromosForShortTerm { token_id, accept } => {
+                self.setplatformpromosforshortterm(deps, env, info, token_id, accept)
+            }
+            ExecuteMsg::CreatePromoCode {
+                code,
+                token_id,
+                discount,
+                max_uses,
+                expires,
+            } => self.createpromocode(deps, env, info, code, token_id, discount, max_uses, expires),
+            ExecuteMsg::RevokePromoCode { code } => self.revokepromocode(deps, info, code),
+            ExecuteMsg::ClaimDamageDeposit {
+                token_id,
+                renting_period,
+                amount,
+                reason,
+            } => self.claimdamagedeposit(deps, env, info, token_id, renting_period, amount, reason),
+            ExecuteMsg::DisputeDamageClaim {
+                token_id,
+                renting_period,
+            } => self.disputedamageclaim(deps, env, info, token_id, renting_period),
+            ExecuteMsg::ResolveDamageDispute {
+                token_id,
+                renting_period,
+                amount,
+            } => self.resolvedamagedispute(deps, info, token_id, renting_period, amount),
+            ExecuteMsg::ReleaseDamageDeposit {
+                token_id,
+                renting_period,
+            } => self.releasedamagedeposit(deps, env, info, token_id, renting_period),
 
             ExecuteMsg::SetListForLongTermRental {
                 token_id,
@@ -191,6 +542,20 @@ where
                 cancellation,
             ),
 
+            ExecuteMsg::SetGuestPolicyForLongTerm {
+                token_id,
+                max_guests,
+                included_guests,
+                extra_guest_fee,
+            } => self.setguestpolicyforlongterm(
+                deps,
+                env,
+                info,
+                token_id,
+                max_guests,
+                included_guests,
+                extra_guest_fee,
+            ),
             ExecuteMsg::SetUnlistForLongtermRental { token_id } => {
                 self.setunlistforlongtermrental(deps, env, info, token_id)
             }
@@ -199,7 +564,7 @@ where
                 token_id,
                 renting_period,
                 guests,
-            } => self.setreservationforlongterm(deps, info, token_id, renting_period, guests),
+            } => self.setreservationforlongterm(deps, env, info, token_id, renting_period, guests),
 
             ExecuteMsg::CancelReservationForLongterm {
                 token_id,
@@ -269,8 +634,21 @@ where
                 address,
             ),
 
+            ExecuteMsg::Fractionalize { token_id, shares } => {
+                self.fractionalize(deps, env, info, token_id, shares)
+            }
+            ExecuteMsg::TransferShares {
+                token_id,
+                recipient,
+                amount,
+            } => self.transfershares(deps, info, token_id, recipient, amount),
+            ExecuteMsg::RedeemShares { token_id } => self.redeemshares(deps, info, token_id),
+            ExecuteMsg::ClaimRewards { token_id } => self.claimrewards(deps, info, token_id),
+
             ExecuteMsg::Withdraw { target, amount } => self.withdraw(deps, info, target, amount),
 
+            ExecuteMsg::Receive(msg) => self.receive(deps, env, info, msg),
+
             ExecuteMsg::SetFeeValue { fee } => self.set_fee_value(deps, info, fee),
 
             ExecuteMsg::Approve {
@@ -304,6 +682,7 @@ where
     E: CustomMsg,
     Q: CustomMsg,
 {
+    #[allow(clippy::too_many_arguments)]
     pub fn mint(
         &self,
         deps: DepsMut,
@@ -312,8 +691,12 @@ where
         owner: Owner,
         token_uri: Option<String>,
         extension: T,
+        royalty: Option<Royalty>,
     ) -> Result<Response<C>, ContractError> {
         // cw_ownable::assert_owner(deps.storage, &info.sender)?;
+        if let Some(royalty) = &royalty {
+            self.check_royalty(deps.as_ref(), royalty)?;
+        }
 
         let longterm_rental = LongTermRental {
             islisted: None,
@@ -326,6 +709,13 @@ where
             auto_approve: false,
             cancellation: vec![],
             minimum_stay: 0u64,
+            max_guests: None,
+            included_guests: 0,
+            extra_guest_fee: 0u128,
+            response_window: None,
+            fee_lines: vec![],
+            instant_book: None,
+            booking_window: BookingWindow::default(),
         };
 
         let shortterm_rental = ShortTermRental {
@@ -339,6 +729,20 @@ where
             auto_approve: false,
             cancellation: vec![],
             minimum_stay: 0u64,
+            availability: AvailabilityCalendar::default(),
+            price_rules: vec![],
+            max_guests: None,
+            included_guests: 0,
+            extra_guest_fee: 0u128,
+            damage_deposit: 0u128,
+            claim_window: 0u64,
+            response_window: None,
+            weekly_discount: 0u64,
+            monthly_discount: 0u64,
+            fee_lines: vec![],
+            accepts_platform_promos: false,
+            instant_book: None,
+            booking_window: BookingWindow::default(),
         };
 
         let sell = Sell {
@@ -349,6 +753,29 @@ where
                 .to_string(),
         };
 
+        let auction = Auction {
+            islisted: None,
+            denom: "ibc/F082B65C88E4B6D5EF1DB243CDA1D331D002759E938A0F5CD3FFDC5D53B3E349"
+                .to_string(),
+            reserve_price: 0u128,
+            start_time: 0u64,
+            end_time: 0u64,
+            minimum_increment: 0u128,
+            extension_window: 0u64,
+            highest_bid: None,
+        };
+
+        let dutch_auction = DutchAuction {
+            islisted: None,
+            denom: "ibc/F082B65C88E4B6D5EF1DB243CDA1D331D002759E938A0F5CD3FFDC5D53B3E349"
+                .to_string(),
+            start_price: 0u128,
+            floor_price: 0u128,
+            start_time: 0u64,
+            end_time: 0u64,
+            step_interval: 0u64,
+        };
+
         // create the token
         let token = TokenInfo {
             owner: owner.clone(),
@@ -358,6 +785,11 @@ where
             longterm_rental,
             shortterm_rental,
             sell,
+            auction,
+            dutch_auction,
+            escrow: None,
+            royalty,
+            total_shares: None,
             token_uri,
             extension,
         };
@@ -383,123 +815,304 @@ where
         fee: u64,
     ) -> Result<Response<C>, ContractError> {
         cw_ownable::assert_owner(deps.storage, &info.sender)?;
+        let royalty_share = self
+            .default_royalty
+            .may_load(deps.storage)?
+            .map_or(0, |royalty| royalty.share);
+        if fee.saturating_add(royalty_share) > 10000 {
+            return Err(ContractError::InvalidInput {});
+        }
         self.set_fee(deps.storage, fee)?;
         Ok(Response::new().add_attribute("action", "setfee"))
     }
 
-    pub fn withdraw(
+    pub fn set_cw20_token(
         &self,
         deps: DepsMut,
         info: MessageInfo,
-        target: String,
-        amount: Coin,
+        address: String,
+        allowed: bool,
     ) -> Result<Response<C>, ContractError> {
         cw_ownable::assert_owner(deps.storage, &info.sender)?;
-
-        if amount.amount.clone() > self.get_balance(deps.storage, amount.denom.clone())? {
-            return Err(ContractError::UnavailableAmount {});
-        }
-
-        self.decrease_balance(deps.storage, amount.denom.clone(), amount.amount.clone())?;
-
+        let address = deps.api.addr_validate(&address)?;
+        // disallowed tokens stay known so funds already held in them are still paid out as CW20
+        self.cw20_tokens
+            .save(deps.storage, address.as_str(), &allowed)?;
         Ok(Response::new()
-            .add_attribute("action", "withdraw")
-            .add_message(BankMsg::Send {
-                to_address: target,
-                amount: vec![amount],
-            }))
+            .add_attribute("action", "setcw20token")
+            .add_attribute("address", address)
+            .add_attribute("allowed", allowed.to_string()))
     }
 
-    pub fn update_ownership(
+    pub fn set_default_royalty(
+        &self,
         deps: DepsMut,
-        env: Env,
         info: MessageInfo,
-        action: cw_ownable::Action,
+        royalty: Option<Royalty>,
     ) -> Result<Response<C>, ContractError> {
-        let ownership = cw_ownable::update_ownership(deps, &env.block, &info.sender, action)?;
-        Ok(Response::new().add_attributes(ownership.into_attributes()))
+        cw_ownable::assert_owner(deps.storage, &info.sender)?;
+        match royalty {
+            Some(royalty) => {
+                self.check_royalty(deps.as_ref(), &royalty)?;
+                self.default_royalty.save(deps.storage, &royalty)?;
+            }
+            None => self.default_royalty.remove(deps.storage),
+        }
+        Ok(Response::new().add_attribute("action", "setdefaultroyalty"))
     }
-}
-
-impl<'a, T, C, E, Q> Cw721Execute<T, C> for Cw721Contract<'a, T, C, E, Q>
-where
-    T: Serialize + DeserializeOwned + Clone,
-    C: CustomMsg,
-    E: CustomMsg,
-    Q: CustomMsg,
-{
-    type Err = ContractError;
 
-    fn transfer_nft(
+    /// Adds bids placed before the bidder index existed, `limit` tokens at a time
+    pub fn index_bids(
         &self,
         deps: DepsMut,
-        env: Env,
         info: MessageInfo,
-        recipient: String,
-        token_id: String,
+        start_after: Option<String>,
+        limit: u32,
     ) -> Result<Response<C>, ContractError> {
-        let mut token = self.tokens.load(deps.storage, &token_id)?;
-
-        // if token.owner != info.sender {
-        //     return Err(ContractError::NotOwner {});
-        // }
-
-        self.check_can_approve(deps.as_ref(), &env, &info, &token)?;
-
-        let prev_owner = token.owner.clone();
-        token.owner.address = recipient.clone();
-        token.approvals = vec![];
-        let fee_percentage = self.get_fee(deps.storage)?;
-
-        let mut position: i32 = -1;
-        let mut amount = Uint128::from(0u64);
-        let mut denom = "".to_string();
-        for (i, item) in token.bids.iter().enumerate() {
-            if item.address == recipient.to_string() {
-                position = i as i32;
-                amount = item.offer.into();
-                denom = item.denom.clone();
-                break;
+        cw_ownable::assert_owner(deps.storage, &info.sender)?;
+        let start = start_after.as_deref().map(Bound::exclusive);
+        let tokens = self
+            .tokens
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit as usize)
+            .collect::<StdResult<Vec<_>>>()?;
+        for (token_id, token) in &tokens {
+            for bid in &token.bids {
+                self.bids_by_bidder
+                    .save(deps.storage, (&bid.address, token_id), &true)?;
             }
         }
 
-        if position != -1 && amount > Uint128::new(0) {
-            self.increase_balance(
-                deps.storage,
-                token.sell.denom.clone(),
-                Uint128::new((u128::from(amount) * u128::from(fee_percentage)) / 10000),
-            )?;
+        let mut response = Response::new()
+            .add_attribute("action", "indexbids")
+            .add_attribute("sender", info.sender);
+        // where the next batch starts, absent once every token is indexed
+        if tokens.len() == limit as usize {
+            if let Some((token_id, _)) = tokens.last() {
+                response = response.add_attribute("last_token_id", token_id);
+            }
         }
-        let amount_after_fee = amount
-            .checked_sub(Uint128::new(
-                (u128::from(amount) * u128::from(fee_percentage)) / 10000,
-            ))
-            .unwrap_or_default();
-        token.bids.retain(|bid| bid.address != info.sender);
-        self.tokens.save(deps.storage, &token_id, &token)?;
-        if amount > Uint128::new(0) {
-            Ok(Response::new()
-                .add_attribute("action", "transfer_nft")
-                .add_attribute("sender", info.sender.clone())
-                .add_attribute("token_id", token_id)
-                .add_message(BankMsg::Send {
-                    to_address: prev_owner.address.to_string(),
-                    amount: vec![Coin {
-                        denom: denom,
-                        amount: amount_after_fee,
-                    }],
-                }))
-        } else {
-            Ok(Response::new()
-                .add_attribute("action", "transfer_nft")
-                .add_attribute("sender", info.sender.clone())
-                .add_attribute("token_id", token_id))
+        Ok(response)
+    }
+
+    pub fn withdraw(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        target: String,
+        amount: Coin,
+    ) -> Result<Response<C>, ContractError> {
+        cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+        if amount.amount > self.get_balance(deps.storage, amount.denom.clone())? {
+            return Err(ContractError::UnavailableAmount {});
         }
+
+        self.decrease_balance(deps.storage, amount.denom.clone(), amount.amount)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "withdraw")
+            .add_message(self.payout(deps.storage, target, amount.denom, amount.amount)?))
     }
 
-    // fn send_nft(
-    //     &self,
-    //     deps: DepsMut,
+    /// Entry point for CW20 payments. The token contract address stands in for the denom,
+    /// so it has to match the denom of the listing being paid for.
+    pub fn receive(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        wrapper: Cw20ReceiveMsg,
+    ) -> Result<Response<C>, ContractError> {
+        // native funds sent along a direct call would be lost
+        if !info.funds.is_empty() {
+            return Err(ContractError::InvalidDeposit {});
+        }
+        let denom = info.sender.to_string();
+        if !self
+            .cw20_tokens
+            .may_load(deps.storage, &denom)?
+            .unwrap_or_default()
+        {
+            return Err(ContractError::Cw20NotAllowed {});
+        }
+
+        let info = MessageInfo {
+            sender: deps.api.addr_validate(&wrapper.sender)?,
+            funds: vec![Coin {
+                denom,
+                amount: wrapper.amount,
+            }],
+        };
+        match from_binary(&wrapper.msg)? {
+            ReceiveMsg::SetBidToBuy { token_id, expires } => {
+                self.setbidtobuy(deps, env, info, token_id, expires)
+            }
+            ReceiveMsg::AcceptCounterOffer { token_id } => {
+                self.acceptcounteroffer(deps, env, info, token_id)
+            }
+            ReceiveMsg::SetBidForAuction { token_id } => {
+                self.setbidforauction(deps, env, info, token_id)
+            }
+            ReceiveMsg::BuyDutchAuction { token_id } => {
+                self.buydutchauction(deps, env, info, token_id)
+            }
+            ReceiveMsg::MakeOffer {
+                token_id,
+                traits,
+                expires,
+            } => self.makeoffer(deps, env, info, token_id, traits, expires),
+            ReceiveMsg::OfferEscrowSale {
+                token_id,
+                price,
+                inspection_period,
+                closing_period,
+                penalty,
+            } => self.offerescrowsale(
+                deps,
+                env,
+                info,
+                token_id,
+                price,
+                inspection_period,
+                closing_period,
+                penalty,
+            ),
+            ReceiveMsg::CloseEscrowSale { token_id } => {
+                self.closeescrowsale(deps, env, info, token_id)
+            }
+            ReceiveMsg::SetReservationForShortTerm {
+                token_id,
+                renting_period,
+                guests,
+                promo_code,
+            } => self.setreservationforshortterm(
+                deps,
+                env,
+                info,
+                token_id,
+                renting_period,
+                guests,
+                promo_code,
+            ),
+            ReceiveMsg::SetReservationForLongTerm {
+                token_id,
+                renting_period,
+                guests,
+            } => self.setreservationforlongterm(deps, env, info, token_id, renting_period, guests),
+            ReceiveMsg::PostHostBond {} => self.posthostbond(deps, info),
+            ReceiveMsg::ModifyReservation {
+                token_id,
+                renting_period,
+                new_renting_period,
+            } => self.modifyreservation(
+                deps,
+                env,
+                info,
+                token_id,
+                renting_period,
+                new_renting_period,
+            ),
+            ReceiveMsg::CreateGroupReservationForShortTerm {
+                token_id,
+                renting_period,
+                guests,
+                shares,
+                deadline,
+            } => self.creategroupreservationforshortterm(
+                deps,
+                env,
+                info,
+                token_id,
+                renting_period,
+                guests,
+                shares,
+                deadline,
+            ),
+            ReceiveMsg::FundGroupReservation { group_id } => {
+                self.fundgroupreservation(deps, env, info, group_id)
+            }
+            ReceiveMsg::DepositForLongTermRental {
+                token_id,
+                renting_period,
+            } => self.depositforlongtermrental(deps, info, token_id, renting_period),
+        }
+    }
+
+    pub fn update_ownership(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        action: cw_ownable::Action,
+    ) -> Result<Response<C>, ContractError> {
+        let ownership = cw_ownable::update_ownership(deps, &env.block, &info.sender, action)?;
+        Ok(Response::new().add_attributes(ownership.into_attributes()))
+    }
+}
+
+impl<'a, T, C, E, Q> Cw721Execute<T, C> for Cw721Contract<'a, T, C, E, Q>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    C: CustomMsg,
+    E: CustomMsg,
+    Q: CustomMsg,
+{
+    type Err = ContractError;
+
+    fn transfer_nft(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        recipient: String,
+        token_id: String,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+
+        // if token.owner != info.sender {
+        //     return Err(ContractError::NotOwner {});
+        // }
+
+        self.check_can_approve(deps.as_ref(), &env, &info, &token)?;
+        self.check_can_edit_auction(&env, &token)?;
+        self.check_not_fractionalized(&token)?;
+        self.check_can_edit_escrow(&token)?;
+
+        // transferring to a bidder settles their bid
+        let position = token.bids.iter().position(|bid| bid.address == recipient);
+        let mut messages = match position {
+            Some(position) => {
+                let bid = token.bids.remove(position);
+                if bid.is_expired(&env.block) {
+                    return Err(ContractError::Expired {});
+                }
+                self.bids_by_bidder
+                    .remove(deps.storage, (&bid.address, &token_id));
+                self.settle_sale(deps.storage, &mut token, &recipient, &bid.denom, bid.offer)?
+            }
+            None => {
+                token.owner.address = recipient.clone();
+                token.approvals = vec![];
+                self.lock_host_bond(deps.storage, &recipient, &token.rentals)?;
+                vec![]
+            }
+        };
+        // the other bids were made to the previous owner, who may have countered them
+        messages.extend(self.refund_bids(deps.storage, &token.bids)?);
+        self.unindex_bids(deps.storage, &token_id, &token.bids);
+        token.bids = vec![];
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "transfer_nft")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_messages(messages))
+    }
+
+    // fn send_nft(
+    //     &self,
+    //     deps: DepsMut,
     //     env: Env,
     //     info: MessageInfo,
     //     contract: String,
@@ -618,13 +1231,32 @@ where
         self.check_can_edit_long(&env, &token)?;
         self.check_can_edit_short(&env, &token)?;
         self.check_can_edit_bid(&env, &token)?;
+        self.check_can_edit_auction(&env, &token)?;
+        self.check_not_fractionalized(&token)?;
+        self.check_can_edit_escrow(&token)?;
+        if self
+            .damage_deposits
+            .prefix(&token_id)
+            .keys(deps.storage, None, None, Order::Ascending)
+            .next()
+            .is_some()
+        {
+            return Err(ContractError::DamageDepositHeld {});
+        }
         self.tokens.remove(deps.storage, &token_id)?;
         self.decrement_tokens(deps.storage)?;
 
+        // an offer still pending in escrow is declined
+        let mut messages = vec![];
+        if let Some(escrow) = token.escrow {
+            messages.push(self.payout(deps.storage, escrow.buyer, escrow.denom, escrow.deposit)?);
+        }
+
         Ok(Response::new()
             .add_attribute("action", "burn")
             .add_attribute("sender", info.sender)
-            .add_attribute("token_id", token_id))
+            .add_attribute("token_id", token_id)
+            .add_messages(messages))
     }
 }
 
@@ -672,6 +1304,7 @@ where
         self.check_can_edit_short(&env, &token)?;
         self.check_can_edit_long(&env, &token)?;
         self.check_can_edit_bid(&env, &token)?;
+        self.check_can_edit_auction(&env, &token)?;
         token.token_uri = Some(token_uri);
         self.tokens.save(deps.storage, &token_id, &token)?;
 
@@ -696,6 +1329,7 @@ where
         self.check_can_edit_short(&env, &token)?;
         self.check_can_edit_long(&env, &token)?;
         self.check_can_edit_bid(&env, &token)?;
+        self.check_can_edit_auction(&env, &token)?;
         token.extension = extension;
         self.tokens.save(deps.storage, &token_id, &token)?;
 
@@ -705,6 +1339,7 @@ where
             .add_attribute("token_id", token_id))
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn setlistforsell(
         &self,
         deps: DepsMut,
@@ -720,6 +1355,9 @@ where
         // ensure we have permissions
         self.check_can_send(deps.as_ref(), &env, &info, &token)?;
         self.check_can_edit_bid(&env, &token)?;
+        self.check_can_edit_auction(&env, &token)?;
+        self.check_not_fractionalized(&token)?;
+        self.check_can_edit_escrow(&token)?;
         token.sell.islisted = Some(islisted);
         token.sell.price = price;
         token.sell.auto_approve = auto_approve;
@@ -735,26 +1373,28 @@ where
     pub fn setbidtobuy(
         &self,
         deps: DepsMut,
-        _env: Env,
+        env: Env,
         info: MessageInfo,
         token_id: String,
+        expires: Option<Expiration>,
     ) -> Result<Response<C>, ContractError> {
         let mut token = self.tokens.load(deps.storage, &token_id)?;
+        self.check_can_edit_auction(&env, &token)?;
 
         let mut position: i32 = -1;
         let mut amount = Uint128::from(0u64);
         let mut denom = "".to_string();
         for (i, item) in token.bids.iter().enumerate() {
-            if item.address == info.sender.to_string() {
+            if item.address == info.sender {
                 position = i as i32;
-                amount = item.offer.into();
+                amount = item.offer;
                 denom = item.denom.clone();
                 break;
             }
         }
 
         if position == -1 {
-            if !token.sell.islisted.is_some() {
+            if token.sell.islisted != Some(true) {
                 return Err(ContractError::NotListed {});
             }
             if info.funds[0].denom != token.sell.denom {
@@ -763,15 +1403,58 @@ where
             if info.funds[0].amount < Uint128::from(token.sell.price) {
                 return Err(ContractError::InsufficientDeposit {});
             }
+            if expires.is_some_and(|expires| expires.is_expired(&env.block)) {
+                return Err(ContractError::Expired {});
+            }
+
+            if token.sell.auto_approve {
+                // buy now: the sale settles right away at the listed price and every
+                // other bidder gets refunded
+                let price = Uint128::from(token.sell.price);
+                let sent_amount = info.funds[0].amount;
+                let denom = info.funds[0].denom.clone();
+                let mut messages = self.refund_bids(deps.storage, &token.bids)?;
+                self.unindex_bids(deps.storage, &token_id, &token.bids);
+                token.bids = vec![];
+                messages.extend(self.settle_sale(
+                    deps.storage,
+                    &mut token,
+                    info.sender.as_str(),
+                    &denom,
+                    price,
+                )?);
+                if sent_amount > price {
+                    messages.push(self.payout(
+                        deps.storage,
+                        info.sender.as_str(),
+                        denom,
+                        sent_amount - price,
+                    )?);
+                }
+                self.tokens.save(deps.storage, &token_id, &token)?;
+
+                return Ok(Response::new()
+                    .add_attribute("action", "setbidtobuy")
+                    .add_attribute("sender", info.sender)
+                    .add_attribute("token_id", token_id)
+                    .add_attribute("auto_approve", "true")
+                    .add_messages(messages));
+            }
 
             let bid = Bid {
                 address: info.sender.to_string(),
                 offer: info.funds[0].amount,
                 denom: info.funds[0].denom.to_string(),
+                expires,
+                counter_offer: None,
             };
             token.bids.push(bid);
+            self.bids_by_bidder
+                .save(deps.storage, (info.sender.as_str(), &token_id), &true)?;
         } else {
             token.bids.retain(|item| item.address != info.sender);
+            self.bids_by_bidder
+                .remove(deps.storage, (info.sender.as_str(), &token_id));
         }
 
         self.tokens.save(deps.storage, &token_id, &token)?;
@@ -780,13 +1463,7 @@ where
                 .add_attribute("action", "setbidtobuy")
                 .add_attribute("sender", info.sender.clone())
                 .add_attribute("token_id", token_id)
-                .add_message(BankMsg::Send {
-                    to_address: info.sender.to_string(),
-                    amount: vec![Coin {
-                        denom: denom,
-                        amount: amount,
-                    }],
-                }))
+                .add_message(self.payout(deps.storage, info.sender.to_string(), denom, amount)?))
         } else {
             Ok(Response::new()
                 .add_attribute("action", "setbidtobuy")
@@ -812,7 +1489,7 @@ where
         for (i, item) in token.bids.iter().enumerate() {
             if item.address == bidder {
                 position = i as i32;
-                amount = item.offer.into();
+                amount = item.offer;
                 denom = item.denom.clone();
                 break;
             }
@@ -822,6 +1499,8 @@ where
             return Err(ContractError::NotReserved {});
         } else {
             token.bids.retain(|item| item.address != bidder);
+            self.bids_by_bidder
+                .remove(deps.storage, (&bidder, &token_id));
         }
 
         self.tokens.save(deps.storage, &token_id, &token)?;
@@ -830,13 +1509,7 @@ where
                 .add_attribute("action", "rejectbid")
                 .add_attribute("sender", info.sender.clone())
                 .add_attribute("token_id", token_id)
-                .add_message(BankMsg::Send {
-                    to_address: bidder.to_string(),
-                    amount: vec![Coin {
-                        denom: denom,
-                        amount: amount,
-                    }],
-                }))
+                .add_message(self.payout(deps.storage, bidder.to_string(), denom, amount)?))
         } else {
             Ok(Response::new()
                 .add_attribute("action", "rejectbid")
@@ -845,40 +1518,171 @@ where
         }
     }
 
-    pub fn setlistforshorttermrental(
+    pub fn setcounteroffer(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        bidder: String,
+        amount: u128,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
+
+        let bid = token
+            .bids
+            .iter_mut()
+            .find(|item| item.address == bidder)
+            .ok_or(ContractError::NotReserved {})?;
+        if bid.is_expired(&env.block) {
+            return Err(ContractError::Expired {});
+        }
+        if Uint128::from(amount) <= bid.offer {
+            return Err(ContractError::InvalidInput {});
+        }
+        bid.counter_offer = Some(Uint128::from(amount));
+
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "setcounteroffer")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_attribute("bidder", bidder))
+    }
+
+    pub fn acceptcounteroffer(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        self.check_can_edit_auction(&env, &token)?;
+        self.check_can_edit_escrow(&token)?;
+
+        let position = token
+            .bids
+            .iter()
+            .position(|item| item.address == info.sender)
+            .ok_or(ContractError::NotReserved {})?;
+        let bid = token.bids.remove(position);
+        if bid.is_expired(&env.block) {
+            return Err(ContractError::Expired {});
+        }
+        let price = bid.counter_offer.ok_or(ContractError::NoCounterOffer {})?;
+
+        let top_up = price - bid.offer;
+        if info.funds.len() != 1 || info.funds[0].denom != bid.denom {
+            return Err(ContractError::InvalidDeposit {});
+        }
+        if info.funds[0].amount < top_up {
+            return Err(ContractError::InsufficientDeposit {});
+        }
+
+        let mut messages = self.refund_bids(deps.storage, &token.bids)?;
+        self.unindex_bids(deps.storage, &token_id, &token.bids);
+        self.bids_by_bidder
+            .remove(deps.storage, (&bid.address, &token_id));
+        token.bids = vec![];
+        messages.extend(self.settle_sale(
+            deps.storage,
+            &mut token,
+            info.sender.as_str(),
+            &bid.denom,
+            price,
+        )?);
+        if info.funds[0].amount > top_up {
+            messages.push(self.payout(
+                deps.storage,
+                info.sender.as_str(),
+                bid.denom.as_str(),
+                info.funds[0].amount - top_up,
+            )?);
+        }
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "acceptcounteroffer")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_attribute("price", price)
+            .add_messages(messages))
+    }
+
+    pub fn reclaimexpiredbids(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+
+        let (expired, active): (Vec<Bid>, Vec<Bid>) = token
+            .bids
+            .into_iter()
+            .partition(|bid| bid.is_expired(&env.block));
+        token.bids = active;
+        self.tokens.save(deps.storage, &token_id, &token)?;
+        self.unindex_bids(deps.storage, &token_id, &expired);
+
+        Ok(Response::new()
+            .add_attribute("action", "reclaimexpiredbids")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_attribute("reclaimed", expired.len().to_string())
+            .add_messages(self.refund_bids(deps.storage, &expired)?))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn setlistforauction(
         &self,
         deps: DepsMut,
         env: Env,
         info: MessageInfo,
         token_id: String,
         denom: String,
-        price_per_day: u128,
-        auto_approve: bool,
-        available_period: Vec<String>,
-        minimum_stay: u64,
-        cancellation: Vec<CancellationItem>,
+        reserve_price: u128,
+        start_time: u64,
+        end_time: u64,
+        minimum_increment: u128,
+        extension_window: u64,
     ) -> Result<Response<C>, ContractError> {
         let mut token = self.tokens.load(deps.storage, &token_id)?;
         // ensure we have permissions
         self.check_can_send(deps.as_ref(), &env, &info, &token)?;
-        self.check_can_edit_short(&env, &token)?;
+        self.check_can_edit_bid(&env, &token)?;
+        self.check_not_fractionalized(&token)?;
+        self.check_can_edit_escrow(&token)?;
+        if token.auction.highest_bid.is_some() || token.dutch_auction.islisted == Some(true) {
+            return Err(ContractError::AuctionActive {});
+        }
+        if start_time >= end_time || end_time <= env.block.time.seconds() {
+            return Err(ContractError::InvalidInput {});
+        }
 
-        token.shortterm_rental.islisted = Some(true);
-        token.shortterm_rental.price_per_day = price_per_day;
-        token.shortterm_rental.available_period = available_period;
-        token.shortterm_rental.auto_approve = auto_approve;
-        token.shortterm_rental.denom = denom;
-        token.shortterm_rental.minimum_stay = minimum_stay;
-        token.shortterm_rental.cancellation = cancellation;
+        token.auction = Auction {
+            islisted: Some(true),
+            denom,
+            reserve_price,
+            start_time,
+            end_time,
+            minimum_increment,
+            extension_window,
+            highest_bid: None,
+        };
         self.tokens.save(deps.storage, &token_id, &token)?;
 
         Ok(Response::new()
-            .add_attribute("action", "setlistforshorttermrental")
+            .add_attribute("action", "setlistforauction")
             .add_attribute("sender", info.sender)
             .add_attribute("token_id", token_id))
     }
 
-    pub fn setunlistforshorttermrental(
+    pub fn setunlistforauction(
         &self,
         deps: DepsMut,
         env: Env,
@@ -888,534 +1692,416 @@ where
         let mut token = self.tokens.load(deps.storage, &token_id)?;
         // ensure we have permissions
         self.check_can_send(deps.as_ref(), &env, &info, &token)?;
-        self.check_can_edit_short(&env, &token)?;
-
-        token.shortterm_rental.islisted = None;
-        token.shortterm_rental.price_per_day = 0u128;
-        token.shortterm_rental.available_period = vec![];
-        token.shortterm_rental.auto_approve = false;
-        token.shortterm_rental.minimum_stay = 0u64;
-        token.shortterm_rental.cancellation = vec![];
-        token.shortterm_rental.denom = "".to_string();
+        if token.auction.highest_bid.is_some() {
+            return Err(ContractError::BidsActive {});
+        }
 
+        token.auction.islisted = None;
         self.tokens.save(deps.storage, &token_id, &token)?;
 
         Ok(Response::new()
-            .add_attribute("action", "setunlistforshorttermrental")
+            .add_attribute("action", "setunlistforauction")
             .add_attribute("sender", info.sender)
             .add_attribute("token_id", token_id))
     }
 
-    pub fn setreservationforshortterm(
+    pub fn setbidforauction(
         &self,
         deps: DepsMut,
+        env: Env,
         info: MessageInfo,
         token_id: String,
-        renting_period: Vec<String>,
-        guests: usize,
     ) -> Result<Response<C>, ContractError> {
         let mut token = self.tokens.load(deps.storage, &token_id)?;
-        let new_checkin = renting_period[0].parse::<u64>();
-        let new_checkin_timestamp;
+        if token.auction.islisted != Some(true) {
+            return Err(ContractError::NotListed {});
+        }
 
-        match new_checkin {
-            Ok(timestamp) => {
-                new_checkin_timestamp = timestamp;
-            }
-            Err(_e) => {
-                return Err(ContractError::NotReserved {});
-            }
+        let current_time = env.block.time.seconds();
+        if current_time < token.auction.start_time {
+            return Err(ContractError::AuctionNotStarted {});
+        }
+        if current_time >= token.auction.end_time {
+            return Err(ContractError::AuctionEnded {});
         }
-        let new_checkout = renting_period[1].parse::<u64>();
-        let new_checkout_timestamp;
 
-        match new_checkout {
-            Ok(timestamp) => {
-                new_checkout_timestamp = timestamp;
-            }
-            Err(_e) => {
-                return Err(ContractError::NotReserved {});
-            }
+        if info.funds.len() != 1 || info.funds[0].denom != token.auction.denom {
+            return Err(ContractError::InvalidDeposit {});
+        }
+        let offer = info.funds[0].amount;
+        let minimum_bid = match &token.auction.highest_bid {
+            Some(bid) => bid.offer + Uint128::from(token.auction.minimum_increment.max(1)),
+            None => Uint128::from(token.auction.reserve_price),
+        };
+        if offer < minimum_bid {
+            return Err(ContractError::InsufficientDeposit {});
         }
 
-        if ((new_checkout_timestamp - new_checkin_timestamp) / 86400)
-            < token.shortterm_rental.minimum_stay
-        {
-            return Err(ContractError::LessThanMinimum {});
+        // anti-sniping: a late bid keeps the auction open for another window
+        if token.auction.end_time - current_time < token.auction.extension_window {
+            token.auction.end_time = current_time + token.auction.extension_window;
         }
 
-        let mut placetoreserve: i32 = -1;
-        let lenofrentals = token.rentals.len();
-
-        let mut flag = false;
-        for (i, traveler) in token.rentals.iter().enumerate() {
-            let checkin = traveler.renting_period[0];
-            let checkout = traveler.renting_period[1];
-            if new_checkout_timestamp < checkin {
-                if i == 0 {
-                    placetoreserve = 0;
-                    break;
-                } else if flag {
-                    placetoreserve = i as i32;
-                    break;
-                }
-            } else if checkout < new_checkin_timestamp {
-                flag = true;
-                if i == lenofrentals - 1 {
-                    placetoreserve = lenofrentals as i32;
-                    break;
-                }
-            } else {
-                flag = false;
-            }
-        }
+        let outbid = token.auction.highest_bid.replace(Bid {
+            address: info.sender.to_string(),
+            offer,
+            denom: info.funds[0].denom.clone(),
+            expires: None,
+            counter_offer: None,
+        });
+        self.tokens.save(deps.storage, &token_id, &token)?;
 
-        if placetoreserve == -1 {
-            if lenofrentals > 0 {
-                return Err(ContractError::UnavailablePeriod {});
-            } else {
-                placetoreserve = 0;
-            }
+        let res = Response::new()
+            .add_attribute("action", "setbidforauction")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id);
+        match outbid {
+            Some(bid) => Ok(res.add_message(self.payout(
+                deps.storage,
+                bid.address,
+                bid.denom,
+                bid.offer,
+            )?)),
+            None => Ok(res),
         }
+    }
 
-        if info.funds[0].denom != token.shortterm_rental.denom {
-            return Err(ContractError::InvalidDeposit {});
+    pub fn settleauction(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        if token.auction.islisted != Some(true) {
+            return Err(ContractError::NotListed {});
         }
-        let sent_amount = info.funds[0].amount;
-        let fee_percentage = self.get_fee(deps.storage)?;
-        let rent_amount = token.shortterm_rental.price_per_day
-            * u128::from(new_checkout_timestamp - new_checkin_timestamp)
-            / (86400);
-        if sent_amount
-            < Uint128::from(rent_amount)
-                + Uint128::new((u128::from(rent_amount) * u128::from(fee_percentage)) / 10000)
-        {
-            return Err(ContractError::InsufficientDeposit {});
+        if env.block.time.seconds() < token.auction.end_time {
+            return Err(ContractError::AuctionNotEnded {});
         }
 
-        self.increase_balance(
-            deps.storage,
-            info.funds[0].denom.clone(),
-            sent_amount - Uint128::from(rent_amount),
-        )?;
-
-        let traveler = Rental {
-            denom: token.shortterm_rental.denom.clone(),
-            rental_type: false,
-            approved_date: None,
-            deposit_amount: Uint128::from(rent_amount),
-            renting_period: vec![new_checkin_timestamp, new_checkout_timestamp],
-            address: Some(info.sender.clone()),
-            approved: token.shortterm_rental.auto_approve,
-            cancelled: false,
-            guests: guests,
+        token.auction.islisted = None;
+        let messages = match token.auction.highest_bid.take() {
+            Some(bid) => self.settle_sale(
+                deps.storage,
+                &mut token,
+                &bid.address,
+                &bid.denom,
+                bid.offer,
+            )?,
+            None => vec![],
         };
-
-        // token.shortterm_rental.deposit_amount += sent_amount;
-        token.rentals.insert(placetoreserve as usize, traveler);
-
         self.tokens.save(deps.storage, &token_id, &token)?;
 
-        // if token.shortterm_rental.auto_approve {
-        //     Ok(Response::new()
-        //         .add_attribute("action", "setreservationforshortterm")
-        //         .add_attribute("sender", info.sender)
-        //         .add_attribute("token_id", token_id)
-        //         .add_message(BankMsg::Send {
-        //             to_address: token.owner.into_string(),
-        //             amount: vec![Coin {
-        //                 denom: token.shortterm_rental.denom,
-        //                 amount: sent_amount,
-        //             }],
-        //         }))
-        // } else {
         Ok(Response::new()
-            .add_attribute("action", "setreservationforshortterm")
+            .add_attribute("action", "settleauction")
             .add_attribute("sender", info.sender)
-            .add_attribute("token_id", token_id))
-        // }
+            .add_attribute("token_id", token_id)
+            .add_messages(messages))
     }
 
-    pub fn setapproveforshortterm(
+    #[allow(clippy::too_many_arguments)]
+    pub fn setlistfordutchauction(
         &self,
         deps: DepsMut,
-        info: MessageInfo,
         env: Env,
+        info: MessageInfo,
         token_id: String,
-        renting_period: Vec<String>,
-        traveler: String,
+        denom: String,
+        start_price: u128,
+        floor_price: u128,
+        start_time: u64,
+        end_time: u64,
+        step_interval: u64,
     ) -> Result<Response<C>, ContractError> {
         let mut token = self.tokens.load(deps.storage, &token_id)?;
+        // ensure we have permissions
         self.check_can_send(deps.as_ref(), &env, &info, &token)?;
-
-        let current_time = env.block.time.seconds();
-
-        let check_in_time = renting_period[0].parse::<u64>();
-        let check_in_time_timestamp;
-
-        match check_in_time {
-            Ok(timestamp) => {
-                check_in_time_timestamp = timestamp;
-            }
-            Err(_e) => {
-                return Err(ContractError::NotReserved {});
-            }
-        }
-
-        if check_in_time_timestamp <= current_time {
-            return Err(ContractError::RentalAlreadyStarted {});
-        }
-
-        let mut position: i32 = -1;
-        // let mut amount = Uint128::from(0u64);
-        for (i, item) in token.rentals.iter().enumerate() {
-            if item.address == Some(Addr::unchecked(traveler.clone()))
-                // && item.renting_period == renting_period
-                && item.renting_period[0].to_string() == renting_period[0]
-                && item.renting_period[1].to_string() == renting_period[1]
-                && !item.approved
-            {
-                position = i as i32;
-                // amount = item.deposit_amount;
-                break;
-            }
+        self.check_can_edit_bid(&env, &token)?;
+        self.check_not_fractionalized(&token)?;
+        self.check_can_edit_escrow(&token)?;
+        if token.auction.islisted == Some(true) {
+            return Err(ContractError::AuctionActive {});
         }
-        if position == -1 {
-            return Err(ContractError::ApprovedAlready {});
+        if floor_price > start_price
+            || start_time >= end_time
+            || end_time <= env.block.time.seconds()
+        {
+            return Err(ContractError::InvalidInput {});
         }
-        token.rentals[position as usize].approved = true;
 
+        token.dutch_auction = DutchAuction {
+            islisted: Some(true),
+            denom,
+            start_price,
+            floor_price,
+            start_time,
+            end_time,
+            step_interval,
+        };
         self.tokens.save(deps.storage, &token_id, &token)?;
 
-        Ok(
-            Response::new()
-                .add_attribute("action", "setreservationforshortterm")
-                .add_attribute("sender", info.sender)
-                .add_attribute("token_id", token_id), // .add_message(BankMsg::Send {
-                                                      //     to_address: token.owner.into_string(),
-                                                      //     amount: vec![Coin {
-                                                      //         denom: token.shortterm_rental.denom,
-                                                      //         amount: amount,
-                                                      //     }],
-                                                      // })
-        )
+        Ok(Response::new()
+            .add_attribute("action", "setlistfordutchauction")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
     }
 
-    pub fn rejectreservationforshortterm(
+    pub fn setunlistfordutchauction(
         &self,
         deps: DepsMut,
         env: Env,
         info: MessageInfo,
         token_id: String,
-        traveler: String,
-        renting_period: Vec<String>,
     ) -> Result<Response<C>, ContractError> {
         let mut token = self.tokens.load(deps.storage, &token_id)?;
+        // ensure we have permissions
         self.check_can_send(deps.as_ref(), &env, &info, &token)?;
 
-        let mut position: i32 = -1;
-        let mut refundable_amount: Uint128 = Uint128::new(0);
-        for (i, item) in token.rentals.iter().enumerate() {
-            if item.address == Some(Addr::unchecked(traveler.clone()))
-                && item.renting_period[0].to_string() == renting_period[0]
-                && item.renting_period[1].to_string() == renting_period[1]
-                && item.rental_type == false
-            {
-                position = i as i32;
-                if item.approved {
-                    refundable_amount = item.deposit_amount;
-                } else {
-                    refundable_amount = item.deposit_amount;
-                }
-            }
-        }
-        if position == -1 {
-            return Err(ContractError::NotReserved {});
-        } else {
-            token.rentals.remove(position as usize);
-            self.tokens.save(deps.storage, &token_id, &token)?;
-        }
+        token.dutch_auction.islisted = None;
+        self.tokens.save(deps.storage, &token_id, &token)?;
 
         Ok(Response::new()
-            .add_attribute("action", "rejectreservationforshortterm")
+            .add_attribute("action", "setunlistfordutchauction")
             .add_attribute("sender", info.sender)
-            .add_attribute("token_id", token_id)
-            .add_message(BankMsg::Send {
-                to_address: traveler,
-                amount: vec![Coin {
-                    denom: token.shortterm_rental.denom,
-                    amount: refundable_amount,
-                }],
-            }))
+            .add_attribute("token_id", token_id))
     }
 
-    pub fn cancelreservationafterapprovalforshortterm(
+    pub fn buydutchauction(
         &self,
         deps: DepsMut,
-        info: MessageInfo,
         env: Env,
+        info: MessageInfo,
         token_id: String,
-        renting_period: Vec<String>,
     ) -> Result<Response<C>, ContractError> {
         let mut token = self.tokens.load(deps.storage, &token_id)?;
-
-        let mut position: i32 = -1;
-        let mut amount = Uint128::new(0);
-        let traveler_address = info.sender.to_string();
-        for (i, item) in token.rentals.iter().enumerate() {
-            if item.address == Some(info.sender.clone())
-                && item.renting_period[0].to_string() == renting_period[0]
-                && item.renting_period[1].to_string() == renting_period[1]
-                && item.rental_type == false
-            {
-                if item.approved && !item.cancelled {
-                    position = i as i32;
-                    amount = item.deposit_amount;
-                } else {
-                    return Err(ContractError::NotApproved {});
-                }
-            }
+        if token.dutch_auction.islisted != Some(true) {
+            return Err(ContractError::NotListed {});
         }
-        let mut refundable_amount = Uint128::new(0);
-        let mut cancellation = token.shortterm_rental.cancellation.clone();
-        cancellation.sort_by(|a, b| b.percentage.cmp(&a.percentage));
-        let current_time = env.block.time.seconds();
-
-        let check_in_time = renting_period[0].parse::<u64>();
-        let check_in_time_timestamp;
 
-        match check_in_time {
-            Ok(timestamp) => {
-                check_in_time_timestamp = timestamp;
-            }
-            Err(_e) => {
-                return Err(ContractError::NotReserved {});
-            }
+        let current_time = env.block.time.seconds();
+        if current_time < token.dutch_auction.start_time {
+            return Err(ContractError::AuctionNotStarted {});
         }
 
-        if check_in_time_timestamp <= current_time {
-            return Err(ContractError::RentalAlreadyStarted {});
+        if info.funds.len() != 1 || info.funds[0].denom != token.dutch_auction.denom {
+            return Err(ContractError::InvalidDeposit {});
         }
-
-        let diff_days = (check_in_time_timestamp - current_time) / 86400;
-        for (_i, item) in cancellation.iter().enumerate() {
-            if item.deadline < diff_days {
-                refundable_amount =
-                    Uint128::new((amount.u128() * u128::from(item.percentage)) / 100);
-                break;
-            }
+        let price = Uint128::from(token.dutch_auction.price_at(current_time));
+        let sent_amount = info.funds[0].amount;
+        if sent_amount < price {
+            return Err(ContractError::InsufficientDeposit {});
         }
 
-        if cancellation.len() == 0 {
-            refundable_amount = amount;
+        let denom = token.dutch_auction.denom.clone();
+        let mut messages = self.settle_sale(
+            deps.storage,
+            &mut token,
+            info.sender.as_str(),
+            &denom,
+            price,
+        )?;
+        if sent_amount > price {
+            messages.push(self.payout(
+                deps.storage,
+                info.sender.as_str(),
+                denom,
+                sent_amount - price,
+            )?);
         }
+        self.tokens.save(deps.storage, &token_id, &token)?;
 
-        if position != -1 {
-            // token.rentals.remove(position as usize);
-
-            token.rentals[position as usize].cancelled = true;
-            token.rentals[position as usize].deposit_amount = amount - refundable_amount;
-
-            self.tokens.save(deps.storage, &token_id, &token)?;
-            if refundable_amount > Uint128::new(0) {
-                Ok(Response::new()
-                    .add_attribute("action", "cancelreservationafterapprovalforshortterm")
-                    .add_attribute("sender", info.sender)
-                    .add_attribute("token_id", token_id)
-                    .add_message(BankMsg::Send {
-                        to_address: traveler_address,
-                        amount: vec![Coin {
-                            denom: token.shortterm_rental.denom,
-                            amount: refundable_amount,
-                        }],
-                    }))
-            } else {
-                Ok(Response::new()
-                    .add_attribute("action", "cancelreservationafterapprovalforshortterm")
-                    .add_attribute("sender", info.sender)
-                    .add_attribute("token_id", token_id))
-            }
-        } else {
-            return Err(ContractError::NotReserved {});
-        }
+        Ok(Response::new()
+            .add_attribute("action", "buydutchauction")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_attribute("price", price)
+            .add_messages(messages))
     }
 
-    pub fn cancelreservationbeforeapprovalforshortterm(
+    pub fn makeoffer(
         &self,
         deps: DepsMut,
+        env: Env,
         info: MessageInfo,
-        token_id: String,
-        renting_period: Vec<String>,
+        token_id: Option<String>,
+        traits: Vec<Trait>,
+        expires: Option<Expiration>,
     ) -> Result<Response<C>, ContractError> {
-        let mut token = self.tokens.load(deps.storage, &token_id)?;
-
-        let mut position: i32 = -1;
-        let mut amount = Uint128::from(0u64);
-        let traveler_address = info.sender.to_string();
-        for (i, item) in token.rentals.iter().enumerate() {
-            if item.address == Some(info.sender.clone())
-                && item.renting_period[0].to_string() == renting_period[0]
-                && item.renting_period[1].to_string() == renting_period[1]
-                && item.rental_type == false
-            {
-                if item.approved {
-                    return Err(ContractError::ApprovedAlready {});
-                } else {
-                    position = i as i32;
-                    amount = item.deposit_amount;
+        match &token_id {
+            Some(token_id) => {
+                let token = self.tokens.load(deps.storage, token_id)?;
+                if info.sender == token.owner.address {
+                    return Err(ContractError::InvalidInput {});
                 }
             }
+            None => {
+                if traits.is_empty() {
+                    return Err(ContractError::InvalidInput {});
+                }
+            }
+        }
+        if info.funds.len() != 1 || info.funds[0].amount.is_zero() {
+            return Err(ContractError::InsufficientDeposit {});
+        }
+        if expires.is_some_and(|expires| expires.is_expired(&env.block)) {
+            return Err(ContractError::Expired {});
         }
 
-        if position != -1 {
-            token.rentals.remove(position as usize);
-            self.tokens.save(deps.storage, &token_id, &token)?;
-            Ok(Response::new()
-                .add_attribute("action", "cancelreservationbeforeapprovalforshortterm")
-                .add_attribute("sender", info.sender)
-                .add_attribute("token_id", token_id)
-                .add_message(BankMsg::Send {
-                    to_address: traveler_address,
-                    amount: vec![Coin {
-                        denom: token.shortterm_rental.denom,
-                        amount: amount,
-                    }],
-                }))
-        } else {
-            return Err(ContractError::NotReserved {});
+        let offer_id = self.offer_count.may_load(deps.storage)?.unwrap_or_default() + 1;
+        self.offer_count.save(deps.storage, &offer_id)?;
+        let offer = Offer {
+            id: offer_id,
+            bidder: info.sender.to_string(),
+            denom: info.funds[0].denom.clone(),
+            amount: info.funds[0].amount,
+            token_id,
+            traits,
+            expires,
+        };
+        self.offers.save(deps.storage, offer_id, &offer)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "makeoffer")
+            .add_attribute("sender", info.sender)
+            .add_attribute("offer_id", offer_id.to_string()))
+    }
+
+    pub fn canceloffer(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        offer_id: u64,
+    ) -> Result<Response<C>, ContractError> {
+        let offer = self
+            .offers
+            .may_load(deps.storage, offer_id)?
+            .ok_or(ContractError::NoOffer {})?;
+        if info.sender != offer.bidder && !offer.is_expired(&env.block) {
+            return Err(ContractError::NotBuyer {});
         }
+        self.offers.remove(deps.storage, offer_id)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "canceloffer")
+            .add_attribute("sender", info.sender)
+            .add_attribute("offer_id", offer_id.to_string())
+            .add_message(self.payout(deps.storage, offer.bidder, offer.denom, offer.amount)?))
     }
 
-    pub fn finalizeshorttermrental(
+    pub fn acceptoffer(
         &self,
         deps: DepsMut,
         env: Env,
         info: MessageInfo,
+        offer_id: u64,
         token_id: String,
-        traveler: String,
-        renting_period: Vec<String>,
     ) -> Result<Response<C>, ContractError> {
         let mut token = self.tokens.load(deps.storage, &token_id)?;
-
         self.check_can_send(deps.as_ref(), &env, &info, &token)?;
-
-        let mut position: i32 = -1;
-        let mut amount = Uint128::from(0u64);
-
-        let current_time = env.block.time.seconds();
-
-        let check_out_time = renting_period[1].parse::<u64>();
-        let check_out_time_timestamp;
-
-        match check_out_time {
-            Ok(timestamp) => {
-                check_out_time_timestamp = timestamp;
-            }
-            Err(_e) => {
-                return Err(ContractError::NotReserved {});
-            }
+        self.check_can_edit_auction(&env, &token)?;
+        self.check_not_fractionalized(&token)?;
+        self.check_can_edit_escrow(&token)?;
+
+        let offer = self
+            .offers
+            .may_load(deps.storage, offer_id)?
+            .ok_or(ContractError::NoOffer {})?;
+        if offer.is_expired(&env.block) {
+            return Err(ContractError::Expired {});
         }
-
-        let mut target = "".to_string();
-
-        for (i, item) in token.rentals.iter().enumerate() {
-            if item.address == Some(Addr::unchecked(traveler.clone()))
-                && item.renting_period[0].to_string() == renting_period[0]
-                && item.renting_period[1].to_string() == renting_period[1]
-                && item.rental_type == false
-            {
-                position = i as i32;
-                amount = item.deposit_amount;
-
-                if item.cancelled {
-                    target = token.owner.address.to_string();
-                }
-                if !item.cancelled {
-                    if !item.approved {
-                        target = traveler.clone();
-                        // return  Err(ContractError::NotApproved {});
-                    }
-                    if item.approved {
-                        target = token.owner.address.to_string();
-                        let fee_percentage = self.get_fee(deps.storage)?;
-                        self.increase_balance(
-                            deps.storage,
-                            token.shortterm_rental.denom.clone(),
-                            Uint128::new((u128::from(amount) * u128::from(fee_percentage)) / 10000),
-                        )?;
-                        amount -=
-                            Uint128::new((u128::from(amount) * u128::from(fee_percentage)) / 10000);
-                    }
-                }
+        let matches = match &offer.token_id {
+            Some(offer_token_id) => *offer_token_id == token_id,
+            None => {
+                let attributes = self.token_traits(&token)?;
+                offer.traits.iter().all(|item| attributes.contains(item))
             }
+        };
+        if !matches {
+            return Err(ContractError::OfferMismatch {});
         }
-        if position == -1 {
-            return Err(ContractError::NotReserved {});
-        } else {
-            if check_out_time_timestamp > current_time
-                && !token.rentals[position as usize].cancelled
-            {
-                return Err(ContractError::RentalActive {});
-            }
+        self.offers.remove(deps.storage, offer_id)?;
 
-            token.rentals.remove(position as usize);
-            self.tokens.save(deps.storage, &token_id, &token)?;
-        }
+        // the sale is final, open bids on the token are refunded
+        let mut messages = self.refund_bids(deps.storage, &token.bids)?;
+        self.unindex_bids(deps.storage, &token_id, &token.bids);
+        token.bids = vec![];
+        messages.extend(self.settle_sale(
+            deps.storage,
+            &mut token,
+            &offer.bidder,
+            &offer.denom,
+            offer.amount,
+        )?);
+        self.tokens.save(deps.storage, &token_id, &token)?;
 
-        if amount > Uint128::new(0) {
-            Ok(Response::new()
-                .add_attribute("action", "finalizeshorttermrental")
-                .add_attribute("sender", info.sender)
-                .add_attribute("token_id", token_id)
-                .add_message(BankMsg::Send {
-                    to_address: target.clone(),
-                    amount: vec![Coin {
-                        denom: token.shortterm_rental.denom,
-                        amount: amount,
-                    }],
-                }))
-        } else {
-            Ok(Response::new()
-                .add_attribute("action", "finalizeshorttermrental")
-                .add_attribute("sender", info.sender)
-                .add_attribute("token_id", token_id))
-        }
+        Ok(Response::new()
+            .add_attribute("action", "acceptoffer")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_attribute("offer_id", offer_id.to_string())
+            .add_messages(messages))
     }
 
-    pub fn setlistforlongtermrental(
+    #[allow(clippy::too_many_arguments)]
+    pub fn offerescrowsale(
         &self,
         deps: DepsMut,
         env: Env,
         info: MessageInfo,
         token_id: String,
-        denom: String,
-        price_per_month: u128,
-        auto_approve: bool,
-        available_period: Vec<String>,
-        minimum_stay: u64,
-        cancellation: Vec<CancellationItem>,
+        price: u128,
+        inspection_period: u64,
+        closing_period: u64,
+        penalty: u64,
     ) -> Result<Response<C>, ContractError> {
         let mut token = self.tokens.load(deps.storage, &token_id)?;
-        // ensure we have permissions
-        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
-        self.check_can_edit_long(&env, &token)?;
+        self.check_can_edit_auction(&env, &token)?;
+        self.check_not_fractionalized(&token)?;
+        if token.escrow.is_some() {
+            return Err(ContractError::EscrowActive {});
+        }
+        if info.sender == token.owner.address
+            || penalty > 10000
+            || inspection_period > MAX_ESCROW_PERIOD
+            || closing_period > MAX_ESCROW_PERIOD
+        {
+            return Err(ContractError::InvalidInput {});
+        }
+        if info.funds.len() != 1 || info.funds[0].amount.is_zero() {
+            return Err(ContractError::InsufficientDeposit {});
+        }
+        if info.funds[0].amount > Uint128::from(price) {
+            return Err(ContractError::InvalidInput {});
+        }
+        // only one escrowed sale runs at a time, so the slot is not taken for a token amount
+        let min_deposit = Uint128::from(price.max(token.sell.price))
+            .multiply_ratio(MIN_ESCROW_DEPOSIT, 10000u128);
+        if info.funds[0].amount < min_deposit {
+            return Err(ContractError::InsufficientDeposit {});
+        }
 
-        token.longterm_rental.islisted = Some(true);
-        token.longterm_rental.price_per_month = price_per_month;
-        token.longterm_rental.available_period = available_period;
-        token.longterm_rental.auto_approve = auto_approve;
-        token.longterm_rental.denom = denom;
-        token.longterm_rental.minimum_stay = minimum_stay;
-        token.longterm_rental.cancellation = cancellation;
+        token.escrow = Some(EscrowSale {
+            buyer: info.sender.to_string(),
+            denom: info.funds[0].denom.clone(),
+            price: Uint128::from(price),
+            deposit: info.funds[0].amount,
+            inspection_period,
+            closing_period,
+            penalty,
+            accepted_at: None,
+        });
         self.tokens.save(deps.storage, &token_id, &token)?;
 
         Ok(Response::new()
-            .add_attribute("action", "setlistforlongtermrental")
+            .add_attribute("action", "offerescrowsale")
             .add_attribute("sender", info.sender)
             .add_attribute("token_id", token_id))
     }
 
-    pub fn setunlistforlongtermrental(
+    pub fn acceptescrowsale(
         &self,
         deps: DepsMut,
         env: Env,
@@ -1423,580 +2109,3699 @@ where
         token_id: String,
     ) -> Result<Response<C>, ContractError> {
         let mut token = self.tokens.load(deps.storage, &token_id)?;
-        // ensure we have permissions
         self.check_can_send(deps.as_ref(), &env, &info, &token)?;
-        self.check_can_edit_long(&env, &token)?;
-
-        token.longterm_rental.islisted = None;
-        token.longterm_rental.price_per_month = 0u128;
-        token.longterm_rental.available_period = vec![];
-        token.longterm_rental.auto_approve = false;
-        token.longterm_rental.minimum_stay = 0u64;
-        token.longterm_rental.cancellation = vec![];
-        token.longterm_rental.denom = "".to_string();
+        self.check_can_edit_bid(&env, &token)?;
+        self.check_can_edit_auction(&env, &token)?;
+        self.check_not_fractionalized(&token)?;
+        let mut escrow = token.escrow.clone().ok_or(ContractError::NoEscrow {})?;
+        if escrow.accepted_at.is_some() {
+            return Err(ContractError::ApprovedAlready {});
+        }
 
+        escrow.accepted_at = Some(env.block.time.seconds());
+        token.escrow = Some(escrow);
+        token.sell.islisted = None;
         self.tokens.save(deps.storage, &token_id, &token)?;
 
         Ok(Response::new()
-            .add_attribute("action", "setunlistforlongtermrental")
+            .add_attribute("action", "acceptescrowsale")
             .add_attribute("sender", info.sender)
             .add_attribute("token_id", token_id))
     }
 
-    pub fn setreservationforlongterm(
+    pub fn closeescrowsale(
         &self,
         deps: DepsMut,
+        env: Env,
         info: MessageInfo,
         token_id: String,
-        renting_period: Vec<String>,
-        guests: usize,
     ) -> Result<Response<C>, ContractError> {
         let mut token = self.tokens.load(deps.storage, &token_id)?;
-        let new_checkin = renting_period[0].parse::<u64>();
-        let new_checkin_timestamp;
-
-        match new_checkin {
-            Ok(timestamp) => {
-                new_checkin_timestamp = timestamp;
-            }
-            Err(_e) => {
-                return Err(ContractError::NotReserved {});
-            }
+        let escrow = token.escrow.clone().ok_or(ContractError::NoEscrow {})?;
+        if info.sender != escrow.buyer {
+            return Err(ContractError::NotBuyer {});
         }
-        let new_checkout = renting_period[1].parse::<u64>();
-        let new_checkout_timestamp;
-
-        match new_checkout {
-            Ok(timestamp) => {
-                new_checkout_timestamp = timestamp;
-            }
-            Err(_e) => {
-                return Err(ContractError::NotReserved {});
-            }
+        let closing_deadline = escrow
+            .closing_deadline()
+            .ok_or(ContractError::NotApproved {})?;
+        if env.block.time.seconds() > closing_deadline {
+            return Err(ContractError::Expired {});
         }
 
-        if ((new_checkout_timestamp - new_checkin_timestamp) / 86400)
-            < token.longterm_rental.minimum_stay
-        {
-            return Err(ContractError::LessThanMinimum {});
+        let balance_due = escrow.balance_due();
+        let sent_amount = match info.funds.first() {
+            Some(coin) if coin.denom == escrow.denom => coin.amount,
+            Some(_) => return Err(ContractError::InvalidDeposit {}),
+            None => Uint128::new(0),
+        };
+        if sent_amount < balance_due {
+            return Err(ContractError::InsufficientDeposit {});
         }
 
-        let mut placetoreserve: i32 = -1;
-        let lenofrentals = token.rentals.len();
-
-        let mut flag = false;
-        for (i, tenant) in token.rentals.iter().enumerate() {
-            let checkin = tenant.renting_period[0];
-            let checkout = tenant.renting_period[1];
-            if new_checkout_timestamp < checkin {
-                if i == 0 {
-                    placetoreserve = 0;
-                    break;
-                } else if flag {
-                    placetoreserve = i as i32;
-                    break;
-                }
-            } else if checkout < new_checkin_timestamp {
-                flag = true;
-                if i == lenofrentals - 1 {
-                    placetoreserve = lenofrentals as i32;
-                    break;
-                }
-            } else {
-                flag = false;
-            }
+        token.escrow = None;
+        let mut messages = self.settle_sale(
+            deps.storage,
+            &mut token,
+            &escrow.buyer,
+            &escrow.denom,
+            escrow.price,
+        )?;
+        if sent_amount > balance_due {
+            messages.push(self.payout(
+                deps.storage,
+                escrow.buyer,
+                escrow.denom,
+                sent_amount - balance_due,
+            )?);
         }
+        self.tokens.save(deps.storage, &token_id, &token)?;
 
-        if placetoreserve == -1 {
-            if lenofrentals > 0 {
-                return Err(ContractError::UnavailablePeriod {});
-            } else {
-                placetoreserve = 0;
+        Ok(Response::new()
+            .add_attribute("action", "closeescrowsale")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_messages(messages))
+    }
+
+    pub fn cancelescrowsale(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        let escrow = token.escrow.clone().ok_or(ContractError::NoEscrow {})?;
+        let current_time = env.block.time.seconds();
+
+        let penalty = if info.sender == escrow.buyer {
+            escrow.penalty_at(current_time)
+        } else {
+            self.check_can_send(deps.as_ref(), &env, &info, &token)?;
+            match escrow.closing_deadline() {
+                // an offer can always be declined
+                None => Uint128::new(0),
+                // the buyer defaulted on closing
+                Some(deadline) if current_time > deadline => escrow.penalty_at(current_time),
+                Some(_) => return Err(ContractError::EscrowActive {}),
             }
-        }
+        };
 
-        if info.funds[0].denom != token.longterm_rental.denom {
-            return Err(ContractError::InvalidDeposit {});
+        token.escrow = None;
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
+        let mut messages = vec![];
+        if penalty > Uint128::new(0) {
+            messages.push(self.payout(
+                deps.storage,
+                token.owner.address.clone(),
+                escrow.denom.clone(),
+                penalty,
+            )?);
         }
-        if info.funds[0].amount < token.longterm_rental.price_per_month.into() {
-            return Err(ContractError::InsufficientDeposit {});
+        if escrow.deposit > penalty {
+            messages.push(self.payout(
+                deps.storage,
+                escrow.buyer,
+                escrow.denom,
+                escrow.deposit - penalty,
+            )?);
         }
 
-        let tenant = Rental {
-            denom: token.longterm_rental.denom.clone(),
-            rental_type: true,
-            approved: token.longterm_rental.auto_approve,
-            deposit_amount: Uint128::from(info.funds[0].amount),
-            renting_period: vec![new_checkin_timestamp, new_checkout_timestamp],
-            address: Some(info.sender.clone()),
-            approved_date: None,
-            cancelled: false,
-            guests,
-        };
+        Ok(Response::new()
+            .add_attribute("action", "cancelescrowsale")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_attribute("penalty", penalty)
+            .add_messages(messages))
+    }
 
-        token.rentals.insert(placetoreserve as usize, tenant);
+    #[allow(clippy::too_many_arguments)]
+    pub fn setlistforshorttermrental(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        denom: String,
+        price_per_day: u128,
+        auto_approve: bool,
+        available_period: Vec<String>,
+        minimum_stay: u64,
+        cancellation: Vec<CancellationItem>,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        // ensure we have permissions
+        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
+        self.check_can_edit_short(&env, &token)?;
+        self.add_host_bond(deps.storage, &token.owner.address, &info.funds)?;
+        self.check_host_can_list(deps.storage, &token.owner.address)?;
 
+        token.shortterm_rental.islisted = Some(true);
+        token.shortterm_rental.price_per_day = price_per_day;
+        token.shortterm_rental.availability.open = parse_available_period(&available_period)?;
+        token.shortterm_rental.available_period = available_period;
+        token.shortterm_rental.auto_approve = auto_approve;
+        token.shortterm_rental.denom = denom;
+        token.shortterm_rental.minimum_stay = minimum_stay;
+        token.shortterm_rental.cancellation = cancellation;
         self.tokens.save(deps.storage, &token_id, &token)?;
+
         Ok(Response::new()
-            .add_attribute("action", "setreservationforlongterm")
+            .add_attribute("action", "setlistforshorttermrental")
             .add_attribute("sender", info.sender)
             .add_attribute("token_id", token_id))
     }
 
-    pub fn cancelreservationbeforeapprovalforlongterm(
+    pub fn setavailabilityforshortterm(
         &self,
         deps: DepsMut,
+        env: Env,
         info: MessageInfo,
         token_id: String,
-        renting_period: Vec<String>,
+        availability: AvailabilityCalendar,
     ) -> Result<Response<C>, ContractError> {
         let mut token = self.tokens.load(deps.storage, &token_id)?;
-
-        let mut position: i32 = -1;
-        let mut amount = Uint128::from(0u64);
-        let tenant_address = info.sender.to_string();
-        for (i, item) in token.rentals.iter().enumerate() {
-            if item.address == Some(info.sender.clone())
-                && item.renting_period[0].to_string() == renting_period[0]
-                && item.renting_period[1].to_string() == renting_period[1]
-                && item.rental_type == true
-            {
-                if item.approved_date.is_some() {
-                    return Err(ContractError::ApprovedAlready {});
-                } else {
-                    position = i as i32;
-                    amount = item.deposit_amount;
-                }
-            }
+        // ensure we have permissions
+        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
+        if availability
+            .open
+            .iter()
+            .chain(availability.blocked.iter())
+            .any(|range| range.start >= range.end)
+        {
+            return Err(ContractError::InvalidInput {});
         }
 
-        if position == -1 {
-            return Err(ContractError::NotReserved {});
-        } else {
-            token.rentals.remove(position as usize);
-            self.tokens.save(deps.storage, &token_id, &token)?;
-        }
+        token.shortterm_rental.available_period = availability
+            .open
+            .iter()
+            .flat_map(|range| [range.start.to_string(), range.end.to_string()])
+            .collect();
+        token.shortterm_rental.availability = availability;
+        self.tokens.save(deps.storage, &token_id, &token)?;
 
-        if amount > Uint128::new(0) {
-            Ok(Response::new()
-                .add_attribute("action", "cancelreservationbeforeapprovalforlongterm")
-                .add_attribute("sender", info.sender)
-                .add_attribute("token_id", token_id)
-                .add_message(BankMsg::Send {
-                    to_address: tenant_address,
-                    amount: vec![Coin {
-                        denom: token.longterm_rental.denom,
-                        amount: amount,
-                    }],
-                }))
-        } else {
-            Ok(Response::new()
-                .add_attribute("action", "cancelreservationbeforeapprovalforlongterm")
-                .add_attribute("sender", info.sender)
-                .add_attribute("token_id", token_id))
-        }
+        Ok(Response::new()
+            .add_attribute("action", "setavailabilityforshortterm")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
     }
 
-    pub fn rejectreservationforlongterm(
+    pub fn setpricerulesforshortterm(
         &self,
         deps: DepsMut,
         env: Env,
         info: MessageInfo,
         token_id: String,
-        tenant: String,
-        renting_period: Vec<String>,
+        price_rules: Vec<PriceRule>,
     ) -> Result<Response<C>, ContractError> {
         let mut token = self.tokens.load(deps.storage, &token_id)?;
+        // ensure we have permissions
         self.check_can_send(deps.as_ref(), &env, &info, &token)?;
-
-        let mut position: i32 = -1;
-        let mut refundable_amount: Uint128 = Uint128::new(0);
-        for (i, item) in token.rentals.iter().enumerate() {
-            if item.address == Some(Addr::unchecked(tenant.clone()))
-                && item.renting_period[0].to_string() == renting_period[0]
-                && item.renting_period[1].to_string() == renting_period[1]
-                && item.rental_type == true
-            {
-                refundable_amount = item.deposit_amount;
-                position = i as i32;
-            }
-        }
-        if position == -1 {
-            return Err(ContractError::NotReserved {});
-        } else {
-            token.rentals.remove(position as usize);
-            self.tokens.save(deps.storage, &token_id, &token)?;
+        if price_rules.iter().any(|rule| match &rule.condition {
+            PriceCondition::Dates { range } => range.start >= range.end,
+            PriceCondition::Weekdays { weekdays } => weekdays.iter().any(|day| *day > 6),
+            _ => false,
+        }) {
+            return Err(ContractError::InvalidInput {});
         }
 
-        if refundable_amount > Uint128::new(0) {
-            Ok(Response::new()
-                .add_attribute("action", "rejectreservationforlongterm")
-                .add_attribute("sender", info.sender)
-                .add_attribute("token_id", token_id)
-                .add_message(BankMsg::Send {
-                    to_address: tenant,
-                    amount: vec![Coin {
-                        denom: token.longterm_rental.denom,
-                        amount: refundable_amount,
-                    }],
-                }))
-        } else {
-            Ok(Response::new()
-                .add_attribute("action", "rejectreservationforlongterm")
-                .add_attribute("sender", info.sender)
-                .add_attribute("token_id", token_id))
+        token.shortterm_rental.price_rules = price_rules;
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "setpricerulesforshortterm")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn setguestpolicyforshortterm(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        max_guests: Option<usize>,
+        included_guests: usize,
+        extra_guest_fee: u128,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        // ensure we have permissions
+        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
+        if max_guests.is_some_and(|max_guests| included_guests > max_guests) {
+            return Err(ContractError::InvalidInput {});
         }
+
+        token.shortterm_rental.max_guests = max_guests;
+        token.shortterm_rental.included_guests = included_guests;
+        token.shortterm_rental.extra_guest_fee = extra_guest_fee;
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "setguestpolicyforshortterm")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
     }
 
-    pub fn depositforlongtermrental(
+    pub fn setresponsewindowforshortterm(
         &self,
         deps: DepsMut,
+        env: Env,
         info: MessageInfo,
         token_id: String,
-        renting_period: Vec<String>,
+        response_window: Option<u64>,
     ) -> Result<Response<C>, ContractError> {
         let mut token = self.tokens.load(deps.storage, &token_id)?;
+        // ensure we have permissions
+        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
 
-        let mut position: i32 = -1;
-        let sent_amount = info.funds[0].amount;
-        if info.funds[0].denom != token.longterm_rental.denom {
-            return Err(ContractError::InvalidDeposit {});
-        }
+        token.shortterm_rental.response_window = response_window;
+        self.tokens.save(deps.storage, &token_id, &token)?;
 
-        if sent_amount > Uint128::new(0) {
-            for (i, item) in token.rentals.iter().enumerate() {
-                if item.address == Some(info.sender.clone())
-                    && item.renting_period[0].to_string() == renting_period[0]
-                    && item.renting_period[1].to_string() == renting_period[1]
-                    && item.rental_type == true
-                {
-                    position = i as i32;
-                }
-            }
+        Ok(Response::new()
+            .add_attribute("action", "setresponsewindowforshortterm")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+    }
 
-            if position == -1 {
-                return Err(ContractError::NotReserved {});
-            }
+    pub fn setdamagedepositforshortterm(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        damage_deposit: u128,
+        claim_window: u64,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        // ensure we have permissions
+        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
 
-            token.rentals[position as usize].deposit_amount += Uint128::from(sent_amount);
-            self.tokens.save(deps.storage, &token_id, &token)?;
-        } else {
-            return Err(ContractError::InsufficientDeposit {});
+        token.shortterm_rental.damage_deposit = damage_deposit;
+        token.shortterm_rental.claim_window = claim_window;
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "setdamagedepositforshortterm")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+    }
+
+    pub fn setstaydiscountsforshortterm(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        weekly_discount: u64,
+        monthly_discount: u64,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        // ensure we have permissions
+        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
+        if weekly_discount > 10000 || monthly_discount > 10000 {
+            return Err(ContractError::InvalidInput {});
         }
 
+        token.shortterm_rental.weekly_discount = weekly_discount;
+        token.shortterm_rental.monthly_discount = monthly_discount;
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
         Ok(Response::new()
-            .add_attribute("action", "depositforlongtermrental")
+            .add_attribute("action", "setstaydiscountsforshortterm")
             .add_attribute("sender", info.sender)
             .add_attribute("token_id", token_id))
     }
 
-    pub fn setapproveforlongterm(
+    pub fn setplatformpromosforshortterm(
         &self,
         deps: DepsMut,
+        env: Env,
         info: MessageInfo,
+        token_id: String,
+        accept: bool,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        // ensure we have permissions
+        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
+
+        token.shortterm_rental.accepts_platform_promos = accept;
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "setplatformpromosforshortterm")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+    }
+
+    pub fn setfeelinesforshortterm(
+        &self,
+        deps: DepsMut,
         env: Env,
+        info: MessageInfo,
         token_id: String,
-        renting_period: Vec<String>,
-        tenant: String,
-        approved_date: String,
+        fee_lines: Vec<FeeLine>,
     ) -> Result<Response<C>, ContractError> {
         let mut token = self.tokens.load(deps.storage, &token_id)?;
+        // ensure we have permissions
         self.check_can_send(deps.as_ref(), &env, &info, &token)?;
-        let current_time = env.block.time.seconds();
+        self.check_fee_lines(deps.as_ref(), &token, &fee_lines)?;
 
-        let check_in_time = renting_period[0].parse::<u64>();
-        let check_in_time_timestamp;
+        token.shortterm_rental.fee_lines = fee_lines;
+        self.tokens.save(deps.storage, &token_id, &token)?;
 
-        match check_in_time {
-            Ok(timestamp) => {
-                check_in_time_timestamp = timestamp;
-            }
-            Err(_e) => {
-                return Err(ContractError::NotReserved {});
-            }
-        }
+        Ok(Response::new()
+            .add_attribute("action", "setfeelinesforshortterm")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+    }
 
-        if check_in_time_timestamp <= current_time {
-            return Err(ContractError::RentalAlreadyStarted {});
-        }
+    pub fn setinstantbookrulesforshortterm(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        rules: Option<InstantBookRules>,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        // ensure we have permissions
+        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
 
-        let mut position: i32 = -1;
-        for (i, item) in token.rentals.iter().enumerate() {
-            if item.address == Some(Addr::unchecked(tenant.clone()))
-                && item.renting_period[0].to_string() == renting_period[0]
-                && item.renting_period[1].to_string() == renting_period[1]
-                && item.rental_type == true
-            {
-                position = i as i32;
-                break;
-            }
-        }
-        if position == -1 {
-            return Err(ContractError::NotReserved {});
-        }
-        token.rentals[position as usize].approved_date = Some(approved_date);
+        token.shortterm_rental.instant_book = rules;
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "setinstantbookrulesforshortterm")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+    }
+
+    pub fn setbookingwindowforshortterm(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        booking_window: BookingWindow,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        // ensure we have permissions
+        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
 
+        token.shortterm_rental.booking_window = booking_window;
         self.tokens.save(deps.storage, &token_id, &token)?;
 
         Ok(Response::new()
-            .add_attribute("action", "setapproveforlongterm")
+            .add_attribute("action", "setbookingwindowforshortterm")
             .add_attribute("sender", info.sender)
             .add_attribute("token_id", token_id))
     }
 
-    pub fn cancelreservationafterapprovalforlongterm(
+    pub fn setbookingwindowforlongterm(
         &self,
         deps: DepsMut,
+        env: Env,
         info: MessageInfo,
         token_id: String,
-        renting_period: Vec<String>,
+        booking_window: BookingWindow,
     ) -> Result<Response<C>, ContractError> {
         let mut token = self.tokens.load(deps.storage, &token_id)?;
+        // ensure we have permissions
+        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
 
-        let mut position: i32 = -1;
-        for (i, item) in token.rentals.iter().enumerate() {
-            if item.address == Some(info.sender.clone())
-                && item.renting_period[0].to_string() == renting_period[0]
-                && item.renting_period[1].to_string() == renting_period[1]
-                && item.rental_type == true
-            {
-                if item.approved_date.is_none() {
-                    return Err(ContractError::NotApproved {});
-                } else {
-                    position = i as i32;
-                }
+        token.longterm_rental.booking_window = booking_window;
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "setbookingwindowforlongterm")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn createpromocode(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        code: String,
+        token_id: Option<String>,
+        discount: PromoDiscount,
+        max_uses: Option<u32>,
+        expires: Option<Expiration>,
+    ) -> Result<Response<C>, ContractError> {
+        match &token_id {
+            Some(token_id) => {
+                let token = self.tokens.load(deps.storage, token_id)?;
+                self.check_can_send(deps.as_ref(), &env, &info, &token)?;
             }
+            None => cw_ownable::assert_owner(deps.storage, &info.sender)?,
+        }
+        let valid_discount = match &discount {
+            PromoDiscount::Percent { bps } => *bps > 0 && *bps <= 10000,
+            PromoDiscount::Fixed { amount } => *amount > Uint128::new(0),
+        };
+        if code.is_empty() || code.len() > 32 || !valid_discount {
+            return Err(ContractError::InvalidInput {});
+        }
+        if let Some(expires) = &expires {
+            if expires.is_expired(&env.block) {
+                return Err(ContractError::Expired {});
+            }
+        }
+        if self.promo_codes.has(deps.storage, &code) {
+            return Err(ContractError::InvalidInput {});
         }
 
-        if position != -1 {
-            token.rentals[position as usize].cancelled = true;
-            self.tokens.save(deps.storage, &token_id, &token)?;
-            Ok(Response::new()
-                .add_attribute("action", "cancelreservationafterapprovalforlongterm")
-                .add_attribute("sender", info.sender)
-                .add_attribute("token_id", token_id))
-        } else {
-            return Err(ContractError::NotReserved {});
+        self.promo_codes.save(
+            deps.storage,
+            &code,
+            &PromoCode {
+                code: code.clone(),
+                issuer: info.sender.to_string(),
+                token_id,
+                discount,
+                max_uses,
+                uses: 0,
+                expires,
+            },
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("action", "createpromocode")
+            .add_attribute("sender", info.sender)
+            .add_attribute("code", code))
+    }
+
+    pub fn revokepromocode(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        code: String,
+    ) -> Result<Response<C>, ContractError> {
+        let promo = self.promo_codes.load(deps.storage, &code)?;
+        if promo.issuer != info.sender.as_str() {
+            cw_ownable::assert_owner(deps.storage, &info.sender)?;
         }
+        self.promo_codes.remove(deps.storage, &code);
+
+        Ok(Response::new()
+            .add_attribute("action", "revokepromocode")
+            .add_attribute("sender", info.sender)
+            .add_attribute("code", code))
     }
 
-    pub fn finalizelongtermrental(
+    pub fn setunlistforshorttermrental(
         &self,
         deps: DepsMut,
         env: Env,
         info: MessageInfo,
         token_id: String,
-        tenant: String,
-        renting_period: Vec<String>,
     ) -> Result<Response<C>, ContractError> {
         let mut token = self.tokens.load(deps.storage, &token_id)?;
-
+        // ensure we have permissions
         self.check_can_send(deps.as_ref(), &env, &info, &token)?;
+        self.check_can_edit_short(&env, &token)?;
 
-        let mut position: i32 = -1;
-        let mut amount = Uint128::from(0u64);
+        token.shortterm_rental.islisted = None;
+        token.shortterm_rental.price_per_day = 0u128;
+        token.shortterm_rental.available_period = vec![];
+        token.shortterm_rental.availability = AvailabilityCalendar::default();
+        token.shortterm_rental.price_rules = vec![];
+        token.shortterm_rental.max_guests = None;
+        token.shortterm_rental.included_guests = 0;
+        token.shortterm_rental.extra_guest_fee = 0u128;
+        token.shortterm_rental.damage_deposit = 0u128;
+        token.shortterm_rental.claim_window = 0u64;
+        token.shortterm_rental.response_window = None;
+        token.shortterm_rental.weekly_discount = 0u64;
+        token.shortterm_rental.monthly_discount = 0u64;
+        token.shortterm_rental.fee_lines = vec![];
+        token.shortterm_rental.instant_book = None;
+        token.shortterm_rental.booking_window = BookingWindow::default();
+        token.shortterm_rental.auto_approve = false;
+        token.shortterm_rental.minimum_stay = 0u64;
+        token.shortterm_rental.cancellation = vec![];
+        token.shortterm_rental.denom = "".to_string();
+
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "setunlistforshorttermrental")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn setreservationforshortterm(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        renting_period: Vec<String>,
+        guests: usize,
+        promo_code: Option<String>,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        self.check_host_can_list(deps.storage, &token.owner.address)?;
+        let new_checkin = renting_period[0].parse::<u64>();
+        let new_checkin_timestamp = match new_checkin {
+            Ok(timestamp) => timestamp,
+            Err(_e) => {
+                return Err(ContractError::NotReserved {});
+            }
+        };
+        let new_checkout = renting_period[1].parse::<u64>();
+        let new_checkout_timestamp = match new_checkout {
+            Ok(timestamp) => timestamp,
+            Err(_e) => {
+                return Err(ContractError::NotReserved {});
+            }
+        };
+
+        if ((new_checkout_timestamp - new_checkin_timestamp) / 86400)
+            < token.shortterm_rental.minimum_stay
+        {
+            return Err(ContractError::LessThanMinimum {});
+        }
+
+        if !token
+            .shortterm_rental
+            .availability
+            .is_available(new_checkin_timestamp, new_checkout_timestamp)
+        {
+            return Err(ContractError::NotAvailable {});
+        }
+
+        if token
+            .shortterm_rental
+            .max_guests
+            .is_some_and(|max_guests| guests > max_guests)
+        {
+            return Err(ContractError::TooManyGuests {});
+        }
+
+        let placetoreserve = reservation_slot(
+            &token.rentals,
+            new_checkin_timestamp,
+            new_checkout_timestamp,
+            env.block.time.seconds(),
+            &token.shortterm_rental.booking_window,
+        )?;
+
+        if info.funds[0].denom != token.shortterm_rental.denom {
+            return Err(ContractError::InvalidDeposit {});
+        }
+        let sent_amount = info.funds[0].amount;
+        let promo = match &promo_code {
+            Some(code) => Some(self.redeemable_promo_code(
+                deps.storage,
+                &env.block,
+                code,
+                &token_id,
+                &token,
+            )?),
+            None => None,
+        };
+        let quote = self.quote_shortterm(
+            deps.storage,
+            &token,
+            new_checkin_timestamp,
+            new_checkout_timestamp,
+            env.block.time.seconds(),
+            guests,
+            promo.as_ref().map(|promo| &promo.discount),
+        )?;
+        let rent_amount = quote.rent_amount;
+        if sent_amount < quote.total {
+            return Err(ContractError::InsufficientDeposit {});
+        }
+
+        if quote.damage_deposit > Uint128::new(0)
+            && self
+                .damage_deposits
+                .has(deps.storage, (&token_id, new_checkin_timestamp))
+        {
+            return Err(ContractError::UnavailablePeriod {});
+        }
+
+        // nothing is written before this point, so a group booking that fails here can
+        // still refund its payers
+        self.increase_balance(
+            deps.storage,
+            info.funds[0].denom.clone(),
+            sent_amount - rent_amount - quote.fee - quote.damage_deposit - fees_total(&quote.fees),
+        )?;
+
+        if quote.damage_deposit > Uint128::new(0) {
+            self.damage_deposits.save(
+                deps.storage,
+                (&token_id, new_checkin_timestamp),
+                &DamageDeposit {
+                    token_id: token_id.clone(),
+                    traveler: info.sender.to_string(),
+                    renting_period: vec![new_checkin_timestamp, new_checkout_timestamp],
+                    denom: token.shortterm_rental.denom.clone(),
+                    amount: quote.damage_deposit,
+                    claimed: Uint128::new(0),
+                    reason: None,
+                    disputed: false,
+                    claim_deadline: new_checkout_timestamp
+                        .saturating_add(token.shortterm_rental.claim_window),
+                    claimed_at: None,
+                    payers: vec![],
+                },
+            )?;
+        }
+
+        let auto_approve = self.auto_approves(
+            deps.storage,
+            &token.owner.address,
+            token.shortterm_rental.auto_approve,
+        )? && self.instant_books(
+            deps.storage,
+            token.shortterm_rental.instant_book.as_ref(),
+            info.sender.as_str(),
+            (new_checkout_timestamp - new_checkin_timestamp) / 86400,
+            guests,
+        )?;
+        let traveler = Rental {
+            denom: token.shortterm_rental.denom.clone(),
+            rental_type: false,
+            approved_date: None,
+            deposit_amount: rent_amount,
+            renting_period: vec![new_checkin_timestamp, new_checkout_timestamp],
+            address: Some(info.sender.clone()),
+            approved: auto_approve,
+            cancelled: false,
+            guests,
+            payers: vec![],
+            platform_fee: Some(quote.fee),
+            respond_by: respond_by(
+                auto_approve,
+                token.shortterm_rental.response_window,
+                env.block.time.seconds(),
+            ),
+            discount: quote.discount,
+            promo_code,
+            promo_discount: promo.as_ref().map(|promo| promo.discount.clone()),
+            fees: quote.fees,
+            access_instructions: None,
+        };
+        if let Some(mut promo) = promo {
+            promo.uses += 1;
+            self.promo_codes.save(deps.storage, &promo.code, &promo)?;
+        }
+
+        // token.shortterm_rental.deposit_amount += sent_amount;
+        token.rentals.insert(placetoreserve, traveler);
+        self.lock_host_bond(deps.storage, &token.owner.address, &token.rentals)?;
+
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
+        // if token.shortterm_rental.auto_approve {
+        //     Ok(Response::new()
+        //         .add_attribute("action", "setreservationforshortterm")
+        //         .add_attribute("sender", info.sender)
+        //         .add_attribute("token_id", token_id)
+        //         .add_message(BankMsg::Send {
+        //             to_address: token.owner.into_string(),
+        //             amount: vec![Coin {
+        //                 denom: token.shortterm_rental.denom,
+        //                 amount: sent_amount,
+        //             }],
+        //         }))
+        // } else {
+        Ok(Response::new()
+            .add_attribute("action", "setreservationforshortterm")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+        // }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn creategroupreservationforshortterm(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        renting_period: Vec<String>,
+        guests: usize,
+        shares: Vec<PayerShare>,
+        deadline: u64,
+    ) -> Result<Response<C>, ContractError> {
+        let token = self.tokens.load(deps.storage, &token_id)?;
+        let (check_in, check_out) = parse_stay(&renting_period)?;
+
+        if deadline <= env.block.time.seconds() {
+            return Err(ContractError::DeadlinePassed {});
+        }
+        if deadline > check_in || shares.is_empty() {
+            return Err(ContractError::InvalidInput {});
+        }
+        for (i, share) in shares.iter().enumerate() {
+            deps.api.addr_validate(&share.payer)?;
+            if share.amount.is_zero() || shares[..i].iter().any(|other| other.payer == share.payer)
+            {
+                return Err(ContractError::InvalidInput {});
+            }
+        }
+        let quote = self.quote_shortterm(
+            deps.storage,
+            &token,
+            check_in,
+            check_out,
+            env.block.time.seconds(),
+            guests,
+            None,
+        )?;
+        if shares.iter().map(|share| share.amount).sum::<Uint128>() < quote.total {
+            return Err(ContractError::InsufficientDeposit {});
+        }
+
+        let group_id = self.group_count.may_load(deps.storage)?.unwrap_or_default() + 1;
+        self.group_count.save(deps.storage, &group_id)?;
+        let mut group = GroupReservation {
+            id: group_id,
+            token_id: token_id.clone(),
+            organizer: info.sender.clone(),
+            renting_period,
+            guests,
+            denom: token.shortterm_rental.denom,
+            shares: shares
+                .into_iter()
+                .map(|share| PayerShare {
+                    paid: false,
+                    ..share
+                })
+                .collect(),
+            deadline,
+        };
+        if !info.funds.is_empty() {
+            self.pay_group_share(&mut group, &info)?;
+        }
+        let booking = self.book_group_reservation(deps, env, group)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "creategroupreservationforshortterm")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_attribute("group_id", group_id.to_string())
+            .add_attributes(booking.attributes)
+            .add_submessages(booking.messages))
+    }
+
+    pub fn fundgroupreservation(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        group_id: u64,
+    ) -> Result<Response<C>, ContractError> {
+        let mut group = self
+            .group_reservations
+            .may_load(deps.storage, group_id)?
+            .ok_or(ContractError::NoGroupReservation {})?;
+        if env.block.time.seconds() >= group.deadline {
+            return Err(ContractError::DeadlinePassed {});
+        }
+        self.pay_group_share(&mut group, &info)?;
+        let token_id = group.token_id.clone();
+        let booking = self.book_group_reservation(deps, env, group)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "fundgroupreservation")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_attribute("group_id", group_id.to_string())
+            .add_attributes(booking.attributes)
+            .add_submessages(booking.messages))
+    }
+
+    pub fn refundgroupreservation(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        group_id: u64,
+    ) -> Result<Response<C>, ContractError> {
+        let group = self
+            .group_reservations
+            .may_load(deps.storage, group_id)?
+            .ok_or(ContractError::NoGroupReservation {})?;
+        if env.block.time.seconds() < group.deadline && info.sender != group.organizer {
+            return Err(ContractError::FundingOpen {});
+        }
+        self.group_reservations.remove(deps.storage, group_id);
+        let refunds = self.refund_group_shares(deps.storage, &group)?;
 
+        Ok(Response::new()
+            .add_attribute("action", "refundgroupreservation")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", group.token_id)
+            .add_attribute("group_id", group_id.to_string())
+            .add_messages(refunds))
+    }
+
+    /// Moves a reservation to new dates. Short-term stays are repriced and the difference is
+    /// charged or refunded, long-term deposits do not depend on the dates. Either needs the
+    /// host's approval again unless the listing auto-approves.
+    pub fn modifyreservation(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        renting_period: Vec<String>,
+        new_renting_period: Vec<String>,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        self.check_host_bond(deps.storage, &token.owner.address)?;
+        let check_in = parse_check_in(&renting_period)?;
+        let (new_check_in, new_check_out) = parse_stay(&new_renting_period)?;
+        if new_check_in >= new_check_out {
+            return Err(ContractError::InvalidInput {});
+        }
         let current_time = env.block.time.seconds();
+        if check_in <= current_time || new_check_in <= current_time {
+            return Err(ContractError::RentalAlreadyStarted {});
+        }
+
+        let position = token
+            .rentals
+            .iter()
+            .position(|item| {
+                item.address == Some(info.sender.clone())
+                    && item.renting_period[0].to_string() == renting_period[0]
+                    && item.renting_period[1].to_string() == renting_period[1]
+                    && !item.cancelled
+            })
+            .ok_or(ContractError::NotReserved {})?;
+        let mut rental = token.rentals.remove(position);
+
+        let (minimum_stay, auto_approve, response_window, instant_book) = if rental.rental_type {
+            (
+                token.longterm_rental.minimum_stay,
+                token.longterm_rental.auto_approve,
+                token.longterm_rental.response_window,
+                token.longterm_rental.instant_book.as_ref(),
+            )
+        } else {
+            (
+                token.shortterm_rental.minimum_stay,
+                token.shortterm_rental.auto_approve,
+                token.shortterm_rental.response_window,
+                token.shortterm_rental.instant_book.as_ref(),
+            )
+        };
+        let auto_approve = self.auto_approves(deps.storage, &token.owner.address, auto_approve)?
+            && self.instant_books(
+                deps.storage,
+                instant_book,
+                info.sender.as_str(),
+                (new_check_out - new_check_in) / 86400,
+                rental.guests,
+            )?;
+        if (new_check_out - new_check_in) / 86400 < minimum_stay {
+            return Err(ContractError::LessThanMinimum {});
+        }
+        if !rental.rental_type
+            && !token
+                .shortterm_rental
+                .availability
+                .is_available(new_check_in, new_check_out)
+        {
+            return Err(ContractError::NotAvailable {});
+        }
+        let booking_window = if rental.rental_type {
+            token.longterm_rental.booking_window.clone()
+        } else {
+            token.shortterm_rental.booking_window.clone()
+        };
+        let placetoreserve = reservation_slot(
+            &token.rentals,
+            new_check_in,
+            new_check_out,
+            current_time,
+            &booking_window,
+        )?;
+
+        let sent_amount = match info.funds.first() {
+            Some(coin) if coin.denom != rental.denom => {
+                return Err(ContractError::InvalidDeposit {});
+            }
+            Some(coin) => coin.amount,
+            None => Uint128::new(0),
+        };
+        let refund;
+        if !rental.rental_type {
+            // the code was redeemed with the reservation, it applies even if used up or
+            // revoked since
+            let quote = self.quote_shortterm(
+                deps.storage,
+                &token,
+                new_check_in,
+                new_check_out,
+                current_time,
+                rental.guests,
+                rental.promo_discount.as_ref(),
+            )?;
+            let old_fee = self.held_platform_fee(deps.storage, &rental)?;
+            let paid = rental.deposit_amount + old_fee + fees_total(&rental.fees) + sent_amount;
+            let due = quote.rent_amount + quote.fee + fees_total(&quote.fees);
+            if paid < due {
+                return Err(ContractError::InsufficientDeposit {});
+            }
+            refund = paid - due;
+            rental.deposit_amount = quote.rent_amount;
+            rental.platform_fee = Some(quote.fee);
+            rental.discount = quote.discount;
+            rental.fees = quote.fees;
+
+            if let Some(mut deposit) = self
+                .damage_deposits
+                .may_load(deps.storage, (&token_id, check_in))?
+            {
+                if new_check_in != check_in
+                    && self
+                        .damage_deposits
+                        .has(deps.storage, (&token_id, new_check_in))
+                {
+                    return Err(ContractError::UnavailablePeriod {});
+                }
+                self.damage_deposits
+                    .remove(deps.storage, (&token_id, check_in));
+                deposit.claim_deadline = (deposit.claim_deadline - deposit.renting_period[1])
+                    .saturating_add(new_check_out);
+                deposit.renting_period = vec![new_check_in, new_check_out];
+                self.damage_deposits
+                    .save(deps.storage, (&token_id, new_check_in), &deposit)?;
+            }
+        } else {
+            // fee lines charged per night follow the new dates, the deposit has to keep
+            // covering the first month
+            let quote = self.quote_longterm(
+                deps.storage,
+                &token,
+                new_check_in,
+                new_check_out,
+                rental.guests,
+            )?;
+            let paid = fees_total(&rental.fees) + sent_amount;
+            let due = fees_total(&quote.fees);
+            if paid < due || rental.deposit_amount < quote.rent_amount {
+                return Err(ContractError::InsufficientDeposit {});
+            }
+            refund = paid - due;
+            rental.fees = quote.fees;
+        }
+
+        rental.renting_period = vec![new_check_in, new_check_out];
+        rental.approved = auto_approve;
+        if !auto_approve {
+            rental.approved_date = None;
+        } else if rental.rental_type && rental.approved_date.is_none() {
+            rental.approved_date = Some(current_time.to_string());
+        }
+        rental.respond_by = respond_by(auto_approve, response_window, current_time);
+        rental.access_instructions = None;
+        let denom = rental.denom.clone();
+        let payers = rental.payers.clone();
+        token.rentals.insert(placetoreserve, rental);
+        self.lock_host_bond(deps.storage, &token.owner.address, &token.rentals)?;
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
+        let mut response = Response::new()
+            .add_attribute("action", "modifyreservation")
+            .add_attribute("sender", info.sender.clone())
+            .add_attribute("token_id", token_id);
+        if refund > Uint128::new(0) {
+            response = response.add_messages(self.refund_payers(
+                deps.storage,
+                &payers,
+                info.sender.as_str(),
+                &denom,
+                refund,
+            )?);
+        }
+        Ok(response)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn reviewstay(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        renting_period: Vec<String>,
+        rating: u8,
+        review_hash: String,
+    ) -> Result<Response<C>, ContractError> {
+        if !(1..=5).contains(&rating) || review_hash.is_empty() || review_hash.len() > 128 {
+            return Err(ContractError::InvalidInput {});
+        }
+        let check_in = parse_check_in(&renting_period)?;
+        let mut stay = self
+            .stays
+            .may_load(deps.storage, (&token_id, check_in))?
+            .ok_or(ContractError::NoStay {})?;
+        if env.block.time.seconds() > stay.review_by {
+            return Err(ContractError::ReviewWindowClosed {});
+        }
+
+        let by_host = info.sender == stay.host;
+        let subject = if by_host {
+            if stay.host_reviewed {
+                return Err(ContractError::AlreadyReviewed {});
+            }
+            stay.host_reviewed = true;
+            let mut traveler_rating = self
+                .traveler_ratings
+                .may_load(deps.storage, &stay.traveler)?
+                .unwrap_or_default();
+            traveler_rating.add(rating);
+            self.traveler_ratings
+                .save(deps.storage, &stay.traveler, &traveler_rating)?;
+            stay.traveler.clone()
+        } else if info.sender == stay.traveler {
+            if stay.traveler_reviewed {
+                return Err(ContractError::AlreadyReviewed {});
+            }
+            stay.traveler_reviewed = true;
+            let mut token_rating = self
+                .token_ratings
+                .may_load(deps.storage, &token_id)?
+                .unwrap_or_default();
+            token_rating.add(rating);
+            self.token_ratings
+                .save(deps.storage, &token_id, &token_rating)?;
+            let mut host_rating = self
+                .host_ratings
+                .may_load(deps.storage, &stay.host)?
+                .unwrap_or_default();
+            host_rating.add(rating);
+            self.host_ratings
+                .save(deps.storage, &stay.host, &host_rating)?;
+            stay.host.clone()
+        } else {
+            return Err(ContractError::Ownership(OwnershipError::NotOwner));
+        };
+
+        if stay.host_reviewed && stay.traveler_reviewed {
+            self.stays.remove(deps.storage, (&token_id, check_in));
+        } else {
+            self.stays
+                .save(deps.storage, (&token_id, check_in), &stay)?;
+        }
+
+        let review_id = self
+            .review_count
+            .may_load(deps.storage)?
+            .unwrap_or_default()
+            + 1;
+        self.review_count.save(deps.storage, &review_id)?;
+        self.reviews.save(
+            deps.storage,
+            review_id,
+            &Review {
+                id: review_id,
+                token_id: token_id.clone(),
+                renting_period: stay.renting_period,
+                author: info.sender.to_string(),
+                subject,
+                by_host,
+                rating,
+                review_hash,
+                created_at: env.block.time.seconds(),
+            },
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("action", "reviewstay")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_attribute("review_id", review_id.to_string()))
+    }
+
+    pub fn cancelreservationbyhost(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        traveler: String,
+        renting_period: Vec<String>,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
+
+        let position = token
+            .rentals
+            .iter()
+            .position(|item| {
+                item.address == Some(Addr::unchecked(traveler.clone()))
+                    && item.renting_period[0].to_string() == renting_period[0]
+                    && item.renting_period[1].to_string() == renting_period[1]
+                    && !item.cancelled
+            })
+            .ok_or(ContractError::NotReserved {})?;
+        if token.rentals[position].is_pending() {
+            return Err(ContractError::NotApproved {});
+        }
+        let current_time = env.block.time.seconds();
+        if token.rentals[position].renting_period[0] <= current_time {
+            return Err(ContractError::RentalAlreadyStarted {});
+        }
+        let rental = token.rentals.remove(position);
+        self.release_promo_use(deps.storage, &rental)?;
+
+        let mut refund = rental.deposit_amount + fees_total(&rental.fees);
+        let mut fee_shortfall = Uint128::new(0);
+        let mut deposit_refund = vec![];
+        if !rental.rental_type {
+            let held_fee = self.held_platform_fee(deps.storage, &rental)?;
+            fee_shortfall = self.charged_platform_fee(deps.storage, &rental)? - held_fee;
+            refund += held_fee;
+            deposit_refund = self.settle_damage_deposit(
+                deps.storage,
+                &token_id,
+                rental.renting_period[0],
+                &token,
+            )?;
+        }
+
+        let host = token.owner.address.clone();
+        let policy = self.host_policy.may_load(deps.storage)?.unwrap_or_default();
+        let mut record = self
+            .host_records
+            .may_load(deps.storage, &host)?
+            .unwrap_or_default();
+        record.cancellations += 1;
+        record.last_cancellation = Some(current_time);
+        self.host_records.save(deps.storage, &host, &record)?;
+
+        // a platform fee already withdrawn is refunded in full out of the host bond, the
+        // penalty as far as the bond covers it
+        let mut penalty = Uint128::new(0);
+        match self.host_bonds.may_load(deps.storage, &host)? {
+            Some(mut bond) if bond.denom == rental.denom && bond.amount >= fee_shortfall => {
+                bond.amount -= fee_shortfall;
+                refund += fee_shortfall;
+                penalty = rental
+                    .deposit_amount
+                    .multiply_ratio(policy.cancellation_penalty, 10000u64)
+                    .min(bond.amount);
+                bond.amount -= penalty;
+                self.host_bonds.save(deps.storage, &host, &bond)?;
+            }
+            _ if !fee_shortfall.is_zero() => return Err(ContractError::FeeNotCovered {}),
+            _ => {}
+        }
+        refund += penalty;
+
+        if !policy.can_list(&record) {
+            token.shortterm_rental.islisted = None;
+            token.longterm_rental.islisted = None;
+        }
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
+        let mut response = Response::new()
+            .add_attribute("action", "cancelreservationbyhost")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_attribute("penalty", penalty);
+        if refund > Uint128::new(0) {
+            response = response.add_messages(self.refund_payers(
+                deps.storage,
+                &rental.payers,
+                &traveler,
+                &rental.denom,
+                refund,
+            )?);
+        }
+        Ok(response.add_messages(deposit_refund))
+    }
+
+    pub fn posthostbond(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+    ) -> Result<Response<C>, ContractError> {
+        if info.funds.is_empty() {
+            return Err(ContractError::InsufficientDeposit {});
+        }
+        self.add_host_bond(deps.storage, info.sender.as_str(), &info.funds)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "posthostbond")
+            .add_attribute("sender", info.sender))
+    }
+
+    pub fn withdrawhostbond(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response<C>, ContractError> {
+        let bond = self
+            .host_bonds
+            .may_load(deps.storage, info.sender.as_str())?
+            .ok_or(ContractError::UnavailableAmount {})?;
+        let record = self
+            .host_records
+            .may_load(deps.storage, info.sender.as_str())?
+            .unwrap_or_default();
+        if record.bond_locked_until >= env.block.time.seconds() {
+            return Err(ContractError::RentalActive {});
+        }
+        self.host_bonds.remove(deps.storage, info.sender.as_str());
+
+        let mut response = Response::new()
+            .add_attribute("action", "withdrawhostbond")
+            .add_attribute("sender", info.sender.clone());
+        if bond.amount > Uint128::new(0) {
+            response = response.add_message(self.payout(
+                deps.storage,
+                info.sender,
+                bond.denom,
+                bond.amount,
+            )?);
+        }
+        Ok(response)
+    }
+
+    pub fn sethostpolicy(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        policy: HostPolicy,
+    ) -> Result<Response<C>, ContractError> {
+        cw_ownable::assert_owner(deps.storage, &info.sender)?;
+        self.host_policy.save(deps.storage, &policy)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "sethostpolicy")
+            .add_attribute("sender", info.sender))
+    }
+
+    pub fn setkycattestation(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        traveler: String,
+        attested: bool,
+    ) -> Result<Response<C>, ContractError> {
+        cw_ownable::assert_owner(deps.storage, &info.sender)?;
+        deps.api.addr_validate(&traveler)?;
+
+        let mut profile = self
+            .traveler_profiles
+            .may_load(deps.storage, &traveler)?
+            .unwrap_or_default();
+        profile.kyc_attested_at = if attested {
+            Some(env.block.time.seconds())
+        } else {
+            None
+        };
+        self.traveler_profiles
+            .save(deps.storage, &traveler, &profile)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "setkycattestation")
+            .add_attribute("sender", info.sender)
+            .add_attribute("traveler", traveler))
+    }
+
+    /// Anyone can drop a reservation the host left unapproved past its deadline,
+    /// refunding the traveler in full
+    pub fn expirereservation(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        traveler: String,
+        renting_period: Vec<String>,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+
+        let position = token
+            .rentals
+            .iter()
+            .position(|item| {
+                item.address == Some(Addr::unchecked(traveler.clone()))
+                    && item.renting_period[0].to_string() == renting_period[0]
+                    && item.renting_period[1].to_string() == renting_period[1]
+            })
+            .ok_or(ContractError::NotReserved {})?;
+        if !token.rentals[position].is_pending() {
+            return Err(ContractError::ApprovedAlready {});
+        }
+        if !token.rentals[position]
+            .respond_by
+            .is_some_and(|respond_by| respond_by <= env.block.time.seconds())
+        {
+            return Err(ContractError::NotExpired {});
+        }
+        let rental = token.rentals.remove(position);
+        self.tokens.save(deps.storage, &token_id, &token)?;
+        self.release_promo_use(deps.storage, &rental)?;
+
+        let mut refund = rental.deposit_amount + fees_total(&rental.fees);
+        let mut deposit_refund = vec![];
+        if !rental.rental_type {
+            refund += self.held_platform_fee(deps.storage, &rental)?;
+            deposit_refund = self.settle_damage_deposit(
+                deps.storage,
+                &token_id,
+                rental.renting_period[0],
+                &token,
+            )?;
+        }
+
+        let mut response = Response::new()
+            .add_attribute("action", "expirereservation")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id);
+        if refund > Uint128::new(0) {
+            response = response.add_messages(self.refund_payers(
+                deps.storage,
+                &rental.payers,
+                &traveler,
+                &rental.denom,
+                refund,
+            )?);
+        }
+        Ok(response.add_messages(deposit_refund))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn setapproveforshortterm(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        env: Env,
+        token_id: String,
+        renting_period: Vec<String>,
+        traveler: String,
+        access_instructions: Option<Binary>,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
+        if access_instructions
+            .as_ref()
+            .is_some_and(|instructions| instructions.len() > 4096)
+        {
+            return Err(ContractError::InvalidInput {});
+        }
+
+        let current_time = env.block.time.seconds();
+
+        let check_in_time = renting_period[0].parse::<u64>();
+        let check_in_time_timestamp = match check_in_time {
+            Ok(timestamp) => timestamp,
+            Err(_e) => {
+                return Err(ContractError::NotReserved {});
+            }
+        };
+
+        if check_in_time_timestamp <= current_time {
+            return Err(ContractError::RentalAlreadyStarted {});
+        }
+
+        let mut position: i32 = -1;
+        // let mut amount = Uint128::from(0u64);
+        for (i, item) in token.rentals.iter().enumerate() {
+            if item.address == Some(Addr::unchecked(traveler.clone()))
+                // && item.renting_period == renting_period
+                && item.renting_period[0].to_string() == renting_period[0]
+                && item.renting_period[1].to_string() == renting_period[1]
+                && !item.cancelled
+                && (!item.approved || access_instructions.is_some())
+            {
+                position = i as i32;
+                // amount = item.deposit_amount;
+                break;
+            }
+        }
+        if position == -1 {
+            return Err(ContractError::ApprovedAlready {});
+        }
+        token.rentals[position as usize].approved = true;
+        if access_instructions.is_some() {
+            token.rentals[position as usize].access_instructions = access_instructions;
+        }
+
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
+        Ok(
+            Response::new()
+                .add_attribute("action", "setreservationforshortterm")
+                .add_attribute("sender", info.sender)
+                .add_attribute("token_id", token_id), // .add_message(BankMsg::Send {
+                                                      //     to_address: token.owner.into_string(),
+                                                      //     amount: vec![Coin {
+                                                      //         denom: token.shortterm_rental.denom,
+                                                      //         amount: amount,
+                                                      //     }],
+                                                      // })
+        )
+    }
+
+    pub fn rejectreservationforshortterm(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        traveler: String,
+        renting_period: Vec<String>,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
+
+        let mut position: i32 = -1;
+        let mut refundable_amount: Uint128 = Uint128::new(0);
+        for (i, item) in token.rentals.iter().enumerate() {
+            if item.address == Some(Addr::unchecked(traveler.clone()))
+                && item.renting_period[0].to_string() == renting_period[0]
+                && item.renting_period[1].to_string() == renting_period[1]
+                && !item.rental_type
+            {
+                position = i as i32;
+                refundable_amount = item.deposit_amount;
+            }
+        }
+        if position == -1 {
+            return Err(ContractError::NotReserved {});
+        }
+        let mut rental = token.rentals.remove(position as usize);
+        self.tokens.save(deps.storage, &token_id, &token)?;
+        self.keep_platform_fee(deps.storage, &mut rental)?;
+        self.release_promo_use(deps.storage, &rental)?;
+        let deposit_refund =
+            self.settle_damage_deposit(deps.storage, &token_id, rental.renting_period[0], &token)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "rejectreservationforshortterm")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_messages(self.refund_payers(
+                deps.storage,
+                &rental.payers,
+                &traveler,
+                &token.shortterm_rental.denom,
+                refundable_amount + fees_total(&rental.fees),
+            )?)
+            .add_messages(deposit_refund))
+    }
+
+    pub fn cancelreservationafterapprovalforshortterm(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        env: Env,
+        token_id: String,
+        renting_period: Vec<String>,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+
+        let mut position: i32 = -1;
+        let mut amount = Uint128::new(0);
+        let traveler_address = info.sender.to_string();
+        for (i, item) in token.rentals.iter().enumerate() {
+            if item.address == Some(info.sender.clone())
+                && item.renting_period[0].to_string() == renting_period[0]
+                && item.renting_period[1].to_string() == renting_period[1]
+                && !item.rental_type
+            {
+                if item.approved && !item.cancelled {
+                    position = i as i32;
+                    amount = item.deposit_amount;
+                } else {
+                    return Err(ContractError::NotApproved {});
+                }
+            }
+        }
+        let current_time = env.block.time.seconds();
+
+        let check_in_time = renting_period[0].parse::<u64>();
+        let check_in_time_timestamp = match check_in_time {
+            Ok(timestamp) => timestamp,
+            Err(_e) => {
+                return Err(ContractError::NotReserved {});
+            }
+        };
+
+        if check_in_time_timestamp <= current_time {
+            return Err(ContractError::RentalAlreadyStarted {});
+        }
+
+        let percentage = refund_percentage(
+            &token.shortterm_rental.cancellation,
+            check_in_time_timestamp,
+            current_time,
+        );
+        let mut refundable_amount = Uint128::new((amount.u128() * u128::from(percentage)) / 100);
+
+        if position != -1 {
+            // token.rentals.remove(position as usize);
+
+            token.rentals[position as usize].cancelled = true;
+            token.rentals[position as usize].access_instructions = None;
+            token.rentals[position as usize].deposit_amount = amount - refundable_amount;
+            self.keep_platform_fee(deps.storage, &mut token.rentals[position as usize])?;
+            self.release_promo_use(deps.storage, &token.rentals[position as usize])?;
+            refundable_amount +=
+                refund_fees(&mut token.rentals[position as usize].fees, percentage);
+
+            self.tokens.save(deps.storage, &token_id, &token)?;
+            let deposit_refund = self.settle_damage_deposit(
+                deps.storage,
+                &token_id,
+                check_in_time_timestamp,
+                &token,
+            )?;
+            if refundable_amount > Uint128::new(0) {
+                Ok(Response::new()
+                    .add_attribute("action", "cancelreservationafterapprovalforshortterm")
+                    .add_attribute("sender", info.sender)
+                    .add_attribute("token_id", token_id)
+                    .add_messages(self.refund_payers(
+                        deps.storage,
+                        &token.rentals[position as usize].payers,
+                        &traveler_address,
+                        &token.shortterm_rental.denom,
+                        refundable_amount,
+                    )?)
+                    .add_messages(deposit_refund))
+            } else {
+                Ok(Response::new()
+                    .add_attribute("action", "cancelreservationafterapprovalforshortterm")
+                    .add_attribute("sender", info.sender)
+                    .add_attribute("token_id", token_id)
+                    .add_messages(deposit_refund))
+            }
+        } else {
+            Err(ContractError::NotReserved {})
+        }
+    }
+
+    pub fn cancelreservationbeforeapprovalforshortterm(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        token_id: String,
+        renting_period: Vec<String>,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+
+        let mut position: i32 = -1;
+        let mut amount = Uint128::from(0u64);
+        let traveler_address = info.sender.to_string();
+        for (i, item) in token.rentals.iter().enumerate() {
+            if item.address == Some(info.sender.clone())
+                && item.renting_period[0].to_string() == renting_period[0]
+                && item.renting_period[1].to_string() == renting_period[1]
+                && !item.rental_type
+            {
+                if item.approved {
+                    return Err(ContractError::ApprovedAlready {});
+                } else {
+                    position = i as i32;
+                    amount = item.deposit_amount;
+                }
+            }
+        }
+
+        if position != -1 {
+            let mut rental = token.rentals.remove(position as usize);
+            self.tokens.save(deps.storage, &token_id, &token)?;
+            self.keep_platform_fee(deps.storage, &mut rental)?;
+            self.release_promo_use(deps.storage, &rental)?;
+            let deposit_refund = self.settle_damage_deposit(
+                deps.storage,
+                &token_id,
+                rental.renting_period[0],
+                &token,
+            )?;
+            Ok(Response::new()
+                .add_attribute("action", "cancelreservationbeforeapprovalforshortterm")
+                .add_attribute("sender", info.sender)
+                .add_attribute("token_id", token_id)
+                .add_messages(self.refund_payers(
+                    deps.storage,
+                    &rental.payers,
+                    &traveler_address,
+                    &token.shortterm_rental.denom,
+                    amount + fees_total(&rental.fees),
+                )?)
+                .add_messages(deposit_refund))
+        } else {
+            Err(ContractError::NotReserved {})
+        }
+    }
+
+    pub fn finalizeshorttermrental(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        traveler: String,
+        renting_period: Vec<String>,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+
+        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
+
+        let mut position: i32 = -1;
+        let mut amount = Uint128::from(0u64);
+
+        let current_time = env.block.time.seconds();
+
+        let check_out_time = renting_period[1].parse::<u64>();
+        let check_out_time_timestamp = match check_out_time {
+            Ok(timestamp) => timestamp,
+            Err(_e) => {
+                return Err(ContractError::NotReserved {});
+            }
+        };
+
+        let mut target = "".to_string();
+        let mut fees = vec![];
+
+        for (i, item) in token.rentals.iter().enumerate() {
+            if item.address == Some(Addr::unchecked(traveler.clone()))
+                && item.renting_period[0].to_string() == renting_period[0]
+                && item.renting_period[1].to_string() == renting_period[1]
+                && !item.rental_type
+            {
+                position = i as i32;
+                amount = item.deposit_amount;
+
+                if item.cancelled {
+                    target = token.owner.address.to_string();
+                }
+                if !item.cancelled {
+                    if !item.approved {
+                        target = traveler.clone();
+                        // return  Err(ContractError::NotApproved {});
+                    }
+                    if item.approved {
+                        target = token.owner.address.to_string();
+                        let fee_percentage = self.get_fee(deps.storage)?;
+                        self.increase_balance(
+                            deps.storage,
+                            token.shortterm_rental.denom.clone(),
+                            Uint128::new((u128::from(amount) * u128::from(fee_percentage)) / 10000),
+                        )?;
+                        amount -=
+                            Uint128::new((u128::from(amount) * u128::from(fee_percentage)) / 10000);
+                    }
+                }
+            }
+        }
+        if position == -1 {
+            return Err(ContractError::NotReserved {});
+        } else {
+            if check_out_time_timestamp > current_time
+                && !token.rentals[position as usize].cancelled
+            {
+                return Err(ContractError::RentalActive {});
+            }
+
+            let mut rental = token.rentals.remove(position as usize);
+            self.tokens.save(deps.storage, &token_id, &token)?;
+            self.keep_platform_fee(deps.storage, &mut rental)?;
+            if !rental.cancelled && target == token.owner.address {
+                self.record_stay(
+                    deps.storage,
+                    &token_id,
+                    &token.owner.address,
+                    &rental,
+                    env.block.time.seconds(),
+                )?;
+            }
+            fees = rental.fees;
+        }
+
+        // the damage deposit of an approved stay stays held for the claim window
+        let deposit_refund = if target == traveler {
+            let check_in = renting_period[0]
+                .parse::<u64>()
+                .map_err(|_| ContractError::NotReserved {})?;
+            self.settle_damage_deposit(deps.storage, &token_id, check_in, &token)?
+        } else {
+            vec![]
+        };
+
+        let fee_messages = if target == token.owner.address {
+            let (host_fees, messages) =
+                self.settle_fees(deps.storage, &token.shortterm_rental.denom, &fees)?;
+            amount += host_fees;
+            messages
+        } else {
+            amount += fees_total(&fees);
+            vec![]
+        };
+        let messages = if target == token.owner.address {
+            self.pay_rental_income(
+                deps.storage,
+                &token_id,
+                &token,
+                target,
+                &token.shortterm_rental.denom,
+                amount,
+            )?
+        } else if amount > Uint128::new(0) {
+            vec![self.payout(deps.storage, target, token.shortterm_rental.denom, amount)?]
+        } else {
+            vec![]
+        };
+
+        Ok(Response::new()
+            .add_attribute("action", "finalizeshorttermrental")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_messages(messages)
+            .add_messages(fee_messages)
+            .add_messages(deposit_refund))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn claimdamagedeposit(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        renting_period: Vec<String>,
+        amount: u128,
+        reason: String,
+    ) -> Result<Response<C>, ContractError> {
+        let token = self.tokens.load(deps.storage, &token_id)?;
+        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
+
+        let check_in = parse_check_in(&renting_period)?;
+        let mut deposit = self
+            .damage_deposits
+            .may_load(deps.storage, (&token_id, check_in))?
+            .ok_or(ContractError::NoDamageDeposit {})?;
+
+        let current_time = env.block.time.seconds();
+        if current_time < deposit.renting_period[1] {
+            return Err(ContractError::RentalActive {});
+        }
+        if current_time >= deposit.claim_deadline {
+            return Err(ContractError::ClaimWindowClosed {});
+        }
+        if deposit.disputed {
+            return Err(ContractError::DamageDisputed {});
+        }
+        if token
+            .rentals
+            .iter()
+            .any(|item| !item.rental_type && item.renting_period[0] == check_in && !item.approved)
+        {
+            return Err(ContractError::NotApproved {});
+        }
+        if Uint128::new(amount) > deposit.amount {
+            return Err(ContractError::UnavailableAmount {});
+        }
+
+        deposit.claimed = Uint128::new(amount);
+        deposit.reason = Some(reason);
+        deposit.claimed_at = Some(current_time);
+        self.damage_deposits
+            .save(deps.storage, (&token_id, check_in), &deposit)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "claimdamagedeposit")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_attribute("amount", deposit.claimed))
+    }
+
+    pub fn disputedamageclaim(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        renting_period: Vec<String>,
+    ) -> Result<Response<C>, ContractError> {
+        let check_in = parse_check_in(&renting_period)?;
+        let mut deposit = self
+            .damage_deposits
+            .may_load(deps.storage, (&token_id, check_in))?
+            .ok_or(ContractError::NoDamageDeposit {})?;
+
+        if info.sender != deposit.traveler {
+            return Err(ContractError::NotTraveler {});
+        }
+        if deposit.claimed.is_zero() {
+            return Err(ContractError::NoDamageClaim {});
+        }
+        match deposit.dispute_deadline() {
+            Some(deadline) if env.block.time.seconds() < deadline => {}
+            _ => return Err(ContractError::ClaimWindowClosed {}),
+        }
+
+        deposit.disputed = true;
+        self.damage_deposits
+            .save(deps.storage, (&token_id, check_in), &deposit)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "disputedamageclaim")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+    }
+
+    /// The contract owner settles a disputed claim, awarding the host at most the claimed amount
+    pub fn resolvedamagedispute(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        token_id: String,
+        renting_period: Vec<String>,
+        amount: u128,
+    ) -> Result<Response<C>, ContractError> {
+        cw_ownable::assert_owner(deps.storage, &info.sender)?;
+        let token = self.tokens.load(deps.storage, &token_id)?;
+
+        let check_in = parse_check_in(&renting_period)?;
+        let mut deposit = self
+            .damage_deposits
+            .may_load(deps.storage, (&token_id, check_in))?
+            .ok_or(ContractError::NoDamageDeposit {})?;
+
+        if !deposit.disputed {
+            return Err(ContractError::NotDisputed {});
+        }
+        if Uint128::new(amount) > deposit.claimed {
+            return Err(ContractError::UnavailableAmount {});
+        }
+
+        deposit.claimed = Uint128::new(amount);
+        self.damage_deposits
+            .save(deps.storage, (&token_id, check_in), &deposit)?;
+        let messages = self.settle_damage_deposit(deps.storage, &token_id, check_in, &token)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "resolvedamagedispute")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_messages(messages))
+    }
+
+    /// Anyone can release an undisputed deposit once the claim window closes, the host earlier
+    pub fn releasedamagedeposit(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        renting_period: Vec<String>,
+    ) -> Result<Response<C>, ContractError> {
+        let token = self.tokens.load(deps.storage, &token_id)?;
+
+        let check_in = parse_check_in(&renting_period)?;
+        let deposit = self
+            .damage_deposits
+            .may_load(deps.storage, (&token_id, check_in))?
+            .ok_or(ContractError::NoDamageDeposit {})?;
+
+        if deposit.disputed {
+            return Err(ContractError::DamageDisputed {});
+        }
+        let current_time = env.block.time.seconds();
+        // a claim stays open to dispute, even by an early release of the host
+        if !deposit.claimed.is_zero()
+            && deposit
+                .dispute_deadline()
+                .is_some_and(|deadline| current_time < deadline)
+        {
+            return Err(ContractError::ClaimWindowOpen {});
+        }
+        if current_time < deposit.claim_deadline
+            && self
+                .check_can_send(deps.as_ref(), &env, &info, &token)
+                .is_err()
+        {
+            return Err(ContractError::ClaimWindowOpen {});
+        }
+
+        let messages = self.settle_damage_deposit(deps.storage, &token_id, check_in, &token)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "releasedamagedeposit")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_messages(messages))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn setlistforlongtermrental(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        denom: String,
+        price_per_month: u128,
+        auto_approve: bool,
+        available_period: Vec<String>,
+        minimum_stay: u64,
+        cancellation: Vec<CancellationItem>,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        // ensure we have permissions
+        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
+        self.check_can_edit_long(&env, &token)?;
+        self.add_host_bond(deps.storage, &token.owner.address, &info.funds)?;
+        self.check_host_can_list(deps.storage, &token.owner.address)?;
+
+        token.longterm_rental.islisted = Some(true);
+        token.longterm_rental.price_per_month = price_per_month;
+        token.longterm_rental.available_period = available_period;
+        token.longterm_rental.auto_approve = auto_approve;
+        token.longterm_rental.denom = denom;
+        token.longterm_rental.minimum_stay = minimum_stay;
+        token.longterm_rental.cancellation = cancellation;
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "setlistforlongtermrental")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn setguestpolicyforlongterm(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        max_guests: Option<usize>,
+        included_guests: usize,
+        extra_guest_fee: u128,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        // ensure we have permissions
+        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
+        if max_guests.is_some_and(|max_guests| included_guests > max_guests) {
+            return Err(ContractError::InvalidInput {});
+        }
+
+        token.longterm_rental.max_guests = max_guests;
+        token.longterm_rental.included_guests = included_guests;
+        token.longterm_rental.extra_guest_fee = extra_guest_fee;
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "setguestpolicyforlongterm")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+    }
+
+    pub fn setresponsewindowforlongterm(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        response_window: Option<u64>,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        // ensure we have permissions
+        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
+
+        token.longterm_rental.response_window = response_window;
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "setresponsewindowforlongterm")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+    }
+
+    pub fn setfeelinesforlongterm(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        fee_lines: Vec<FeeLine>,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        // ensure we have permissions
+        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
+        self.check_fee_lines(deps.as_ref(), &token, &fee_lines)?;
+
+        token.longterm_rental.fee_lines = fee_lines;
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "setfeelinesforlongterm")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+    }
+
+    pub fn setinstantbookrulesforlongterm(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        rules: Option<InstantBookRules>,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        // ensure we have permissions
+        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
+
+        token.longterm_rental.instant_book = rules;
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "setinstantbookrulesforlongterm")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+    }
+
+    pub fn setunlistforlongtermrental(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        // ensure we have permissions
+        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
+        self.check_can_edit_long(&env, &token)?;
+
+        token.longterm_rental.islisted = None;
+        token.longterm_rental.price_per_month = 0u128;
+        token.longterm_rental.available_period = vec![];
+        token.longterm_rental.auto_approve = false;
+        token.longterm_rental.minimum_stay = 0u64;
+        token.longterm_rental.cancellation = vec![];
+        token.longterm_rental.max_guests = None;
+        token.longterm_rental.included_guests = 0;
+        token.longterm_rental.extra_guest_fee = 0u128;
+        token.longterm_rental.response_window = None;
+        token.longterm_rental.fee_lines = vec![];
+        token.longterm_rental.instant_book = None;
+        token.longterm_rental.booking_window = BookingWindow::default();
+        token.longterm_rental.denom = "".to_string();
+
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "setunlistforlongtermrental")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+    }
+
+    pub fn setreservationforlongterm(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        renting_period: Vec<String>,
+        guests: usize,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        self.check_host_can_list(deps.storage, &token.owner.address)?;
+        let new_checkin = renting_period[0].parse::<u64>();
+        let new_checkin_timestamp = match new_checkin {
+            Ok(timestamp) => timestamp,
+            Err(_e) => {
+                return Err(ContractError::NotReserved {});
+            }
+        };
+        let new_checkout = renting_period[1].parse::<u64>();
+        let new_checkout_timestamp = match new_checkout {
+            Ok(timestamp) => timestamp,
+            Err(_e) => {
+                return Err(ContractError::NotReserved {});
+            }
+        };
+
+        if ((new_checkout_timestamp - new_checkin_timestamp) / 86400)
+            < token.longterm_rental.minimum_stay
+        {
+            return Err(ContractError::LessThanMinimum {});
+        }
+
+        if token
+            .longterm_rental
+            .max_guests
+            .is_some_and(|max_guests| guests > max_guests)
+        {
+            return Err(ContractError::TooManyGuests {});
+        }
+
+        let placetoreserve = reservation_slot(
+            &token.rentals,
+            new_checkin_timestamp,
+            new_checkout_timestamp,
+            env.block.time.seconds(),
+            &token.longterm_rental.booking_window,
+        )?;
+
+        if info.funds[0].denom != token.longterm_rental.denom {
+            return Err(ContractError::InvalidDeposit {});
+        }
+        let quote = self.quote_longterm(
+            deps.storage,
+            &token,
+            new_checkin_timestamp,
+            new_checkout_timestamp,
+            guests,
+        )?;
+        if info.funds[0].amount < quote.total {
+            return Err(ContractError::InsufficientDeposit {});
+        }
+        let fees_amount = fees_total(&quote.fees);
+
+        let auto_approve = self.auto_approves(
+            deps.storage,
+            &token.owner.address,
+            token.longterm_rental.auto_approve,
+        )? && self.instant_books(
+            deps.storage,
+            token.longterm_rental.instant_book.as_ref(),
+            info.sender.as_str(),
+            (new_checkout_timestamp - new_checkin_timestamp) / 86400,
+            guests,
+        )?;
+        let tenant = Rental {
+            denom: token.longterm_rental.denom.clone(),
+            rental_type: true,
+            approved: auto_approve,
+            deposit_amount: info.funds[0].amount - fees_amount,
+            renting_period: vec![new_checkin_timestamp, new_checkout_timestamp],
+            address: Some(info.sender.clone()),
+            // an auto-approved booking is approved as of now, so finalize pays the landlord
+            approved_date: auto_approve.then(|| env.block.time.seconds().to_string()),
+            cancelled: false,
+            guests,
+            payers: vec![],
+            platform_fee: None,
+            respond_by: respond_by(
+                auto_approve,
+                token.longterm_rental.response_window,
+                env.block.time.seconds(),
+            ),
+            discount: Uint128::new(0),
+            promo_code: None,
+            promo_discount: None,
+            fees: quote.fees,
+            access_instructions: None,
+        };
+
+        token.rentals.insert(placetoreserve, tenant);
+        self.lock_host_bond(deps.storage, &token.owner.address, &token.rentals)?;
+
+        self.tokens.save(deps.storage, &token_id, &token)?;
+        Ok(Response::new()
+            .add_attribute("action", "setreservationforlongterm")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+    }
+
+    pub fn cancelreservationbeforeapprovalforlongterm(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        token_id: String,
+        renting_period: Vec<String>,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+
+        let mut position: i32 = -1;
+        let mut amount = Uint128::from(0u64);
+        let tenant_address = info.sender.to_string();
+        for (i, item) in token.rentals.iter().enumerate() {
+            if item.address == Some(info.sender.clone())
+                && item.renting_period[0].to_string() == renting_period[0]
+                && item.renting_period[1].to_string() == renting_period[1]
+                && item.rental_type
+            {
+                if item.approved_date.is_some() {
+                    return Err(ContractError::ApprovedAlready {});
+                } else {
+                    position = i as i32;
+                    amount = item.deposit_amount;
+                }
+            }
+        }
+
+        if position == -1 {
+            return Err(ContractError::NotReserved {});
+        } else {
+            amount += fees_total(&token.rentals.remove(position as usize).fees);
+            self.tokens.save(deps.storage, &token_id, &token)?;
+        }
+
+        if amount > Uint128::new(0) {
+            Ok(Response::new()
+                .add_attribute("action", "cancelreservationbeforeapprovalforlongterm")
+                .add_attribute("sender", info.sender)
+                .add_attribute("token_id", token_id)
+                .add_message(self.payout(
+                    deps.storage,
+                    tenant_address,
+                    token.longterm_rental.denom,
+                    amount,
+                )?))
+        } else {
+            Ok(Response::new()
+                .add_attribute("action", "cancelreservationbeforeapprovalforlongterm")
+                .add_attribute("sender", info.sender)
+                .add_attribute("token_id", token_id))
+        }
+    }
+
+    pub fn rejectreservationforlongterm(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        tenant: String,
+        renting_period: Vec<String>,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
+
+        let mut position: i32 = -1;
+        let mut refundable_amount: Uint128 = Uint128::new(0);
+        for (i, item) in token.rentals.iter().enumerate() {
+            if item.address == Some(Addr::unchecked(tenant.clone()))
+                && item.renting_period[0].to_string() == renting_period[0]
+                && item.renting_period[1].to_string() == renting_period[1]
+                && item.rental_type
+            {
+                refundable_amount = item.deposit_amount;
+                position = i as i32;
+            }
+        }
+        if position == -1 {
+            return Err(ContractError::NotReserved {});
+        } else {
+            refundable_amount += fees_total(&token.rentals.remove(position as usize).fees);
+            self.tokens.save(deps.storage, &token_id, &token)?;
+        }
+
+        if refundable_amount > Uint128::new(0) {
+            Ok(Response::new()
+                .add_attribute("action", "rejectreservationforlongterm")
+                .add_attribute("sender", info.sender)
+                .add_attribute("token_id", token_id)
+                .add_message(self.payout(
+                    deps.storage,
+                    tenant,
+                    token.longterm_rental.denom,
+                    refundable_amount,
+                )?))
+        } else {
+            Ok(Response::new()
+                .add_attribute("action", "rejectreservationforlongterm")
+                .add_attribute("sender", info.sender)
+                .add_attribute("token_id", token_id))
+        }
+    }
+
+    pub fn depositforlongtermrental(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        token_id: String,
+        renting_period: Vec<String>,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+
+        let mut position: i32 = -1;
+        let sent_amount = info.funds[0].amount;
+        if info.funds[0].denom != token.longterm_rental.denom {
+            return Err(ContractError::InvalidDeposit {});
+        }
+
+        if sent_amount > Uint128::new(0) {
+            for (i, item) in token.rentals.iter().enumerate() {
+                if item.address == Some(info.sender.clone())
+                    && item.renting_period[0].to_string() == renting_period[0]
+                    && item.renting_period[1].to_string() == renting_period[1]
+                    && item.rental_type
+                {
+                    position = i as i32;
+                }
+            }
+
+            if position == -1 {
+                return Err(ContractError::NotReserved {});
+            }
+
+            token.rentals[position as usize].deposit_amount += sent_amount;
+            self.tokens.save(deps.storage, &token_id, &token)?;
+        } else {
+            return Err(ContractError::InsufficientDeposit {});
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "depositforlongtermrental")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn setapproveforlongterm(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        env: Env,
+        token_id: String,
+        renting_period: Vec<String>,
+        tenant: String,
+        approved_date: String,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
+        let current_time = env.block.time.seconds();
+
+        let check_in_time = renting_period[0].parse::<u64>();
+        let check_in_time_timestamp = match check_in_time {
+            Ok(timestamp) => timestamp,
+            Err(_e) => {
+                return Err(ContractError::NotReserved {});
+            }
+        };
+
+        if check_in_time_timestamp <= current_time {
+            return Err(ContractError::RentalAlreadyStarted {});
+        }
+
+        let mut position: i32 = -1;
+        for (i, item) in token.rentals.iter().enumerate() {
+            if item.address == Some(Addr::unchecked(tenant.clone()))
+                && item.renting_period[0].to_string() == renting_period[0]
+                && item.renting_period[1].to_string() == renting_period[1]
+                && item.rental_type
+            {
+                position = i as i32;
+                break;
+            }
+        }
+        if position == -1 {
+            return Err(ContractError::NotReserved {});
+        }
+        token.rentals[position as usize].approved_date = Some(approved_date);
+
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "setapproveforlongterm")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+    }
+
+    pub fn cancelreservationafterapprovalforlongterm(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        token_id: String,
+        renting_period: Vec<String>,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+
+        let mut position: i32 = -1;
+        for (i, item) in token.rentals.iter().enumerate() {
+            if item.address == Some(info.sender.clone())
+                && item.renting_period[0].to_string() == renting_period[0]
+                && item.renting_period[1].to_string() == renting_period[1]
+                && item.rental_type
+            {
+                if item.approved_date.is_none() {
+                    return Err(ContractError::NotApproved {});
+                } else {
+                    position = i as i32;
+                }
+            }
+        }
+
+        if position != -1 {
+            token.rentals[position as usize].cancelled = true;
+            // long-term rent is never refunded on cancellation
+            let fee_refund = refund_fees(&mut token.rentals[position as usize].fees, 0);
+            self.tokens.save(deps.storage, &token_id, &token)?;
+            let mut response = Response::new()
+                .add_attribute("action", "cancelreservationafterapprovalforlongterm")
+                .add_attribute("sender", info.sender.clone())
+                .add_attribute("token_id", token_id);
+            if fee_refund > Uint128::new(0) {
+                response = response.add_message(self.payout(
+                    deps.storage,
+                    info.sender,
+                    token.longterm_rental.denom,
+                    fee_refund,
+                )?);
+            }
+            Ok(response)
+        } else {
+            Err(ContractError::NotReserved {})
+        }
+    }
+
+    pub fn finalizelongtermrental(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        tenant: String,
+        renting_period: Vec<String>,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+
+        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
+
+        let mut position: i32 = -1;
+        let mut amount = Uint128::from(0u64);
+
+        let current_time = env.block.time.seconds();
+
+        let check_out_time = renting_period[1].parse::<u64>();
+        let check_out_time_timestamp = match check_out_time {
+            Ok(timestamp) => timestamp,
+            Err(_e) => {
+                return Err(ContractError::NotReserved {});
+            }
+        };
+
+        let mut target = "".to_string();
+        let mut fees = vec![];
+
+        for (i, item) in token.rentals.iter().enumerate() {
+            if item.address == Some(Addr::unchecked(tenant.clone()))
+                && item.renting_period[0].to_string() == renting_period[0]
+                && item.renting_period[1].to_string() == renting_period[1]
+                && item.rental_type
+            {
+                position = i as i32;
+                amount = item.deposit_amount;
+
+                if item.cancelled {
+                    target = token.owner.address.to_string();
+                    let fee_percentage = self.get_fee(deps.storage)?;
+                    self.increase_balance(
+                        deps.storage,
+                        token.longterm_rental.denom.clone(),
+                        Uint128::new((u128::from(amount) * u128::from(fee_percentage)) / 10000),
+                    )?;
+                    amount -=
+                        Uint128::new((u128::from(amount) * u128::from(fee_percentage)) / 10000);
+                }
+                if !item.cancelled {
+                    if item.approved_date.is_none() {
+                        target = tenant.clone();
+                        // return  Err(ContractError::NotApproved {});
+                    } else {
+                        target = token.owner.address.to_string();
+                        let fee_percentage = self.get_fee(deps.storage)?;
+                        self.increase_balance(
+                            deps.storage,
+                            token.longterm_rental.denom.clone(),
+                            Uint128::new((u128::from(amount) * u128::from(fee_percentage)) / 10000),
+                        )?;
+                        amount -=
+                            Uint128::new((u128::from(amount) * u128::from(fee_percentage)) / 10000);
+                    }
+                }
+            }
+        }
+        if position == -1 {
+            return Err(ContractError::NotReserved {});
+        } else {
+            if check_out_time_timestamp > current_time
+                && !token.rentals[position as usize].cancelled
+            {
+                return Err(ContractError::RentalActive {});
+            }
+
+            let rental = token.rentals.remove(position as usize);
+            self.tokens.save(deps.storage, &token_id, &token)?;
+            if !rental.cancelled && target == token.owner.address {
+                self.record_stay(
+                    deps.storage,
+                    &token_id,
+                    &token.owner.address,
+                    &rental,
+                    env.block.time.seconds(),
+                )?;
+            }
+            fees = rental.fees;
+        }
+
+        let fee_messages = if target == token.owner.address {
+            let (host_fees, messages) =
+                self.settle_fees(deps.storage, &token.longterm_rental.denom, &fees)?;
+            amount += host_fees;
+            messages
+        } else {
+            amount += fees_total(&fees);
+            vec![]
+        };
+        let messages = if target == token.owner.address {
+            self.pay_rental_income(
+                deps.storage,
+                &token_id,
+                &token,
+                target,
+                &token.longterm_rental.denom,
+                amount,
+            )?
+        } else if amount > Uint128::new(0) {
+            vec![self.payout(deps.storage, target, token.longterm_rental.denom, amount)?]
+        } else {
+            vec![]
+        };
+
+        Ok(Response::new()
+            .add_attribute("action", "finalizelongtermrental")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_messages(messages)
+            .add_messages(fee_messages))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn withdrawtolandlord(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        tenant: String,
+        renting_period: Vec<String>,
+        amount: u128,
+        address: String,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+
+        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
+        let mut position = -1;
+
+        let current_time = env.block.time.seconds();
+
+        let check_in_time = renting_period[0].parse::<u64>();
+        let check_in_time_timestamp = match check_in_time {
+            Ok(timestamp) => timestamp,
+            Err(_e) => {
+                return Err(ContractError::NotReserved {});
+            }
+        };
+
+        if current_time <= check_in_time_timestamp {
+            return Err(ContractError::RentalNotActivated {});
+        }
+        let fee_percentage = self.get_fee(deps.storage)?;
+        let amount = Uint128::from(amount);
+        let fee_amount = amount.multiply_ratio(fee_percentage, 10000u128);
+
+        for (i, item) in token.rentals.iter().enumerate() {
+            if item.address == Some(Addr::unchecked(tenant.clone()))
+                && item.renting_period[0].to_string() == renting_period[0]
+                && item.renting_period[1].to_string() == renting_period[1]
+                && item.rental_type
+            {
+                position = i as i32;
+                if item.cancelled {
+                    return Err(ContractError::NotApproved {});
+                }
+                // the first month, guest surcharge included, stays reserved
+                let reserve = Uint128::from(token.longterm_rental.monthly_price(item.guests));
+                match item.deposit_amount.checked_sub(reserve) {
+                    Ok(available) if available >= amount => {}
+                    _ => return Err(ContractError::UnavailableAmount {}),
+                }
+            }
+        }
+        if position == -1 {
+            return Err(ContractError::NotReserved {});
+        } else {
+            self.increase_balance(
+                deps.storage,
+                token.longterm_rental.denom.clone(),
+                fee_amount,
+            )?;
+            token.rentals[position as usize].deposit_amount -= amount;
+            self.tokens.save(deps.storage, &token_id, &token)?;
+        }
+        let messages = self.pay_rental_income(
+            deps.storage,
+            &token_id,
+            &token,
+            address,
+            &token.longterm_rental.denom,
+            amount - fee_amount,
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("action", "withdrawtolandlord")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_messages(messages))
+    }
+
+    pub fn fractionalize(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        shares: Uint128,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
+        self.check_can_edit_bid(&env, &token)?;
+        self.check_can_edit_auction(&env, &token)?;
+        self.check_not_fractionalized(&token)?;
+        self.check_can_edit_escrow(&token)?;
+        if shares.is_zero() {
+            return Err(ContractError::InvalidInput {});
+        }
+        let pays_address = |lines: &[FeeLine]| {
+            lines
+                .iter()
+                .any(|line| matches!(line.recipient, FeeRecipient::Address { .. }))
+        };
+        // fee lines paid to an address would divert income from the share holders
+        if pays_address(&token.shortterm_rental.fee_lines)
+            || pays_address(&token.longterm_rental.fee_lines)
+        {
+            return Err(ContractError::InvalidInput {});
+        }
+
+        // the owner keeps managing rentals, but the token is locked until redeemed
+        token.total_shares = Some(shares);
+        token.sell.islisted = None;
+        token.approvals = vec![];
+        self.tokens.save(deps.storage, &token_id, &token)?;
+        self.settle_rewards(deps.storage, &token_id, &token.owner.address)?;
+        self.shares
+            .save(deps.storage, (&token_id, &token.owner.address), &shares)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "fractionalize")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_attribute("shares", shares))
+    }
+
+    pub fn transfershares(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        token_id: String,
+        recipient: String,
+        amount: Uint128,
+    ) -> Result<Response<C>, ContractError> {
+        let token = self.tokens.load(deps.storage, &token_id)?;
+        if token.total_shares.is_none() {
+            return Err(ContractError::NotFractionalized {});
+        }
+        let recipient = deps.api.addr_validate(&recipient)?.to_string();
+        let sender = info.sender.to_string();
+
+        let balance = self
+            .shares
+            .may_load(deps.storage, (&token_id, &sender))?
+            .unwrap_or_default();
+        if amount.is_zero() || balance < amount {
+            return Err(ContractError::InsufficientShares {});
+        }
+        // credit income earned at the old balances before they change
+        self.settle_rewards(deps.storage, &token_id, &sender)?;
+        self.settle_rewards(deps.storage, &token_id, &recipient)?;
+        if balance == amount {
+            self.shares.remove(deps.storage, (&token_id, &sender));
+        } else {
+            self.shares
+                .save(deps.storage, (&token_id, &sender), &(balance - amount))?;
+        }
+        self.shares.update(
+            deps.storage,
+            (&token_id, &recipient),
+            |shares| -> StdResult<_> { Ok(shares.unwrap_or_default() + amount) },
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("action", "transfershares")
+            .add_attribute("sender", info.sender)
+            .add_attribute("recipient", recipient)
+            .add_attribute("token_id", token_id)
+            .add_attribute("amount", amount))
+    }
+
+    pub fn redeemshares(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        token_id: String,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        let total_shares = token
+            .total_shares
+            .ok_or(ContractError::NotFractionalized {})?;
+        let sender = info.sender.to_string();
+        let balance = self
+            .shares
+            .may_load(deps.storage, (&token_id, &sender))?
+            .unwrap_or_default();
+        if balance != total_shares {
+            return Err(ContractError::InsufficientShares {});
+        }
+
+        self.settle_rewards(deps.storage, &token_id, &sender)?;
+        self.shares.remove(deps.storage, (&token_id, &sender));
+        token.total_shares = None;
+        self.lock_host_bond(deps.storage, &sender, &token.rentals)?;
+        token.owner.address = sender;
+        token.approvals = vec![];
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "redeemshares")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+    }
+
+    pub fn claimrewards(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        token_id: String,
+    ) -> Result<Response<C>, ContractError> {
+        let sender = info.sender.to_string();
+        self.settle_rewards(deps.storage, &token_id, &sender)?;
+        let rewards = self
+            .rewards
+            .prefix(&sender)
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<(String, Uint128)>>>()?;
+        if rewards.is_empty() {
+            return Err(ContractError::UnavailableAmount {});
+        }
+
+        let mut messages = vec![];
+        for (denom, amount) in rewards {
+            self.rewards.remove(deps.storage, (&sender, &denom));
+            messages.push(self.payout(deps.storage, sender.clone(), denom, amount)?);
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "claimrewards")
+            .add_attribute("sender", info.sender)
+            .add_messages(messages))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn _update_approvals(
+        &self,
+        deps: DepsMut,
+        env: &Env,
+        info: &MessageInfo,
+        spender: &str,
+        token_id: &str,
+        // if add == false, remove. if add == true, remove then set with this expiration
+        add: bool,
+        expires: Option<Expiration>,
+    ) -> Result<TokenInfo<T>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, token_id)?;
+        // ensure we have permissions
+        self.check_can_approve(deps.as_ref(), env, info, &token)?;
+
+        // update the approval list (remove any for the same spender before adding)
+        let spender_addr = deps.api.addr_validate(spender)?;
+        token.approvals.retain(|apr| apr.spender != spender_addr);
+
+        // only difference between approve and revoke
+        if add {
+            // reject expired data as invalid
+            let expires = expires.unwrap_or_default();
+            if expires.is_expired(&env.block) {
+                return Err(ContractError::Expired {});
+            }
+            let approval = Approval {
+                spender: spender_addr,
+                expires,
+            };
+            token.approvals.push(approval);
+        }
+
+        self.tokens.save(deps.storage, token_id, &token)?;
+
+        Ok(token)
+    }
+
+    /// Hands the token over to `buyer` for `amount` of `denom`: the platform fee is kept in
+    /// the contract balance and the rest is returned as a payout to the previous owner.
+    /// Approvals and any open sale listings are cleared. The caller saves the token.
+    pub fn settle_sale(
+        &self,
+        storage: &mut dyn Storage,
+        token: &mut TokenInfo<T>,
+        buyer: &str,
+        denom: &str,
+        amount: Uint128,
+    ) -> StdResult<Vec<CosmosMsg<C>>> {
+        let seller = token.owner.address.clone();
+        token.owner.address = buyer.to_string();
+        token.approvals = vec![];
+        self.lock_host_bond(storage, buyer, &token.rentals)?;
+        token.sell.islisted = None;
+        token.dutch_auction.islisted = None;
+
+        let mut messages = vec![];
+        // an offer still pending in escrow is declined
+        if let Some(escrow) = token.escrow.take() {
+            messages.push(self.payout(storage, escrow.buyer, escrow.denom, escrow.deposit)?);
+        }
+
+        let fee_percentage = self.get_fee(storage)?;
+        let fee_amount = Uint128::new((amount.u128() * u128::from(fee_percentage)) / 10000);
+        if fee_amount > Uint128::new(0) {
+            self.increase_balance(storage, denom.to_string(), fee_amount)?;
+        }
+
+        let mut proceeds = amount - fee_amount;
+        if let Some((royalty_amount, message)) =
+            self.royalty_payout(storage, token, denom, amount, proceeds, false)?
+        {
+            proceeds -= royalty_amount;
+            messages.push(message);
+        }
+        if proceeds > Uint128::new(0) {
+            messages.push(self.payout(storage, seller, denom, proceeds)?);
+        }
+        Ok(messages)
+    }
+
+    /// Royalty owed on a sale or rental payment of `amount`, with the message paying it.
+    /// Falls back to the collection royalty when the token has none of its own.
+    /// Never more than `available`, what is left of the payment after the platform fee.
+    pub fn royalty_payout(
+        &self,
+        storage: &dyn Storage,
+        token: &TokenInfo<T>,
+        denom: &str,
+        amount: Uint128,
+        available: Uint128,
+        rental: bool,
+    ) -> StdResult<Option<(Uint128, CosmosMsg<C>)>> {
+        let royalty = match &token.royalty {
+            Some(royalty) => Some(royalty.clone()),
+            None => self.default_royalty.may_load(storage)?,
+        };
+        match royalty {
+            Some(royalty) if !rental || royalty.on_rentals => {
+                let royalty_amount =
+                    Uint128::new((amount.u128() * u128::from(royalty.share)) / 10000)
+                        .min(available);
+                if royalty_amount.is_zero() {
+                    return Ok(None);
+                }
+                let message = self.payout(storage, royalty.recipient, denom, royalty_amount)?;
+                Ok(Some((royalty_amount, message)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Pays rental income, net of the platform fee, to `recipient` after taking the
+    /// royalty when it applies to rentals.
+    /// Income of a fractionalized token is credited to its share holders instead.
+    pub fn pay_rental_income(
+        &self,
+        storage: &mut dyn Storage,
+        token_id: &str,
+        token: &TokenInfo<T>,
+        recipient: String,
+        denom: &str,
+        amount: Uint128,
+    ) -> StdResult<Vec<CosmosMsg<C>>> {
+        let mut messages = vec![];
+        let mut income = amount;
+        if let Some((royalty_amount, message)) =
+            self.royalty_payout(storage, token, denom, amount, amount, true)?
+        {
+            income -= royalty_amount;
+            messages.push(message);
+        }
+        if income.is_zero() {
+            return Ok(messages);
+        }
+        match token.total_shares {
+            Some(total_shares) => {
+                self.distribute_rewards(storage, token_id, total_shares, denom, income)?
+            }
+            None => messages.push(self.payout(storage, recipient, denom, income)?),
+        }
+        Ok(messages)
+    }
+
+    /// Credits `amount` to the share holders of a token pro-rata by raising its income per
+    /// share. Holders are credited lazily by `settle_rewards`, the rounding dust stays here.
+    pub fn distribute_rewards(
+        &self,
+        storage: &mut dyn Storage,
+        token_id: &str,
+        total_shares: Uint128,
+        denom: &str,
+        amount: Uint128,
+    ) -> StdResult<()> {
+        self.reward_per_share
+            .update(storage, (token_id, denom), |per_share| -> StdResult<_> {
+                Ok(per_share
+                    .unwrap_or_default()
+                    .checked_add(Decimal256::from_ratio(amount, total_shares))?)
+            })?;
+        Ok(())
+    }
+
+    /// Credits `holder` with the income their current shares of a token earned since
+    /// they were last settled. Has to run before their share balance changes.
+    pub fn settle_rewards(
+        &self,
+        storage: &mut dyn Storage,
+        token_id: &str,
+        holder: &str,
+    ) -> StdResult<()> {
+        for (denom, amount) in self.unsettled_rewards(storage, token_id, holder)? {
+            self.add_reward(storage, holder, &denom, amount)?;
+        }
+        let per_share = self
+            .reward_per_share
+            .prefix(token_id)
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<(String, Decimal256)>>>()?;
+        for (denom, per_share) in per_share {
+            self.reward_settled
+                .save(storage, (token_id, holder, &denom), &per_share)?;
+        }
+        Ok(())
+    }
+
+    /// Income per denom the holder's shares of a token earned since they were last settled
+    pub fn unsettled_rewards(
+        &self,
+        storage: &dyn Storage,
+        token_id: &str,
+        holder: &str,
+    ) -> StdResult<Vec<(String, Uint128)>> {
+        let shares = self
+            .shares
+            .may_load(storage, (token_id, holder))?
+            .unwrap_or_default();
+        let mut rewards = vec![];
+        for item in
+            self.reward_per_share
+                .prefix(token_id)
+                .range(storage, None, None, Order::Ascending)
+        {
+            let (denom, per_share) = item?;
+            let settled = self
+                .reward_settled
+                .may_load(storage, (token_id, holder, &denom))?
+                .unwrap_or_default();
+            let amount = Uint128::try_from(Uint256::from(shares) * (per_share - settled))?;
+            if !amount.is_zero() {
+                rewards.push((denom, amount));
+            }
+        }
+        Ok(rewards)
+    }
+
+    fn add_reward(
+        &self,
+        storage: &mut dyn Storage,
+        holder: &str,
+        denom: &str,
+        amount: Uint128,
+    ) -> StdResult<()> {
+        if amount.is_zero() {
+            return Ok(());
+        }
+        self.rewards
+            .update(storage, (holder, denom), |reward| -> StdResult<_> {
+                Ok(reward.unwrap_or_default() + amount)
+            })?;
+        Ok(())
+    }
+
+    /// Attributes of the token metadata, empty if the extension carries none
+    pub fn token_traits(&self, token: &TokenInfo<T>) -> StdResult<Vec<Trait>> {
+        let extension: Option<Extension> = from_binary(&to_binary(&token.extension)?).ok();
+        Ok(extension
+            .flatten()
+            .and_then(|metadata| metadata.attributes)
+            .unwrap_or_default())
+    }
+
+    /// Promo code `code` if it can still be redeemed on `token_id`. Platform codes are
+    /// only redeemable on listings accepting them, as the host bears the discount.
+    pub fn redeemable_promo_code(
+        &self,
+        storage: &dyn Storage,
+        block: &BlockInfo,
+        code: &str,
+        token_id: &str,
+        token: &TokenInfo<T>,
+    ) -> Result<PromoCode, ContractError> {
+        match self.promo_codes.may_load(storage, code)? {
+            Some(promo)
+                if promo.is_redeemable(block, token_id)
+                    && (promo.token_id.is_some()
+                        || token.shortterm_rental.accepts_platform_promos) =>
+            {
+                Ok(promo)
+            }
+            _ => Err(ContractError::InvalidPromoCode {}),
+        }
+    }
+
+    /// Rent and platform fee of a short-term stay booked at `booked_at`. The length-of-stay
+    /// discount comes off the rent first and the promo code off what is left, the fee is
+    /// charged on the discounted rent.
+    #[allow(clippy::too_many_arguments)]
+    pub fn quote_shortterm(
+        &self,
+        storage: &dyn Storage,
+        token: &TokenInfo<T>,
+        check_in: u64,
+        check_out: u64,
+        booked_at: u64,
+        guests: usize,
+        promo: Option<&PromoDiscount>,
+    ) -> StdResult<ShortTermQuote> {
+        let nights = token
+            .shortterm_rental
+            .nightly_prices(check_in, check_out, booked_at, guests);
+        let gross: Uint128 = nights
+            .iter()
+            .map(|night| night.price + night.guest_fee)
+            .sum();
+        let stay_discount = token.shortterm_rental.stay_discount(nights.len());
+        let mut discount = gross.multiply_ratio(stay_discount, 10000u64);
+        if let Some(promo) = promo {
+            discount += promo.apply(gross - discount);
+        }
+        let rent_amount = gross - discount;
+        let fees: Vec<ChargedFee> = token
+            .shortterm_rental
+            .fee_lines
+            .iter()
+            .map(|line| line.charged(rent_amount, nights.len() as u64))
+            .collect();
+        let fee_percentage = self.get_fee(storage)?;
+        let fee = Uint128::new((rent_amount.u128() * u128::from(fee_percentage)) / 10000);
+        let damage_deposit = Uint128::new(token.shortterm_rental.damage_deposit);
+        Ok(ShortTermQuote {
+            nights,
+            rent_amount,
+            discount,
+            fee,
+            damage_deposit,
+            total: rent_amount + fee + damage_deposit + fees_total(&fees),
+            denom: token.shortterm_rental.denom.clone(),
+            refund_schedule: refund_schedule(
+                &token.shortterm_rental.cancellation,
+                check_in,
+                rent_amount,
+                &fees,
+            ),
+            fees,
+        })
+    }
+
+    /// First-month rent and fee lines of a long-term stay
+    pub fn quote_longterm(
+        &self,
+        storage: &dyn Storage,
+        token: &TokenInfo<T>,
+        check_in: u64,
+        check_out: u64,
+        guests: usize,
+    ) -> StdResult<LongTermQuote> {
+        let rent_amount = Uint128::from(token.longterm_rental.monthly_price(guests));
+        let nights = (check_out - check_in).div_ceil(86400);
+        let fees: Vec<ChargedFee> = token
+            .longterm_rental
+            .fee_lines
+            .iter()
+            .map(|line| line.charged(rent_amount, nights))
+            .collect();
+        let fee_percentage = self.get_fee(storage)?;
+        let fee = Uint128::new((rent_amount.u128() * u128::from(fee_percentage)) / 10000);
+        Ok(LongTermQuote {
+            rent_amount,
+            fee,
+            total: rent_amount + fees_total(&fees),
+            denom: token.longterm_rental.denom.clone(),
+            // long-term rent is never refunded, fee lines follow their own refund rule
+            refund_schedule: vec![RefundStep {
+                cancel_by: check_out.saturating_sub(1),
+                percentage: 0,
+                refund: fees.iter().map(|fee| fee.refund_amount(0)).sum(),
+            }],
+            fees,
+        })
+    }
+
+    /// Marks the sender's share of a group reservation paid, the funds must match it exactly
+    pub fn pay_group_share(
+        &self,
+        group: &mut GroupReservation,
+        info: &MessageInfo,
+    ) -> Result<(), ContractError> {
+        if info.funds.len() != 1 || info.funds[0].denom != group.denom {
+            return Err(ContractError::InvalidDeposit {});
+        }
+        let share = group
+            .shares
+            .iter_mut()
+            .find(|share| share.payer == info.sender && !share.paid)
+            .ok_or(ContractError::NotPayer {})?;
+        if info.funds[0].amount != share.amount {
+            return Err(ContractError::InsufficientDeposit {});
+        }
+        share.paid = true;
+        Ok(())
+    }
+
+    /// Saves a group reservation still being funded, or books the stay for the organizer
+    /// with every share once it is fully funded. Every payer is refunded instead if the
+    /// stay can no longer be booked, the quote having been taken when the group was created.
+    pub fn book_group_reservation(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        group: GroupReservation,
+    ) -> Result<Response<C>, ContractError> {
+        if !group.is_funded() {
+            self.group_reservations
+                .save(deps.storage, group.id, &group)?;
+            return Ok(Response::new().add_attribute("booked", "false"));
+        }
+        self.group_reservations.remove(deps.storage, group.id);
+
+        let info = MessageInfo {
+            sender: group.organizer.clone(),
+            funds: vec![Coin {
+                denom: group.denom.clone(),
+                amount: group.shares.iter().map(|share| share.amount).sum(),
+            }],
+        };
+        let check_in = parse_check_in(&group.renting_period)?;
+        if let Err(err) = self.setreservationforshortterm(
+            deps.branch(),
+            env,
+            info,
+            group.token_id.clone(),
+            group.renting_period.clone(),
+            group.guests,
+            None,
+        ) {
+            return Ok(Response::new()
+                .add_attribute("booked", "false")
+                .add_attribute("reason", err.to_string())
+                .add_messages(self.refund_group_shares(deps.storage, &group)?));
+        }
+
+        // refunds of the stay go back to whoever paid for it
+        let mut token = self.tokens.load(deps.storage, &group.token_id)?;
+        if let Some(rental) = token.rentals.iter_mut().find(|item| {
+            item.address == Some(group.organizer.clone())
+                && item.renting_period[0] == check_in
+                && !item.cancelled
+        }) {
+            rental.payers = group.shares.clone();
+        }
+        self.tokens.save(deps.storage, &group.token_id, &token)?;
+        if let Some(mut deposit) = self
+            .damage_deposits
+            .may_load(deps.storage, (&group.token_id, check_in))?
+        {
+            deposit.payers = group.shares;
+            self.damage_deposits
+                .save(deps.storage, (&group.token_id, check_in), &deposit)?;
+        }
+        Ok(Response::new().add_attribute("booked", "true"))
+    }
+
+    /// Refunds every paid share of a group reservation to its payer
+    pub fn refund_group_shares(
+        &self,
+        storage: &dyn Storage,
+        group: &GroupReservation,
+    ) -> StdResult<Vec<CosmosMsg<C>>> {
+        group
+            .shares
+            .iter()
+            .filter(|share| share.paid)
+            .map(|share| {
+                self.payout(
+                    storage,
+                    share.payer.as_str(),
+                    group.denom.as_str(),
+                    share.amount,
+                )
+            })
+            .collect()
+    }
+
+    /// Counts a finalized stay for instant booking and gives its host and traveler the
+    /// right to review each other within the review window. Stays by the host at their
+    /// own property and stays without rent count for neither, and only the first stay
+    /// with each host adds to the traveler's completed stays.
+    pub fn record_stay(
+        &self,
+        storage: &mut dyn Storage,
+        token_id: &str,
+        host: &str,
+        rental: &Rental,
+        now: u64,
+    ) -> StdResult<()> {
+        if rental.address.as_ref().map(Addr::as_str) == Some(host)
+            || rental.deposit_amount.is_zero()
+        {
+            return Ok(());
+        }
+        if let Some(traveler) = rental
+            .address
+            .as_ref()
+            .filter(|traveler| !self.stayed_with.has(storage, (traveler.as_str(), host)))
+        {
+            self.stayed_with
+                .save(storage, (traveler.as_str(), host), &Empty {})?;
+            self.traveler_profiles.update(
+                storage,
+                traveler.as_str(),
+                |profile| -> StdResult<_> {
+                    let mut profile = profile.unwrap_or_default();
+                    profile.completed_stays += 1;
+                    Ok(profile)
+                },
+            )?;
+        }
+        self.stays.save(
+            storage,
+            (token_id, rental.renting_period[0]),
+            &CompletedStay {
+                token_id: token_id.to_string(),
+                host: host.to_string(),
+                traveler: rental
+                    .address
+                    .as_ref()
+                    .map(|address| address.to_string())
+                    .unwrap_or_default(),
+                rental_type: rental.rental_type,
+                renting_period: rental.renting_period.clone(),
+                host_reviewed: false,
+                traveler_reviewed: false,
+                review_by: now + REVIEW_WINDOW,
+            },
+        )
+    }
+
+    /// Whether a reservation by `traveler` meets the listing's instant-book rules
+    pub fn instant_books(
+        &self,
+        storage: &dyn Storage,
+        rules: Option<&InstantBookRules>,
+        traveler: &str,
+        nights: u64,
+        guests: usize,
+    ) -> StdResult<bool> {
+        let rules = match rules {
+            Some(rules) => rules,
+            None => return Ok(true),
+        };
+        let profile = self
+            .traveler_profiles
+            .may_load(storage, traveler)?
+            .unwrap_or_default();
+        Ok(rules.allows(&profile, nights, guests))
+    }
+
+    /// Listing's auto-approval, unless the host lost it to cancellations
+    pub fn auto_approves(
+        &self,
+        storage: &dyn Storage,
+        host: &str,
+        auto_approve: bool,
+    ) -> StdResult<bool> {
+        if !auto_approve {
+            return Ok(false);
+        }
+        let policy = self.host_policy.may_load(storage)?.unwrap_or_default();
+        let record = self
+            .host_records
+            .may_load(storage, host)?
+            .unwrap_or_default();
+        Ok(policy.can_auto_approve(&record))
+    }
+
+    pub fn check_host_can_list(
+        &self,
+        storage: &dyn Storage,
+        host: &str,
+    ) -> Result<(), ContractError> {
+        let policy = self.host_policy.may_load(storage)?.unwrap_or_default();
+        let record = self
+            .host_records
+            .may_load(storage, host)?
+            .unwrap_or_default();
+        if !policy.can_list(&record) {
+            return Err(ContractError::HostSuspended {});
+        }
+        self.check_host_bond(storage, host)
+    }
+
+    pub fn check_host_bond(&self, storage: &dyn Storage, host: &str) -> Result<(), ContractError> {
+        let policy = self.host_policy.may_load(storage)?.unwrap_or_default();
+        let bond = self.host_bonds.may_load(storage, host)?;
+        if !policy.has_min_bond(bond.as_ref()) {
+            return Err(ContractError::HostBondTooLow {});
+        }
+        Ok(())
+    }
 
-        let check_out_time = renting_period[1].parse::<u64>();
-        let check_out_time_timestamp;
+    /// Keeps the host bond locked until the latest check-out of the stays at a property
+    pub fn lock_host_bond(
+        &self,
+        storage: &mut dyn Storage,
+        host: &str,
+        rentals: &[Rental],
+    ) -> StdResult<()> {
+        let until = rentals
+            .iter()
+            .filter(|rental| !rental.cancelled)
+            .map(|rental| rental.renting_period[1])
+            .max()
+            .unwrap_or_default();
+        let mut record = self
+            .host_records
+            .may_load(storage, host)?
+            .unwrap_or_default();
+        if until > record.bond_locked_until {
+            record.bond_locked_until = until;
+            self.host_records.save(storage, host, &record)?;
+        }
+        Ok(())
+    }
 
-        match check_out_time {
-            Ok(timestamp) => {
-                check_out_time_timestamp = timestamp;
-            }
-            Err(_e) => {
-                return Err(ContractError::NotReserved {});
+    /// Credits funds to a host bond, which holds a single denom
+    pub fn add_host_bond(
+        &self,
+        storage: &mut dyn Storage,
+        host: &str,
+        funds: &[Coin],
+    ) -> Result<(), ContractError> {
+        let coin = match funds {
+            [] => return Ok(()),
+            [coin] => coin,
+            _ => return Err(ContractError::InvalidDeposit {}),
+        };
+        let mut bond = self
+            .host_bonds
+            .may_load(storage, host)?
+            .unwrap_or_else(|| Coin {
+                denom: coin.denom.clone(),
+                amount: Uint128::new(0),
+            });
+        if bond.denom != coin.denom {
+            if !bond.amount.is_zero() {
+                return Err(ContractError::InvalidDeposit {});
             }
+            bond.denom = coin.denom.clone();
         }
+        bond.amount += coin.amount;
+        self.host_bonds.save(storage, host, &bond)?;
+        Ok(())
+    }
 
-        let mut target = "".to_string();
-
-        for (i, item) in token.rentals.iter().enumerate() {
-            if item.address == Some(Addr::unchecked(tenant.clone()))
-                && item.renting_period[0].to_string() == renting_period[0]
-                && item.renting_period[1].to_string() == renting_period[1]
-                && item.rental_type == true
-            {
-                position = i as i32;
-                amount = item.deposit_amount;
+    /// Takes a platform fee back out of the balance for a refund. Fees already withdrawn
+    /// by the contract owner are not refunded.
+    pub fn refund_fee(
+        &self,
+        storage: &mut dyn Storage,
+        denom: String,
+        fee: Uint128,
+    ) -> StdResult<Uint128> {
+        let refundable = fee.min(self.get_balance(storage, denom.clone())?);
+        self.decrease_balance(storage, denom, refundable)?;
+        Ok(refundable)
+    }
 
-                if item.cancelled {
-                    target = token.owner.address.to_string();
-                    let fee_percentage = self.get_fee(deps.storage)?;
-                    self.increase_balance(
-                        deps.storage,
-                        token.longterm_rental.denom.clone(),
-                        Uint128::new((u128::from(amount) * u128::from(fee_percentage)) / 10000),
-                    )?;
-                    amount -=
-                        Uint128::new((u128::from(amount) * u128::from(fee_percentage)) / 10000);
-                }
-                if !item.cancelled {
-                    if item.approved_date.is_none() {
-                        target = tenant.clone();
-                        // return  Err(ContractError::NotApproved {});
-                    } else {
-                        target = token.owner.address.to_string();
-                        let fee_percentage = self.get_fee(deps.storage)?;
-                        self.increase_balance(
-                            deps.storage,
-                            token.longterm_rental.denom.clone(),
-                            Uint128::new((u128::from(amount) * u128::from(fee_percentage)) / 10000),
-                        )?;
-                        amount -=
-                            Uint128::new((u128::from(amount) * u128::from(fee_percentage)) / 10000);
-                    }
-                }
+    /// Platform fee the traveler paid for a short-term reservation
+    pub fn charged_platform_fee(
+        &self,
+        storage: &dyn Storage,
+        rental: &Rental,
+    ) -> StdResult<Uint128> {
+        match rental.platform_fee {
+            Some(fee) => Ok(fee),
+            None => {
+                let fee_percentage = self.get_fee(storage)?;
+                Ok(Uint128::new(
+                    (rental.deposit_amount.u128() * u128::from(fee_percentage)) / 10000,
+                ))
             }
         }
-        if position == -1 {
-            return Err(ContractError::NotReserved {});
-        } else {
-            if check_out_time_timestamp > current_time
-                && !token.rentals[position as usize].cancelled
-            {
-                return Err(ContractError::RentalActive {});
-            }
+    }
 
-            token.rentals.remove(position as usize);
-            self.tokens.save(deps.storage, &token_id, &token)?;
+    /// Platform fee held for a short-term reservation, to be refunded or charged again.
+    /// Older reservations paid it to the platform at booking, as much of it as the
+    /// platform balance still holds is taken back.
+    pub fn held_platform_fee(
+        &self,
+        storage: &mut dyn Storage,
+        rental: &Rental,
+    ) -> StdResult<Uint128> {
+        let fee = self.charged_platform_fee(storage, rental)?;
+        match rental.platform_fee {
+            Some(_) => Ok(fee),
+            None => self.refund_fee(storage, rental.denom.clone(), fee),
         }
+    }
 
-        if amount > Uint128::new(0) {
-            Ok(Response::new()
-                .add_attribute("action", "finalizelongtermrental")
-                .add_attribute("sender", info.sender)
-                .add_attribute("token_id", token_id)
-                .add_message(BankMsg::Send {
-                    to_address: target.clone(),
-                    amount: vec![Coin {
-                        denom: token.longterm_rental.denom,
-                        amount: amount,
-                    }],
-                }))
-        } else {
-            Ok(Response::new()
-                .add_attribute("action", "finalizelongtermrental")
-                .add_attribute("sender", info.sender)
-                .add_attribute("token_id", token_id))
+    /// Gives back the promo code use of a reservation that ends without a stay, so that
+    /// cancelled bookings do not use up a capped code
+    pub fn release_promo_use(&self, storage: &mut dyn Storage, rental: &Rental) -> StdResult<()> {
+        if let Some(code) = &rental.promo_code {
+            if let Some(mut promo) = self.promo_codes.may_load(storage, code)? {
+                promo.uses = promo.uses.saturating_sub(1);
+                self.promo_codes.save(storage, code, &promo)?;
+            }
         }
+        Ok(())
     }
 
-    pub fn withdrawtolandlord(
+    /// Credits the platform with the fee held for a reservation that can no longer be refunded
+    pub fn keep_platform_fee(
         &self,
-        deps: DepsMut,
-        env: Env,
-        info: MessageInfo,
-        token_id: String,
-        tenant: String,
-        renting_period: Vec<String>,
-        amount: u128,
-        address: String,
-    ) -> Result<Response<C>, ContractError> {
-        let mut token = self.tokens.load(deps.storage, &token_id)?;
-
-        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
-        let mut position = -1;
-
-        let current_time = env.block.time.seconds();
-
-        let check_in_time = renting_period[0].parse::<u64>();
-        let check_in_time_timestamp;
+        storage: &mut dyn Storage,
+        rental: &mut Rental,
+    ) -> StdResult<()> {
+        if let Some(fee) = rental.platform_fee.take() {
+            self.increase_balance(storage, rental.denom.clone(), fee)?;
+        }
+        Ok(())
+    }
 
-        match check_in_time {
-            Ok(timestamp) => {
-                check_in_time_timestamp = timestamp;
-            }
-            Err(_e) => {
-                return Err(ContractError::NotReserved {});
+    /// Removes the damage deposit of a stay, paying the claimed part to the host, or to the
+    /// share holders of a fractionalized token, and the rest back to the traveler
+    pub fn settle_damage_deposit(
+        &self,
+        storage: &mut dyn Storage,
+        token_id: &str,
+        check_in: u64,
+        token: &TokenInfo<T>,
+    ) -> StdResult<Vec<CosmosMsg<C>>> {
+        let deposit = match self
+            .damage_deposits
+            .may_load(storage, (token_id, check_in))?
+        {
+            Some(deposit) => deposit,
+            None => return Ok(vec![]),
+        };
+        self.damage_deposits.remove(storage, (token_id, check_in));
+
+        let mut messages = vec![];
+        if deposit.claimed > Uint128::new(0) {
+            match token.total_shares {
+                Some(total_shares) => self.distribute_rewards(
+                    storage,
+                    token_id,
+                    total_shares,
+                    &deposit.denom,
+                    deposit.claimed,
+                )?,
+                None => messages.push(self.payout(
+                    storage,
+                    token.owner.address.as_str(),
+                    deposit.denom.as_str(),
+                    deposit.claimed,
+                )?),
             }
         }
+        messages.extend(self.refund_payers(
+            storage,
+            &deposit.payers,
+            deposit.traveler.as_str(),
+            deposit.denom.as_str(),
+            deposit.refundable(),
+        )?);
+        Ok(messages)
+    }
 
-        if current_time <= check_in_time_timestamp {
-            return Err(ContractError::RentalNotActivated {});
+    /// Refund of `amount` to the traveler, or to the payers of a group reservation in
+    /// proportion to their shares
+    pub fn refund_payers(
+        &self,
+        storage: &dyn Storage,
+        payers: &[PayerShare],
+        traveler: &str,
+        denom: &str,
+        amount: Uint128,
+    ) -> StdResult<Vec<CosmosMsg<C>>> {
+        if amount.is_zero() {
+            return Ok(vec![]);
         }
-        let fee_percentage = self.get_fee(deps.storage)?;
+        let total: Uint128 = payers.iter().map(|share| share.amount).sum();
+        if total.is_zero() {
+            return Ok(vec![self.payout(storage, traveler, denom, amount)?]);
+        }
+        let mut messages = vec![];
+        let mut remaining = amount;
+        for (i, share) in payers.iter().enumerate() {
+            // the last payer also gets what rounding left over
+            let part = if i + 1 == payers.len() {
+                remaining
+            } else {
+                amount.multiply_ratio(share.amount, total)
+            };
+            remaining -= part;
+            if part > Uint128::new(0) {
+                messages.push(self.payout(storage, share.payer.as_str(), denom, part)?);
+            }
+        }
+        Ok(messages)
+    }
 
-        for (i, item) in token.rentals.iter().enumerate() {
-            if item.address == Some(Addr::unchecked(tenant.clone()))
-                && item.renting_period[0].to_string() == renting_period[0]
-                && item.renting_period[1].to_string() == renting_period[1]
-                && item.rental_type == true
-            {
-                position = i as i32;
-                if item.cancelled {
-                    return Err(ContractError::NotApproved {});
+    /// Routes the fee lines kept on a finalized stay, the host's part is returned to be paid
+    /// out with the rent
+    pub fn settle_fees(
+        &self,
+        storage: &mut dyn Storage,
+        denom: &str,
+        fees: &[ChargedFee],
+    ) -> StdResult<(Uint128, Vec<CosmosMsg<C>>)> {
+        let mut host_amount = Uint128::new(0);
+        let mut messages = vec![];
+        for fee in fees.iter().filter(|fee| fee.amount > Uint128::new(0)) {
+            match &fee.recipient {
+                FeeRecipient::Host => host_amount += fee.amount,
+                FeeRecipient::Platform => {
+                    self.increase_balance(storage, denom.to_string(), fee.amount)?;
                 }
-                if item.deposit_amount - Uint128::from(token.longterm_rental.price_per_month)
-                    < Uint128::from(amount)
-                {
-                    return Err(ContractError::UnavailableAmount {});
+                FeeRecipient::Address { address } => {
+                    messages.push(self.payout(storage, address, denom, fee.amount)?);
                 }
             }
         }
-        if position == -1 {
-            return Err(ContractError::NotReserved {});
-        } else {
-            self.increase_balance(
-                deps.storage,
-                token.longterm_rental.denom.clone(),
-                Uint128::new((u128::from(amount) * u128::from(fee_percentage)) / 10000),
-            )?;
-            token.rentals[position as usize].deposit_amount -= Uint128::from(amount);
-            self.tokens.save(deps.storage, &token_id, &token)?;
-        }
-        Ok(Response::new()
-            .add_attribute("action", "withdrawtolandlord")
-            .add_attribute("sender", info.sender)
-            .add_attribute("token_id", token_id)
-            .add_message(BankMsg::Send {
-                to_address: address,
-                amount: vec![Coin {
-                    denom: token.longterm_rental.denom,
-                    amount: Uint128::from(amount)
-                        - Uint128::new((u128::from(amount) * u128::from(fee_percentage)) / 10000),
-                }],
-            }))
+        Ok((host_amount, messages))
     }
 
-    #[allow(clippy::too_many_arguments)]
-    pub fn _update_approvals(
+    pub fn check_fee_lines(
         &self,
-        deps: DepsMut,
-        env: &Env,
-        info: &MessageInfo,
-        spender: &str,
-        token_id: &str,
-        // if add == false, remove. if add == true, remove then set with this expiration
-        add: bool,
-        expires: Option<Expiration>,
-    ) -> Result<TokenInfo<T>, ContractError> {
-        let mut token = self.tokens.load(deps.storage, token_id)?;
-        // ensure we have permissions
-        self.check_can_approve(deps.as_ref(), env, info, &token)?;
-
-        // update the approval list (remove any for the same spender before adding)
-        let spender_addr = deps.api.addr_validate(spender)?;
-        token.approvals.retain(|apr| apr.spender != spender_addr);
-
-        // only difference between approve and revoke
-        if add {
-            // reject expired data as invalid
-            let expires = expires.unwrap_or_default();
-            if expires.is_expired(&env.block) {
-                return Err(ContractError::Expired {});
+        deps: Deps,
+        token: &TokenInfo<T>,
+        fee_lines: &[FeeLine],
+    ) -> Result<(), ContractError> {
+        for line in fee_lines {
+            if line.name.is_empty() || line.name.len() > 64 {
+                return Err(ContractError::InvalidInput {});
+            }
+            if let FeeCharge::Percent { bps } = line.charge {
+                if bps > 10000 {
+                    return Err(ContractError::InvalidInput {});
+                }
+            }
+            if let FeeRecipient::Address { address } = &line.recipient {
+                deps.api.addr_validate(address)?;
+                // income of a fractionalized token belongs to its share holders
+                self.check_not_fractionalized(token)?;
             }
-            let approval = Approval {
-                spender: spender_addr,
-                expires,
-            };
-            token.approvals.push(approval);
         }
+        Ok(())
+    }
 
-        self.tokens.save(deps.storage, token_id, &token)?;
+    /// Drops the given bids on a token from the bidder index
+    pub fn unindex_bids(&self, storage: &mut dyn Storage, token_id: &str, bids: &[Bid]) {
+        for bid in bids {
+            self.bids_by_bidder
+                .remove(storage, (bid.address.as_str(), token_id));
+        }
+    }
 
-        Ok(token)
+    /// Refund messages returning every given bid to its bidder
+    pub fn refund_bids(&self, storage: &dyn Storage, bids: &[Bid]) -> StdResult<Vec<CosmosMsg<C>>> {
+        bids.iter()
+            .filter(|bid| bid.offer > Uint128::new(0))
+            .map(|bid| self.payout(storage, bid.address.as_str(), bid.denom.as_str(), bid.offer))
+            .collect()
+    }
+
+    /// Payment of `amount` to `to_address`. `denom` is either a native denom or the address
+    /// of a CW20 token that paid into this contract through `Receive`
+    pub fn payout(
+        &self,
+        storage: &dyn Storage,
+        to_address: impl Into<String>,
+        denom: impl Into<String>,
+        amount: Uint128,
+    ) -> StdResult<CosmosMsg<C>> {
+        let denom = denom.into();
+        if self.cw20_tokens.has(storage, &denom) {
+            Ok(WasmMsg::Execute {
+                contract_addr: denom,
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: to_address.into(),
+                    amount,
+                })?,
+                funds: vec![],
+            }
+            .into())
+        } else {
+            Ok(BankMsg::Send {
+                to_address: to_address.into(),
+                amount: vec![Coin { denom, amount }],
+            }
+            .into())
+        }
     }
 
     /// returns true iff the sender can execute approve or reject on the contract
@@ -2008,7 +5813,7 @@ where
         token: &TokenInfo<T>,
     ) -> Result<(), ContractError> {
         // owner can approve
-        if token.owner.address == info.sender.to_string() {
+        if token.owner.address == info.sender {
             return Ok(());
         }
         // operator can approve
@@ -2033,17 +5838,13 @@ where
         _env: &Env,
         token: &TokenInfo<T>,
     ) -> Result<(), ContractError> {
-        if token.rentals.len() == 0 {
-            return Ok(());
+        if token.rentals.is_empty() {
+            Ok(())
         } else {
-            if token
-                .rentals
-                .iter()
-                .any(|rental| rental.rental_type == false)
-            {
-                return Err(ContractError::RentalActive {});
+            if token.rentals.iter().any(|rental| !rental.rental_type) {
+                Err(ContractError::RentalActive {})
             } else {
-                return Ok(());
+                Ok(())
             }
         }
 
@@ -2066,17 +5867,13 @@ where
         _env: &Env,
         token: &TokenInfo<T>,
     ) -> Result<(), ContractError> {
-        if token.rentals.len() == 0 {
-            return Ok(());
+        if token.rentals.is_empty() {
+            Ok(())
         } else {
-            if token
-                .rentals
-                .iter()
-                .any(|rental| rental.rental_type == true)
-            {
-                return Err(ContractError::RentalActive {});
+            if token.rentals.iter().any(|rental| rental.rental_type) {
+                Err(ContractError::RentalActive {})
             } else {
-                return Ok(());
+                Ok(())
             }
         }
         // if token.rentals.len() == 0 {
@@ -2097,10 +5894,49 @@ where
         _env: &Env,
         token: &TokenInfo<T>,
     ) -> Result<(), ContractError> {
-        if token.bids.len() != 0 {
-            return Err(ContractError::BidsActive {});
+        if !token.bids.is_empty() {
+            Err(ContractError::BidsActive {})
         } else {
-            return Ok(());
+            Ok(())
+        }
+    }
+
+    pub fn check_royalty(&self, deps: Deps, royalty: &Royalty) -> Result<(), ContractError> {
+        deps.api.addr_validate(&royalty.recipient)?;
+        // paid next to the platform fee, together they can't exceed the price
+        if royalty.share.saturating_add(self.get_fee(deps.storage)?) > 10000 {
+            return Err(ContractError::InvalidInput {});
+        }
+        Ok(())
+    }
+
+    pub fn check_can_edit_escrow(&self, token: &TokenInfo<T>) -> Result<(), ContractError> {
+        if token
+            .escrow
+            .as_ref()
+            .is_some_and(|escrow| escrow.accepted_at.is_some())
+        {
+            return Err(ContractError::EscrowActive {});
+        }
+        Ok(())
+    }
+
+    pub fn check_not_fractionalized(&self, token: &TokenInfo<T>) -> Result<(), ContractError> {
+        if token.total_shares.is_some() {
+            return Err(ContractError::Fractionalized {});
+        }
+        Ok(())
+    }
+
+    pub fn check_can_edit_auction(
+        &self,
+        _env: &Env,
+        token: &TokenInfo<T>,
+    ) -> Result<(), ContractError> {
+        if token.auction.islisted == Some(true) || token.dutch_auction.islisted == Some(true) {
+            Err(ContractError::AuctionActive {})
+        } else {
+            Ok(())
         }
     }
 
@@ -2113,7 +5949,7 @@ where
         token: &TokenInfo<T>,
     ) -> Result<(), ContractError> {
         // owner can send
-        if token.owner.address == info.sender.to_string() {
+        if token.owner.address == info.sender {
             return Ok(());
         }
 
@@ -2143,3 +5979,153 @@ where
         }
     }
 }
+
+/// Reads `available_period` as consecutive (start, end) timestamp pairs, the same
+/// encoding as `renting_period`. Free-form text is rejected rather than read as a
+/// property open at all times.
+fn parse_available_period(available_period: &[String]) -> Result<Vec<DateRange>, ContractError> {
+    if available_period.len() % 2 != 0 {
+        return Err(ContractError::InvalidInput {});
+    }
+    available_period
+        .chunks(2)
+        .map(|pair| {
+            let start = pair[0].parse::<u64>();
+            let end = pair[1].parse::<u64>();
+            match (start, end) {
+                (Ok(start), Ok(end)) if start < end => Ok(DateRange { start, end }),
+                _ => Err(ContractError::InvalidInput {}),
+            }
+        })
+        .collect()
+}
+
+/// Check-in of a `renting_period`, which keys the stay's damage deposit
+fn parse_check_in(renting_period: &[String]) -> Result<u64, ContractError> {
+    renting_period
+        .first()
+        .and_then(|check_in| check_in.parse::<u64>().ok())
+        .ok_or(ContractError::NotReserved {})
+}
+
+/// Check-in and check-out of a `renting_period`
+fn parse_stay(renting_period: &[String]) -> Result<(u64, u64), ContractError> {
+    let check_out = renting_period
+        .get(1)
+        .and_then(|check_out| check_out.parse::<u64>().ok())
+        .ok_or(ContractError::NotReserved {})?;
+    Ok((parse_check_in(renting_period)?, check_out))
+}
+
+/// Position in the check-in ordered `rentals` at which a stay fits without overlapping any of them,
+/// after checking it against the listing's booking window
+fn reservation_slot(
+    rentals: &[Rental],
+    new_checkin_timestamp: u64,
+    new_checkout_timestamp: u64,
+    current_time: u64,
+    booking_window: &BookingWindow,
+) -> Result<usize, ContractError> {
+    if booking_window.advance_notice > 0
+        && new_checkin_timestamp < current_time.saturating_add(booking_window.advance_notice)
+    {
+        return Err(ContractError::AdvanceNoticeRequired {});
+    }
+    if booking_window
+        .booking_horizon
+        .is_some_and(|horizon| new_checkin_timestamp > current_time.saturating_add(horizon))
+    {
+        return Err(ContractError::BeyondBookingHorizon {});
+    }
+
+    let buffer = booking_window.buffer_days.saturating_mul(86400);
+    match free_slot(
+        rentals,
+        new_checkin_timestamp,
+        new_checkout_timestamp,
+        buffer,
+    ) {
+        Some(placetoreserve) => Ok(placetoreserve),
+        // the stay itself fits, only the turnover time around it is missing
+        None if buffer > 0
+            && free_slot(rentals, new_checkin_timestamp, new_checkout_timestamp, 0).is_some() =>
+        {
+            Err(ContractError::BufferDaysRequired {})
+        }
+        None => Err(ContractError::UnavailablePeriod {}),
+    }
+}
+
+/// Position for a stay keeping `buffer` seconds to the stays around it. Without a buffer
+/// stays cannot share a check-out and check-in time, with one a stay can start as soon as
+/// the buffer after another ends.
+fn free_slot(
+    rentals: &[Rental],
+    new_checkin_timestamp: u64,
+    new_checkout_timestamp: u64,
+    buffer: u64,
+) -> Option<usize> {
+    let mut placetoreserve: i32 = -1;
+    let lenofrentals = rentals.len();
+
+    let clear = |end: u64, start: u64| {
+        if buffer > 0 {
+            end.saturating_add(buffer) <= start
+        } else {
+            end < start
+        }
+    };
+
+    let mut flag = false;
+    for (i, rental) in rentals.iter().enumerate() {
+        let checkin = rental.renting_period[0];
+        let checkout = rental.renting_period[1];
+        if clear(new_checkout_timestamp, checkin) {
+            if i == 0 {
+                placetoreserve = 0;
+                break;
+            } else if flag {
+                placetoreserve = i as i32;
+                break;
+            }
+        } else if clear(checkout, new_checkin_timestamp) {
+            flag = true;
+            if i == lenofrentals - 1 {
+                placetoreserve = lenofrentals as i32;
+                break;
+            }
+        } else {
+            flag = false;
+        }
+    }
+
+    if placetoreserve == -1 {
+        if lenofrentals > 0 {
+            return None;
+        } else {
+            placetoreserve = 0;
+        }
+    }
+    Some(placetoreserve as usize)
+}
+
+/// Host approval deadline of a new reservation
+fn respond_by(auto_approve: bool, response_window: Option<u64>, reserved_at: u64) -> Option<u64> {
+    if auto_approve {
+        None
+    } else {
+        response_window.map(|window| reserved_at + window)
+    }
+}
+
+/// Takes the part refunded on a cancellation refunding `percentage` of the rent off every
+/// fee line, returning the total
+fn refund_fees(fees: &mut [ChargedFee], percentage: u64) -> Uint128 {
+    let mut refund = Uint128::new(0);
+    for fee in fees.iter_mut() {
+        let amount = fee.refund_amount(percentage);
+        fee.amount -= amount;
+        refund += amount;
+    }
+    refund
+}
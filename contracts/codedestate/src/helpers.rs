@@ -172,9 +172,13 @@ impl<Q: CustomMsg, E: CustomMsg> Cw721Contract<Q, E> {
         querier: &QuerierWrapper,
         start_after: Option<String>,
         limit: Option<u32>,
-        owner:String,
+        owner: String,
     ) -> StdResult<TokensResponse> {
-        let req = QueryMsg::AllTokens {owner, start_after, limit };
+        let req = QueryMsg::AllTokens {
+            owner,
+            start_after,
+            limit,
+        };
         self.query(querier, req)
     }
 
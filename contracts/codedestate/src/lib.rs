@@ -25,11 +25,7 @@ pub use cw_ownable::{Action, Ownership, OwnershipError};
 
 use cosmwasm_std::Empty;
 
-#[cw_serde]
-pub struct Trait {
-    pub key: String,
-    pub value: String,
-}
+pub use cw721::Trait;
 
 #[cw_serde]
 #[derive(Default)]
@@ -88,6 +84,16 @@ pub mod entry {
         tract.execute(deps, env, info, msg)
     }
 
+    #[cfg_attr(not(feature = "library"), entry_point)]
+    pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+        // stored state added since the last release deserializes with defaults,
+        // e.g. bids without an expiration never expire
+        // bids placed before the bidder index existed are indexed through `IndexBids`
+        cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+        Ok(Response::new().add_attribute("action", "migrate"))
+    }
+
     #[cfg_attr(not(feature = "library"), entry_point)]
     pub fn query(deps: Deps, env: Env, msg: QueryMsg<Empty>) -> StdResult<Binary> {
         let tract = Cw721Contract::<Extension, Empty, Empty, Empty>::default();
@@ -1,9 +1,20 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
 // use cosmwasm_std::Binary;
 use crate::state::Owner;
-use cosmwasm_std::Coin;
+use crate::Trait;
+use cosmwasm_std::{Binary, Coin, Uint128};
+use cw20::Cw20ReceiveMsg;
+use cw721::AvailabilityCalendar;
 use cw721::CancellationItem;
 use cw721::Expiration;
+use cw721::FeeLine;
+use cw721::HostPolicy;
+use cw721::OffersResponse;
+use cw721::PriceRule;
+use cw721::PromoDiscount;
+use cw721::Royalty;
+use cw721::{BookingWindow, InstantBookRules};
+use cw721::{GroupReservation, PayerShare};
 use cw_ownable::{cw_ownable_execute, cw_ownable_query};
 use schemars::JsonSchema;
 
@@ -78,6 +89,27 @@ pub enum ExecuteMsg<T, E> {
         token_uri: Option<String>,
         /// Any custom extension used by this contract
         extension: T,
+        /// Royalty paid on sales of this token, the collection royalty applies if unset
+        royalty: Option<Royalty>,
+    },
+
+    /// Collection royalty for tokens minted without their own, can only be called by the contract minter
+    SetDefaultRoyalty {
+        royalty: Option<Royalty>,
+    },
+
+    /// Accept or stop accepting payments through `Receive` from a CW20 contract, can only be
+    /// called by the contract minter
+    SetCw20Token {
+        address: String,
+        allowed: bool,
+    },
+
+    /// Add bids placed before the bidder index existed to it, `limit` tokens after
+    /// `start_after` at a time, can only be called by the contract minter
+    IndexBids {
+        start_after: Option<String>,
+        limit: u32,
     },
 
     SetExtension {
@@ -104,6 +136,8 @@ pub enum ExecuteMsg<T, E> {
 
     SetBidToBuy {
         token_id: String,
+        /// Leave unset for a bid that never expires
+        expires: Option<Expiration>,
     },
 
     RejectBid {
@@ -111,7 +145,104 @@ pub enum ExecuteMsg<T, E> {
         bidder: String,
     },
 
+    /// Owner asks a bidder for a higher price
+    SetCounterOffer {
+        token_id: String,
+        bidder: String,
+        amount: u128,
+    },
+    /// Bidder accepts the counter-offer by sending the difference, the sale settles right away
+    AcceptCounterOffer {
+        token_id: String,
+    },
+    /// Anyone can send expired bids back to their bidders
+    ReclaimExpiredBids {
+        token_id: String,
+    },
+
+    // English auction
+    SetListForAuction {
+        token_id: String,
+        denom: String,
+        reserve_price: u128,
+        start_time: u64,
+        end_time: u64,
+        minimum_increment: u128,
+        extension_window: u64,
+    },
+    SetUnlistForAuction {
+        token_id: String,
+    },
+    SetBidForAuction {
+        token_id: String,
+    },
+    /// Anyone can settle an auction once its end time has passed
+    SettleAuction {
+        token_id: String,
+    },
+
+    // Dutch auction
+    SetListForDutchAuction {
+        token_id: String,
+        denom: String,
+        start_price: u128,
+        floor_price: u128,
+        start_time: u64,
+        end_time: u64,
+        step_interval: u64,
+    },
+    SetUnlistForDutchAuction {
+        token_id: String,
+    },
+    /// Buys the token at the current price, any overpayment is refunded
+    BuyDutchAuction {
+        token_id: String,
+    },
+
+    // Offers
+    /// Escrow the attached funds as an offer on `token_id`, listed for sale or not,
+    /// or on any token whose attributes include all of `traits` if `token_id` is unset
+    MakeOffer {
+        token_id: Option<String>,
+        traits: Vec<Trait>,
+        expires: Option<Expiration>,
+    },
+    /// Refund an offer, by its bidder or by anyone once it has expired
+    CancelOffer {
+        offer_id: u64,
+    },
+    /// Sell `token_id` to the bidder of an offer, settled in the same transaction
+    AcceptOffer {
+        offer_id: u64,
+        token_id: String,
+    },
+
+    // Escrow sale
+    /// Offer to buy through an escrowed sale, the attached funds are the earnest deposit.
+    /// The inspection and closing periods are at most a year each.
+    OfferEscrowSale {
+        token_id: String,
+        price: u128,
+        inspection_period: u64,
+        closing_period: u64,
+        penalty: u64,
+    },
+    AcceptEscrowSale {
+        token_id: String,
+    },
+    /// Pay the balance and take ownership, only by the buyer before the closing deadline
+    CloseEscrowSale {
+        token_id: String,
+    },
+    /// Withdraw an offer or back out of an accepted sale.
+    /// The seller can only cancel an accepted sale once the closing deadline has passed.
+    CancelEscrowSale {
+        token_id: String,
+    },
+
     // Short term rental
+    /// Bookings are limited to the (start, end) timestamp pairs in `available_period`,
+    /// an empty list leaves the property open at all times
     SetListForShortTermRental {
         token_id: String,
         denom: String,
@@ -121,6 +252,24 @@ pub enum ExecuteMsg<T, E> {
         minimum_stay: u64,
         cancellation: Vec<CancellationItem>,
     },
+    /// Replace the availability calendar of a short-term rental, open ranges are also
+    /// reflected in `available_period`
+    SetAvailabilityForShortTerm {
+        token_id: String,
+        availability: AvailabilityCalendar,
+    },
+    /// Replace the nightly price rules of a short-term rental
+    SetPriceRulesForShortTerm {
+        token_id: String,
+        price_rules: Vec<PriceRule>,
+    },
+    /// Guest capacity and the extra-guest fee charged per night
+    SetGuestPolicyForShortTerm {
+        token_id: String,
+        max_guests: Option<usize>,
+        included_guests: usize,
+        extra_guest_fee: u128,
+    },
     SetUnlistForShorttermRental {
         token_id: String,
     },
@@ -128,6 +277,103 @@ pub enum ExecuteMsg<T, E> {
         token_id: String,
         renting_period: Vec<String>,
         guests: usize,
+        promo_code: Option<String>,
+    },
+    /// Rate the other party of a finalized stay, once each for the host and the traveler,
+    /// within `REVIEW_WINDOW` of the stay being finalized
+    ReviewStay {
+        token_id: String,
+        renting_period: Vec<String>,
+        rating: u8,
+        review_hash: String,
+    },
+    /// Host cancels an approved stay of either rental type before check-in. The traveler is
+    /// refunded in full plus the policy penalty out of the host bond. A platform fee already
+    /// withdrawn is refunded out of the bond too, and the cancellation fails if it can't be.
+    CancelReservationByHost {
+        token_id: String,
+        traveler: String,
+        renting_period: Vec<String>,
+    },
+    /// Add the funds sent to the sender's host bond, funds sent with `SetListFor*` are too
+    PostHostBond {},
+    /// Return the sender's host bond once the latest stay booked at their properties has
+    /// checked out
+    WithdrawHostBond {},
+    SetHostPolicy {
+        policy: HostPolicy,
+    },
+    /// Seconds the host has to approve a short-term reservation before anyone can expire it
+    SetResponseWindowForShortTerm {
+        token_id: String,
+        response_window: Option<u64>,
+    },
+    SetResponseWindowForLongTerm {
+        token_id: String,
+        response_window: Option<u64>,
+    },
+    /// Cleaning, service and tax lines charged on top of the rent, replacing earlier ones
+    SetFeeLinesForShortTerm {
+        token_id: String,
+        fee_lines: Vec<FeeLine>,
+    },
+    SetFeeLinesForLongTerm {
+        token_id: String,
+        fee_lines: Vec<FeeLine>,
+    },
+    /// Conditions for `auto_approve` to approve a reservation, unset approves every one
+    SetInstantBookRulesForShortTerm {
+        token_id: String,
+        rules: Option<InstantBookRules>,
+    },
+    SetInstantBookRulesForLongTerm {
+        token_id: String,
+        rules: Option<InstantBookRules>,
+    },
+    /// Advance notice, booking horizon and buffer days checked on every new or moved stay
+    SetBookingWindowForShortTerm {
+        token_id: String,
+        booking_window: BookingWindow,
+    },
+    SetBookingWindowForLongTerm {
+        token_id: String,
+        booking_window: BookingWindow,
+    },
+    /// Contract owner records or withdraws a traveler's KYC attestation
+    SetKycAttestation {
+        traveler: String,
+        attested: bool,
+    },
+    /// Drop a reservation left unapproved past its host response deadline and refund the
+    /// traveler in full
+    ExpireReservation {
+        token_id: String,
+        traveler: String,
+        renting_period: Vec<String>,
+    },
+    /// Move the sender's reservation of either rental type to `new_renting_period`. A dearer
+    /// stay needs the difference sent along, a cheaper one is refunded.
+    ModifyReservation {
+        token_id: String,
+        renting_period: Vec<String>,
+        new_renting_period: Vec<String>,
+    },
+    /// Pending reservation funded by several payers, booked for the organizer (the sender)
+    /// once every share is paid. Funds sent along pay the organizer's own share.
+    CreateGroupReservationForShortTerm {
+        token_id: String,
+        renting_period: Vec<String>,
+        guests: usize,
+        shares: Vec<PayerShare>,
+        deadline: u64,
+    },
+    /// Pay the sender's share of a group reservation
+    FundGroupReservation {
+        group_id: u64,
+    },
+    /// Refund every paid share, by anyone after the deadline or by the organizer before it
+    RefundGroupReservation {
+        group_id: u64,
     },
     CancelReservationForShortterm {
         token_id: String,
@@ -144,16 +390,73 @@ pub enum ExecuteMsg<T, E> {
         traveler: String,
         renting_period: Vec<String>,
     },
+    /// Approve a reservation, attaching access instructions encrypted to the traveler's
+    /// public key. They are stored in public contract state, so they must never be sent
+    /// unencrypted. Approving an approved stay again replaces its instructions.
     SetApproveForShortTerm {
         token_id: String,
         traveler: String,
         renting_period: Vec<String>,
+        access_instructions: Option<Binary>,
     },
     FinalizeShortTermRental {
         token_id: String,
         traveler: String,
         renting_period: Vec<String>,
     },
+    /// Refundable damage deposit collected with each reservation, and the seconds after
+    /// check-out in which the host can claim against it
+    SetDamageDepositForShortTerm {
+        token_id: String,
+        damage_deposit: u128,
+        claim_window: u64,
+    },
+    /// Discounts in basis points for stays of at least 7 and 28 nights
+    SetStayDiscountsForShortTerm {
+        token_id: String,
+        weekly_discount: u64,
+        monthly_discount: u64,
+    },
+    /// Accept platform promo codes on the listing, their discount comes off the rent
+    SetPlatformPromosForShortTerm {
+        token_id: String,
+        accept: bool,
+    },
+    /// Hosts issue codes for their own token, the contract owner issues platform codes
+    /// valid for any token accepting them by leaving `token_id` unset
+    CreatePromoCode {
+        code: String,
+        token_id: Option<String>,
+        discount: PromoDiscount,
+        max_uses: Option<u32>,
+        expires: Option<Expiration>,
+    },
+    /// Issuer or contract owner withdraws a promo code
+    RevokePromoCode {
+        code: String,
+    },
+    /// Host deduction from the damage deposit of a finished stay, replaces an earlier claim
+    ClaimDamageDeposit {
+        token_id: String,
+        renting_period: Vec<String>,
+        amount: u128,
+        reason: String,
+    },
+    /// Traveler disputes the host's claim, leaving it to the contract owner
+    DisputeDamageClaim {
+        token_id: String,
+        renting_period: Vec<String>,
+    },
+    ResolveDamageDispute {
+        token_id: String,
+        renting_period: Vec<String>,
+        amount: u128,
+    },
+    /// Pay the claimed amount to the host and refund the rest to the traveler
+    ReleaseDamageDeposit {
+        token_id: String,
+        renting_period: Vec<String>,
+    },
 
     WithdrawToLandlord {
         token_id: String,
@@ -174,6 +477,13 @@ pub enum ExecuteMsg<T, E> {
         cancellation: Vec<CancellationItem>,
     },
 
+    /// Guest capacity and the extra-guest fee charged per month
+    SetGuestPolicyForLongTerm {
+        token_id: String,
+        max_guests: Option<usize>,
+        included_guests: usize,
+        extra_guest_fee: u128,
+    },
     SetUnlistForLongtermRental {
         token_id: String,
     },
@@ -214,17 +524,107 @@ pub enum ExecuteMsg<T, E> {
         renting_period: Vec<String>,
     },
 
+    /// Lock the token and issue `shares` units of it to the owner.
+    /// Rental income and damage claims are then credited to share holders pro-rata, and
+    /// fee lines can no longer be paid to an address.
+    Fractionalize {
+        token_id: String,
+        shares: Uint128,
+    },
+    TransferShares {
+        token_id: String,
+        recipient: String,
+        amount: Uint128,
+    },
+    /// Take ownership of the token back, the sender must hold all of its shares
+    RedeemShares {
+        token_id: String,
+    },
+    /// Pay out all rental income credited to the sender as a share holder,
+    /// after crediting what the sender's shares of `token_id` earned
+    ClaimRewards {
+        token_id: String,
+    },
+
     /// Burn an NFT the sender has access to
     Burn {
         token_id: String,
     },
 
+    /// Pay with a CW20 token, `msg` is a `ReceiveMsg`
+    Receive(Cw20ReceiveMsg),
+
     /// Extension msg
     Extension {
         msg: E,
     },
 }
 
+/// Payment flows that can be funded with a CW20 token through `ExecuteMsg::Receive`.
+/// They behave like their `ExecuteMsg` counterparts with the token contract address as denom.
+#[cw_serde]
+pub enum ReceiveMsg {
+    SetBidToBuy {
+        token_id: String,
+        expires: Option<Expiration>,
+    },
+    AcceptCounterOffer {
+        token_id: String,
+    },
+    SetBidForAuction {
+        token_id: String,
+    },
+    BuyDutchAuction {
+        token_id: String,
+    },
+    MakeOffer {
+        token_id: Option<String>,
+        traits: Vec<Trait>,
+        expires: Option<Expiration>,
+    },
+    OfferEscrowSale {
+        token_id: String,
+        price: u128,
+        inspection_period: u64,
+        closing_period: u64,
+        penalty: u64,
+    },
+    CloseEscrowSale {
+        token_id: String,
+    },
+    SetReservationForShortTerm {
+        token_id: String,
+        renting_period: Vec<String>,
+        guests: usize,
+        promo_code: Option<String>,
+    },
+    SetReservationForLongTerm {
+        token_id: String,
+        renting_period: Vec<String>,
+        guests: usize,
+    },
+    DepositForLongTermRental {
+        token_id: String,
+        renting_period: Vec<String>,
+    },
+    CreateGroupReservationForShortTerm {
+        token_id: String,
+        renting_period: Vec<String>,
+        guests: usize,
+        shares: Vec<PayerShare>,
+        deadline: u64,
+    },
+    FundGroupReservation {
+        group_id: u64,
+    },
+    ModifyReservation {
+        token_id: String,
+        renting_period: Vec<String>,
+        new_renting_period: Vec<String>,
+    },
+    PostHostBond {},
+}
+
 #[cw_ownable_query]
 #[cw_serde]
 #[derive(QueryResponses)]
@@ -293,12 +693,175 @@ pub enum QueryMsg<Q: JsonSchema> {
     #[returns(cw721::Sell)]
     NftInfoSell { token_id: String },
 
+    /// Rentals of a token without their access instructions
     #[returns(cw721::RentalsResponse)]
     NftRentals { token_id: String },
 
+    /// Encrypted access instructions of an approved short-term stay, wiped when the stay
+    /// is cancelled or finalized. Anyone can run this query, the payload is only as secret
+    /// as its client-side encryption.
+    #[returns(Option<Binary>)]
+    AccessInstructions {
+        token_id: String,
+        traveler: String,
+        renting_period: Vec<String>,
+    },
+
     #[returns(cw721::BidsResponse)]
     NftBids { token_id: String },
 
+    /// Open bids placed by `bidder` across all tokens, 10 per page by default and at most 100
+    #[returns(cw721::BidderBidsResponse)]
+    BidsByBidder {
+        bidder: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Royalty owed on a sale of the token at `sale_price`
+    #[returns(cw721::RoyaltiesInfoResponse)]
+    RoyaltyInfo {
+        token_id: String,
+        sale_price: Uint128,
+    },
+
+    /// Open offers on `token_id`, or collection-wide offers if unset, 10 per page by default
+    /// and at most 100
+    #[returns(OffersResponse)]
+    Offers {
+        token_id: Option<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Price of a short-term stay booked now, as charged by `SetReservationForShortTerm`
+    #[returns(cw721::ShortTermQuote)]
+    QuoteShortTerm {
+        token_id: String,
+        renting_period: Vec<String>,
+        guests: usize,
+        promo_code: Option<String>,
+    },
+
+    /// First-month deposit of a long-term stay, as charged by `SetReservationForLongTerm`
+    #[returns(cw721::LongTermQuote)]
+    QuoteLongTerm {
+        token_id: String,
+        renting_period: Vec<String>,
+        guests: usize,
+    },
+
+    /// Free and blocked days of a short-term rental between `from` and `to`
+    #[returns(cw721::AvailabilityResponse)]
+    Availability {
+        token_id: String,
+        from: u64,
+        to: u64,
+    },
+
+    /// Escrowed sale with its deadlines and the amounts at stake
+    #[returns(cw721::EscrowSaleResponse)]
+    NftInfoEscrow { token_id: String },
+
+    /// Reviews of stays on a token by both hosts and travelers, 10 per page by default and
+    /// at most 100
+    #[returns(cw721::ReviewsResponse)]
+    Reviews {
+        token_id: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Reviews of a host or traveler, 10 per page by default and at most 100
+    #[returns(cw721::ReviewsResponse)]
+    ReviewsOf {
+        subject: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    #[returns(cw721::RatingResponse)]
+    TokenRating { token_id: String },
+
+    #[returns(cw721::RatingResponse)]
+    HostRating { host: String },
+
+    #[returns(cw721::RatingResponse)]
+    TravelerRating { traveler: String },
+
+    /// Cancellation record and bond of a host
+    #[returns(cw721::HostRecordResponse)]
+    HostRecord { host: String },
+
+    #[returns(cw721::TravelerProfile)]
+    TravelerProfile { traveler: String },
+
+    #[returns(cw721::HostPolicy)]
+    HostPolicy {},
+
+    #[returns(cw721::PromoCode)]
+    PromoCode { code: String },
+
+    /// Pending reservations past their host response deadline, for a keeper to expire.
+    /// `limit` counts the tokens looked at rather than the reservations returned, 10 per
+    /// page by default and at most 100.
+    #[returns(cw721::ExpiredReservationsResponse)]
+    ExpiredReservations {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    #[returns(GroupReservation)]
+    GroupReservation { group_id: u64 },
+
+    /// Group reservations still unfunded past their deadline, for a keeper to refund.
+    /// `limit` counts the groups looked at rather than the ones returned, 10 per page by
+    /// default and at most 100.
+    #[returns(cw721::ExpiredGroupReservationsResponse)]
+    ExpiredGroupReservations {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Damage deposits held for stays on a token, by check-in, 10 per page by default and at
+    /// most 100
+    #[returns(cw721::DamageDepositsResponse)]
+    DamageDeposits {
+        token_id: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    #[returns(cw721::SharesResponse)]
+    Shares { token_id: String, holder: String },
+
+    /// Share holders of `token_id`, 10 per page by default and at most 100
+    #[returns(cw721::ShareHoldersResponse)]
+    ShareHolders {
+        token_id: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Rental income claimable by a share holder, including what their shares of `token_id`
+    /// earned since they were last credited
+    #[returns(cw721::RewardsResponse)]
+    Rewards { holder: String, token_id: String },
+
+    #[returns(cw721::Auction)]
+    NftInfoAuction { token_id: String },
+
+    /// Current highest bid and time left on the auction
+    #[returns(cw721::AuctionStatusResponse)]
+    AuctionStatus { token_id: String },
+
+    #[returns(cw721::DutchAuction)]
+    NftInfoDutchAuction { token_id: String },
+
+    /// Live price of a dutch auction
+    #[returns(cw721::CurrentPriceResponse)]
+    CurrentPrice { token_id: String },
+
     /// With MetaData Extension.
     /// Returns the result of both `NftInfo` and `OwnerOf` as one query as an optimization
     /// for clients
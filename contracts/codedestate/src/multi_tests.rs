@@ -2,6 +2,7 @@ use cosmwasm_std::{to_binary, Addr, Empty, QuerierWrapper, WasmMsg};
 use cw721::OwnerOfResponse;
 use cw_multi_test::{App, Contract, ContractWrapper, Executor};
 
+use crate::state::Owner;
 use crate::MinterResponse;
 
 fn cw721_base_contract() -> Box<dyn Contract<Empty>> {
@@ -14,16 +15,6 @@ fn cw721_base_contract() -> Box<dyn Contract<Empty>> {
     Box::new(contract)
 }
 
-fn cw721_base_016_contract() -> Box<dyn Contract<Empty>> {
-    use cw721_base_016 as v16;
-    let contract = ContractWrapper::new(
-        v16::entry::execute,
-        v16::entry::instantiate,
-        v16::entry::query,
-    );
-    Box::new(contract)
-}
-
 fn query_owner(querier: QuerierWrapper, cw721: &Addr, token_id: String) -> Addr {
     let resp: OwnerOfResponse = querier
         .query_wasm_smart(
@@ -43,9 +34,13 @@ fn mint_transfer_and_burn(app: &mut App, cw721: Addr, sender: Addr, token_id: St
         cw721.clone(),
         &crate::ExecuteMsg::<Empty, Empty>::Mint {
             token_id: token_id.clone(),
-            owner: sender.to_string(),
+            owner: Owner {
+                chain_type: "cosmos".to_string(),
+                address: sender.to_string(),
+            },
             token_uri: None,
             extension: Empty::default(),
+            royalty: None,
         },
         &[],
     )
@@ -77,60 +72,48 @@ fn mint_transfer_and_burn(app: &mut App, cw721: Addr, sender: Addr, token_id: St
     .unwrap();
 }
 
-/// Instantiates a 0.16 version of this contract and tests that tokens
-/// can be minted, transferred, and burnred after migration.
-
-// #[test]
-// fn test_016_017_migration() {
-//     use cw721_base_016 as v16;
-//     let mut app = App::default();
-//     let admin = || Addr::unchecked("admin");
-
-//     let code_id_016 = app.store_code(cw721_base_016_contract());
-//     let code_id_017 = app.store_code(cw721_base_contract());
-
-//     let cw721 = app
-//         .instantiate_contract(
-//             code_id_016,
-//             admin(),
-//             &v16::InstantiateMsg {
-//                 name: "collection".to_string(),
-//                 symbol: "symbol".to_string(),
-//                 minter: admin().into_string(),
-//             },
-//             &[],
-//             "codedestate",
-//             Some(admin().into_string()),
-//         )
-//         .unwrap();
-
-//     mint_transfer_and_burn(&mut app, cw721.clone(), admin(), "1".to_string());
+/// Instantiates this contract and tests that tokens can be minted,
+/// transferred, and burned before and after a migration.
+#[test]
+fn mint_transfer_burn_across_migration() {
+    let mut app = App::default();
+    let admin = || Addr::unchecked("admin");
+
+    let code_id = app.store_code(cw721_base_contract());
+
+    let cw721 = app
+        .instantiate_contract(
+            code_id,
+            admin(),
+            &crate::InstantiateMsg {
+                name: "collection".to_string(),
+                symbol: "symbol".to_string(),
+                minter: admin().into_string(),
+            },
+            &[],
+            "codedestate",
+            Some(admin().into_string()),
+        )
+        .unwrap();
 
-//     app.execute(
-//         admin(),
-//         WasmMsg::Migrate {
-//             contract_addr: cw721.to_string(),
-//             new_code_id: code_id_017,
-//             msg: to_binary(&Empty::default()).unwrap(),
-//         }
-//         .into(),
-//     )
-//     .unwrap();
+    mint_transfer_and_burn(&mut app, cw721.clone(), admin(), "1".to_string());
 
-//     mint_transfer_and_burn(&mut app, cw721.clone(), admin(), "1".to_string());
+    app.execute(
+        admin(),
+        WasmMsg::Migrate {
+            contract_addr: cw721.to_string(),
+            new_code_id: code_id,
+            msg: to_binary(&Empty::default()).unwrap(),
+        }
+        .into(),
+    )
+    .unwrap();
 
-//     // check new mint query response works.
-//     let m: MinterResponse = app
-//         .wrap()
-//         .query_wasm_smart(&cw721, &crate::QueryMsg::<Empty>::Minter {})
-//         .unwrap();
-//     assert_eq!(m.minter, Some(admin().to_string()));
+    mint_transfer_and_burn(&mut app, cw721.clone(), admin(), "1".to_string());
 
-//     // check that the new response is backwards compatable when minter
-//     // is not None.
-//     let m: v16::MinterResponse = app
-//         .wrap()
-//         .query_wasm_smart(&cw721, &crate::QueryMsg::<Empty>::Minter {})
-//         .unwrap();
-//     assert_eq!(m.minter, admin().to_string());
-// }
+    let m: MinterResponse = app
+        .wrap()
+        .query_wasm_smart(&cw721, &crate::QueryMsg::<Empty>::Minter {})
+        .unwrap();
+    assert_eq!(m.minter, Some(admin().to_string()));
+}
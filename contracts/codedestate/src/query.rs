@@ -4,13 +4,20 @@ use serde::de::DeserializeOwned;
 use serde::Serialize;
 
 use cosmwasm_std::{
-    to_binary, Addr, Binary, BlockInfo, CustomMsg, Deps, Env, Order, StdError, StdResult,
+    to_binary, Addr, Binary, BlockInfo, Coin, CustomMsg, Deps, Env, Order, StdError, StdResult,
+    Uint128,
 };
 
 use cw721::{
-    AllNftInfoResponse, ApprovalResponse, ApprovalsResponse, BidsResponse, ContractInfoResponse,
-    Cw721Query, Expiration, LongTermRental, NftInfoResponse, NumTokensResponse, OperatorResponse,
-    OperatorsResponse, OwnerOfResponse, RentalsResponse, Sell, ShortTermRental, TokensResponse,
+    AllNftInfoResponse, ApprovalResponse, ApprovalsResponse, Auction, AuctionStatusResponse,
+    AvailabilityResponse, BidderBid, BidderBidsResponse, BidsResponse, ContractInfoResponse,
+    CurrentPriceResponse, Cw721Query, DamageDepositsResponse, DateRange, DutchAuction,
+    EscrowSaleResponse, Expiration, ExpiredGroupReservationsResponse, ExpiredReservation,
+    ExpiredReservationsResponse, GroupReservation, HostRecordResponse, LongTermQuote,
+    LongTermRental, NftInfoResponse, NumTokensResponse, OffersResponse, OperatorResponse,
+    OperatorsResponse, OwnerOfResponse, Rating, RatingResponse, Rental, RentalsResponse,
+    ReviewsResponse, RewardsResponse, RoyaltiesInfoResponse, Sell, ShareHolder,
+    ShareHoldersResponse, SharesResponse, ShortTermQuote, ShortTermRental, TokensResponse,
 };
 use cw_storage_plus::Bound;
 // use cw_utils::maybe_addr;
@@ -19,7 +26,10 @@ use crate::msg::{MinterResponse, QueryMsg};
 use crate::state::{Approval, Cw721Contract, TokenInfo};
 
 const DEFAULT_LIMIT: u32 = 4294967295;
-const MAX_LIMIT: u32 = 4294967295;
+/// Page size of the paginated queries over contract-wide indexes
+const DEFAULT_PAGE_LIMIT: u32 = 10;
+const MAX_PAGE_LIMIT: u32 = 100;
+const MAX_AVAILABILITY_DAYS: u64 = 731;
 
 impl<'a, T, C, E, Q> Cw721Query<T> for Cw721Contract<'a, T, C, E, Q>
 where
@@ -68,7 +78,14 @@ where
     fn nft_rentals_info(&self, deps: Deps, token_id: String) -> StdResult<RentalsResponse> {
         let info = self.tokens.load(deps.storage, &token_id)?;
         Ok(cw721::RentalsResponse {
-            rentals: info.rentals,
+            rentals: info
+                .rentals
+                .into_iter()
+                .map(|rental| Rental {
+                    access_instructions: None,
+                    ..rental
+                })
+                .collect(),
         })
     }
 
@@ -77,6 +94,48 @@ where
         Ok(cw721::BidsResponse { bids: info.bids })
     }
 
+    fn nft_auction_info(&self, deps: Deps, token_id: String) -> StdResult<Auction> {
+        let info = self.tokens.load(deps.storage, &token_id)?;
+        Ok(info.auction)
+    }
+
+    fn auction_status(
+        &self,
+        deps: Deps,
+        env: Env,
+        token_id: String,
+    ) -> StdResult<AuctionStatusResponse> {
+        let auction = self.tokens.load(deps.storage, &token_id)?.auction;
+        let minimum_bid = match &auction.highest_bid {
+            Some(bid) => bid.offer + Uint128::from(auction.minimum_increment.max(1)),
+            None => Uint128::from(auction.reserve_price),
+        };
+        Ok(AuctionStatusResponse {
+            time_remaining: auction.end_time.saturating_sub(env.block.time.seconds()),
+            end_time: auction.end_time,
+            highest_bid: auction.highest_bid,
+            minimum_bid,
+        })
+    }
+
+    fn nft_dutch_auction_info(&self, deps: Deps, token_id: String) -> StdResult<DutchAuction> {
+        let info = self.tokens.load(deps.storage, &token_id)?;
+        Ok(info.dutch_auction)
+    }
+
+    fn current_price(
+        &self,
+        deps: Deps,
+        env: Env,
+        token_id: String,
+    ) -> StdResult<CurrentPriceResponse> {
+        let dutch_auction = self.tokens.load(deps.storage, &token_id)?.dutch_auction;
+        Ok(CurrentPriceResponse {
+            price: Uint128::from(dutch_auction.price_at(env.block.time.seconds())),
+            denom: dutch_auction.denom,
+        })
+    }
+
     fn owner_of(
         &self,
         deps: Deps,
@@ -134,7 +193,7 @@ where
         _start_after: Option<String>,
         limit: Option<u32>,
     ) -> StdResult<OperatorsResponse> {
-        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let limit = limit.unwrap_or(DEFAULT_LIMIT) as usize;
         // let start_addr = maybe_addr(deps.api, start_after)?;
         // let start = start_addr.as_ref().map(Bound::exclusive);
         let owner_addr = deps.api.addr_validate(&owner)?;
@@ -219,7 +278,7 @@ where
         start_after: Option<String>,
         limit: Option<u32>,
     ) -> StdResult<TokensResponse> {
-        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let limit = limit.unwrap_or(DEFAULT_LIMIT) as usize;
         let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
 
         let owner_addr = deps.api.addr_validate(&owner)?;
@@ -242,7 +301,7 @@ where
         start_after: Option<String>,
         limit: Option<u32>,
     ) -> StdResult<TokensResponse> {
-        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let limit = limit.unwrap_or(DEFAULT_LIMIT) as usize;
         let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
 
         struct Token {
@@ -341,7 +400,117 @@ where
             QueryMsg::NftInfoSell { token_id } => to_binary(&self.nft_sell_info(deps, token_id)?),
 
             QueryMsg::NftRentals { token_id } => to_binary(&self.nft_rentals_info(deps, token_id)?),
+            QueryMsg::AccessInstructions {
+                token_id,
+                traveler,
+                renting_period,
+            } => to_binary(&self.access_instructions(deps, token_id, traveler, renting_period)?),
             QueryMsg::NftBids { token_id } => to_binary(&self.nft_bids_info(deps, token_id)?),
+            QueryMsg::Offers {
+                token_id,
+                start_after,
+                limit,
+            } => to_binary(&self.offers_info(deps, token_id, start_after, limit)?),
+            QueryMsg::QuoteShortTerm {
+                token_id,
+                renting_period,
+                guests,
+                promo_code,
+            } => to_binary(&self.quote_short_term(
+                deps,
+                env,
+                token_id,
+                renting_period,
+                guests,
+                promo_code,
+            )?),
+            QueryMsg::QuoteLongTerm {
+                token_id,
+                renting_period,
+                guests,
+            } => to_binary(&self.quote_long_term(deps, token_id, renting_period, guests)?),
+            QueryMsg::Availability { token_id, from, to } => {
+                to_binary(&self.availability(deps, token_id, from, to)?)
+            }
+            QueryMsg::NftInfoEscrow { token_id } => {
+                to_binary(&self.escrow_info(deps, env, token_id)?)
+            }
+            QueryMsg::Shares { token_id, holder } => {
+                to_binary(&self.shares_info(deps, token_id, holder)?)
+            }
+            QueryMsg::ShareHolders {
+                token_id,
+                start_after,
+                limit,
+            } => to_binary(&self.share_holders(deps, token_id, start_after, limit)?),
+            QueryMsg::Rewards { holder, token_id } => {
+                to_binary(&self.rewards_info(deps, holder, token_id)?)
+            }
+            QueryMsg::Reviews {
+                token_id,
+                start_after,
+                limit,
+            } => to_binary(&self.reviews_info(deps, token_id, start_after, limit)?),
+            QueryMsg::ReviewsOf {
+                subject,
+                start_after,
+                limit,
+            } => to_binary(&self.reviews_of(deps, subject, start_after, limit)?),
+            QueryMsg::TokenRating { token_id } => to_binary(&rating_response(
+                self.token_ratings.may_load(deps.storage, &token_id)?,
+            )),
+            QueryMsg::HostRating { host } => to_binary(&rating_response(
+                self.host_ratings.may_load(deps.storage, &host)?,
+            )),
+            QueryMsg::TravelerRating { traveler } => to_binary(&rating_response(
+                self.traveler_ratings.may_load(deps.storage, &traveler)?,
+            )),
+            QueryMsg::HostRecord { host } => to_binary(&self.host_record(deps, host)?),
+            QueryMsg::TravelerProfile { traveler } => to_binary(
+                &self
+                    .traveler_profiles
+                    .may_load(deps.storage, &traveler)?
+                    .unwrap_or_default(),
+            ),
+            QueryMsg::HostPolicy {} => {
+                to_binary(&self.host_policy.may_load(deps.storage)?.unwrap_or_default())
+            }
+            QueryMsg::PromoCode { code } => to_binary(&self.promo_codes.load(deps.storage, &code)?),
+            QueryMsg::ExpiredReservations { start_after, limit } => {
+                to_binary(&self.expired_reservations(deps, env, start_after, limit)?)
+            }
+            QueryMsg::GroupReservation { group_id } => {
+                to_binary(&self.group_reservation(deps, group_id)?)
+            }
+            QueryMsg::ExpiredGroupReservations { start_after, limit } => {
+                to_binary(&self.expired_group_reservations(deps, env, start_after, limit)?)
+            }
+            QueryMsg::DamageDeposits {
+                token_id,
+                start_after,
+                limit,
+            } => to_binary(&self.damage_deposits_info(deps, token_id, start_after, limit)?),
+            QueryMsg::BidsByBidder {
+                bidder,
+                start_after,
+                limit,
+            } => to_binary(&self.bids_by_bidder(deps, bidder, start_after, limit)?),
+            QueryMsg::RoyaltyInfo {
+                token_id,
+                sale_price,
+            } => to_binary(&self.royalty_info(deps, token_id, sale_price)?),
+            QueryMsg::NftInfoAuction { token_id } => {
+                to_binary(&self.nft_auction_info(deps, token_id)?)
+            }
+            QueryMsg::AuctionStatus { token_id } => {
+                to_binary(&self.auction_status(deps, env, token_id)?)
+            }
+            QueryMsg::NftInfoDutchAuction { token_id } => {
+                to_binary(&self.nft_dutch_auction_info(deps, token_id)?)
+            }
+            QueryMsg::CurrentPrice { token_id } => {
+                to_binary(&self.current_price(deps, env, token_id)?)
+            }
 
             QueryMsg::OwnerOf {
                 token_id,
@@ -425,6 +594,444 @@ where
         Ok(MinterResponse { minter })
     }
 
+    pub fn bids_by_bidder(
+        &self,
+        deps: Deps,
+        bidder: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<BidderBidsResponse> {
+        let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+        let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+
+        let token_ids = self
+            .bids_by_bidder
+            .prefix(&bidder)
+            .keys(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        let mut bids = vec![];
+        for token_id in token_ids {
+            let token = self.tokens.load(deps.storage, &token_id)?;
+            if let Some(bid) = token.bids.into_iter().find(|bid| bid.address == bidder) {
+                bids.push(BidderBid {
+                    token_id,
+                    offer: bid.offer,
+                    denom: bid.denom,
+                    islisted: token.sell.islisted,
+                    expires: bid.expires,
+                    counter_offer: bid.counter_offer,
+                });
+            }
+        }
+
+        Ok(BidderBidsResponse { bids })
+    }
+
+    pub fn offers_info(
+        &self,
+        deps: Deps,
+        token_id: Option<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<OffersResponse> {
+        let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+
+        let offers = self
+            .offers
+            .idx
+            .token
+            .prefix(token_id.unwrap_or_default())
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, offer)| offer))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        Ok(OffersResponse { offers })
+    }
+
+    pub fn reviews_info(
+        &self,
+        deps: Deps,
+        token_id: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<ReviewsResponse> {
+        let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+
+        let reviews = self
+            .reviews
+            .idx
+            .token
+            .prefix(token_id)
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, review)| review))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        Ok(ReviewsResponse { reviews })
+    }
+
+    pub fn reviews_of(
+        &self,
+        deps: Deps,
+        subject: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<ReviewsResponse> {
+        let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+
+        let reviews = self
+            .reviews
+            .idx
+            .subject
+            .prefix(subject)
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, review)| review))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        Ok(ReviewsResponse { reviews })
+    }
+
+    pub fn host_record(&self, deps: Deps, host: String) -> StdResult<HostRecordResponse> {
+        let policy = self.host_policy.may_load(deps.storage)?.unwrap_or_default();
+        let record = self
+            .host_records
+            .may_load(deps.storage, &host)?
+            .unwrap_or_default();
+        Ok(HostRecordResponse {
+            bond: self.host_bonds.may_load(deps.storage, &host)?,
+            auto_approve_eligible: policy.can_auto_approve(&record),
+            listing_eligible: policy.can_list(&record),
+            record,
+        })
+    }
+
+    /// Pages through tokens after `start_after`, always returning every expired reservation
+    /// of the last token included
+    pub fn expired_reservations(
+        &self,
+        deps: Deps,
+        env: Env,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<ExpiredReservationsResponse> {
+        let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+        let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+        let current_time = env.block.time.seconds();
+
+        let mut reservations = vec![];
+        let mut last_token_id = None;
+        for item in self
+            .tokens
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+        {
+            let (token_id, token) = item?;
+            last_token_id = Some(token_id.clone());
+            for rental in token.rentals {
+                match rental.respond_by {
+                    Some(respond_by) if rental.is_pending() && respond_by <= current_time => {
+                        reservations.push(ExpiredReservation {
+                            token_id: token_id.clone(),
+                            address: rental
+                                .address
+                                .map(|address| address.to_string())
+                                .unwrap_or_default(),
+                            rental_type: rental.rental_type,
+                            renting_period: rental.renting_period,
+                            respond_by,
+                        })
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(ExpiredReservationsResponse {
+            reservations,
+            last_token_id,
+        })
+    }
+
+    pub fn group_reservation(&self, deps: Deps, group_id: u64) -> StdResult<GroupReservation> {
+        self.group_reservations.load(deps.storage, group_id)
+    }
+
+    pub fn expired_group_reservations(
+        &self,
+        deps: Deps,
+        env: Env,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<ExpiredGroupReservationsResponse> {
+        let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+        let current_time = env.block.time.seconds();
+
+        let mut groups = vec![];
+        let mut last_group_id = None;
+        for item in self
+            .group_reservations
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+        {
+            let (group_id, group) = item?;
+            last_group_id = Some(group_id);
+            // funded groups are booked and removed, so what is left is still funding
+            if group.deadline <= current_time {
+                groups.push(group);
+            }
+        }
+
+        Ok(ExpiredGroupReservationsResponse {
+            groups,
+            last_group_id,
+        })
+    }
+
+    pub fn damage_deposits_info(
+        &self,
+        deps: Deps,
+        token_id: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<DamageDepositsResponse> {
+        let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+
+        let deposits = self
+            .damage_deposits
+            .prefix(&token_id)
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, deposit)| deposit))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        Ok(DamageDepositsResponse { deposits })
+    }
+
+    pub fn quote_short_term(
+        &self,
+        deps: Deps,
+        env: Env,
+        token_id: String,
+        renting_period: Vec<String>,
+        guests: usize,
+        promo_code: Option<String>,
+    ) -> StdResult<ShortTermQuote> {
+        let token = self.tokens.load(deps.storage, &token_id)?;
+        let (check_in, check_out) = parse_renting_period(&renting_period)?;
+        let promo = match promo_code {
+            Some(code) => Some(
+                self.redeemable_promo_code(deps.storage, &env.block, &code, &token_id, &token)
+                    .map_err(|e| StdError::generic_err(e.to_string()))?,
+            ),
+            None => None,
+        };
+        self.quote_shortterm(
+            deps.storage,
+            &token,
+            check_in,
+            check_out,
+            env.block.time.seconds(),
+            guests,
+            promo.as_ref().map(|promo| &promo.discount),
+        )
+    }
+
+    pub fn access_instructions(
+        &self,
+        deps: Deps,
+        token_id: String,
+        traveler: String,
+        renting_period: Vec<String>,
+    ) -> StdResult<Option<Binary>> {
+        let token = self.tokens.load(deps.storage, &token_id)?;
+        let (check_in, check_out) = parse_renting_period(&renting_period)?;
+        Ok(token
+            .rentals
+            .into_iter()
+            .find(|rental| {
+                !rental.rental_type
+                    && rental
+                        .address
+                        .as_ref()
+                        .is_some_and(|address| address == &traveler)
+                    && rental.renting_period == [check_in, check_out]
+            })
+            .and_then(|rental| rental.access_instructions))
+    }
+
+    pub fn quote_long_term(
+        &self,
+        deps: Deps,
+        token_id: String,
+        renting_period: Vec<String>,
+        guests: usize,
+    ) -> StdResult<LongTermQuote> {
+        let token = self.tokens.load(deps.storage, &token_id)?;
+        let (check_in, check_out) = parse_renting_period(&renting_period)?;
+        self.quote_longterm(deps.storage, &token, check_in, check_out, guests)
+    }
+
+    /// Splits `from..to` into whole days and merges them into free and blocked ranges
+    pub fn availability(
+        &self,
+        deps: Deps,
+        token_id: String,
+        from: u64,
+        to: u64,
+    ) -> StdResult<AvailabilityResponse> {
+        if from >= to || (to - from) / 86400 > MAX_AVAILABILITY_DAYS {
+            return Err(StdError::generic_err("Invalid availability range"));
+        }
+        let token = self.tokens.load(deps.storage, &token_id)?;
+
+        let mut free: Vec<DateRange> = vec![];
+        let mut blocked: Vec<DateRange> = vec![];
+        let mut day = from;
+        while day < to {
+            let end = (day + 86400).min(to);
+            let reserved = token.rentals.iter().any(|rental| {
+                !rental.cancelled
+                    && rental.renting_period[0] < end
+                    && day < rental.renting_period[1]
+            });
+            let ranges = if reserved || !token.shortterm_rental.availability.is_available(day, end)
+            {
+                &mut blocked
+            } else {
+                &mut free
+            };
+            match ranges.last_mut() {
+                Some(range) if range.end == day => range.end = end,
+                _ => ranges.push(DateRange { start: day, end }),
+            }
+            day = end;
+        }
+
+        Ok(AvailabilityResponse { free, blocked })
+    }
+
+    pub fn escrow_info(
+        &self,
+        deps: Deps,
+        env: Env,
+        token_id: String,
+    ) -> StdResult<EscrowSaleResponse> {
+        let token = self.tokens.load(deps.storage, &token_id)?;
+        Ok(match token.escrow {
+            Some(escrow) => EscrowSaleResponse {
+                inspection_end: escrow.inspection_end(),
+                closing_deadline: escrow.closing_deadline(),
+                balance_due: escrow.balance_due(),
+                cancellation_penalty: escrow.penalty_at(env.block.time.seconds()),
+                escrow: Some(escrow),
+            },
+            None => EscrowSaleResponse {
+                escrow: None,
+                inspection_end: None,
+                closing_deadline: None,
+                balance_due: Uint128::new(0),
+                cancellation_penalty: Uint128::new(0),
+            },
+        })
+    }
+
+    pub fn shares_info(
+        &self,
+        deps: Deps,
+        token_id: String,
+        holder: String,
+    ) -> StdResult<SharesResponse> {
+        let token = self.tokens.load(deps.storage, &token_id)?;
+        let shares = self
+            .shares
+            .may_load(deps.storage, (&token_id, &holder))?
+            .unwrap_or_default();
+        Ok(SharesResponse {
+            shares,
+            total_shares: token.total_shares.unwrap_or_default(),
+        })
+    }
+
+    pub fn share_holders(
+        &self,
+        deps: Deps,
+        token_id: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<ShareHoldersResponse> {
+        let token = self.tokens.load(deps.storage, &token_id)?;
+        let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+        let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+
+        let holders = self
+            .shares
+            .prefix(&token_id)
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(address, shares)| ShareHolder { address, shares }))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        Ok(ShareHoldersResponse {
+            holders,
+            total_shares: token.total_shares.unwrap_or_default(),
+        })
+    }
+
+    pub fn rewards_info(
+        &self,
+        deps: Deps,
+        holder: String,
+        token_id: String,
+    ) -> StdResult<RewardsResponse> {
+        let mut rewards = self
+            .rewards
+            .prefix(&holder)
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(denom, amount)| Coin { denom, amount }))
+            .collect::<StdResult<Vec<_>>>()?;
+        for (denom, amount) in self.unsettled_rewards(deps.storage, &token_id, &holder)? {
+            match rewards.iter_mut().find(|reward| reward.denom == denom) {
+                Some(reward) => reward.amount += amount,
+                None => rewards.push(Coin { denom, amount }),
+            }
+        }
+        Ok(RewardsResponse { rewards })
+    }
+
+    pub fn royalty_info(
+        &self,
+        deps: Deps,
+        token_id: String,
+        sale_price: Uint128,
+    ) -> StdResult<RoyaltiesInfoResponse> {
+        let token = self.tokens.load(deps.storage, &token_id)?;
+        let royalty = match token.royalty {
+            Some(royalty) => Some(royalty),
+            None => self.default_royalty.may_load(deps.storage)?,
+        };
+        Ok(match royalty {
+            Some(royalty) => RoyaltiesInfoResponse {
+                address: royalty.recipient,
+                royalty_amount: Uint128::new(
+                    (sale_price.u128() * u128::from(royalty.share)) / 10000,
+                ),
+            },
+            None => RoyaltiesInfoResponse {
+                address: "".to_string(),
+                royalty_amount: Uint128::new(0),
+            },
+        })
+    }
+
     pub fn ownership(deps: Deps) -> StdResult<cw_ownable::Ownership<Addr>> {
         cw_ownable::get_ownership(deps.storage)
     }
@@ -455,3 +1062,25 @@ fn humanize_approval(approval: &Approval) -> cw721::Approval {
         expires: approval.expires,
     }
 }
+
+fn rating_response(rating: Option<Rating>) -> RatingResponse {
+    let rating = rating.unwrap_or_default();
+    RatingResponse {
+        count: rating.count,
+        average: rating.average(),
+    }
+}
+
+fn parse_renting_period(renting_period: &[String]) -> StdResult<(u64, u64)> {
+    let parse = |index: usize| -> StdResult<u64> {
+        renting_period
+            .get(index)
+            .and_then(|timestamp| timestamp.parse::<u64>().ok())
+            .ok_or_else(|| StdError::generic_err("Invalid renting period"))
+    };
+    let (check_in, check_out) = (parse(0)?, parse(1)?);
+    if check_in >= check_out {
+        return Err(StdError::generic_err("Invalid renting period"));
+    }
+    Ok((check_in, check_out))
+}
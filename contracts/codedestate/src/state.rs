@@ -4,10 +4,14 @@ use serde::{Deserialize, Serialize};
 use std::marker::PhantomData;
 use std::vec;
 
-use cosmwasm_std::{Addr, BlockInfo, CustomMsg, StdResult, Storage, Uint128};
+use cosmwasm_std::{
+    Addr, BlockInfo, Coin, CustomMsg, Decimal256, Empty, StdResult, Storage, Uint128,
+};
 
 use cw721::{
-    Bid, ContractInfoResponse, Cw721, Expiration, LongTermRental, Rental, Sell, ShortTermRental,
+    Auction, Bid, CompletedStay, ContractInfoResponse, Cw721, DamageDeposit, DutchAuction,
+    EscrowSale, Expiration, GroupReservation, HostPolicy, HostRecord, LongTermRental, Offer,
+    PromoCode, Rating, Rental, Review, Royalty, Sell, ShortTermRental, TravelerProfile,
 };
 use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
 
@@ -20,10 +24,53 @@ where
     pub contract_info: Item<'a, ContractInfoResponse>,
     pub token_count: Item<'a, u64>,
     pub fee: Item<'a, u64>,
+    /// Royalty for tokens minted without one
+    pub default_royalty: Item<'a, Royalty>,
+    /// Platform fees, keyed by native denom or CW20 contract address
     pub balances: Map<'a, &'a str, Uint128>,
+    /// CW20 contracts the owner allowed to pay through `Receive`, with whether they still are.
+    /// Payouts in these denoms are CW20 transfers.
+    pub cw20_tokens: Map<'a, &'a str, bool>,
     /// Stored as (granter, operator) giving operator full control over granter's account
     pub operators: Map<'a, (&'a String, &'a String), Expiration>,
     pub tokens: IndexedMap<'a, &'a str, TokenInfo<T>, TokenIndexes<'a, T>>,
+    pub offer_count: Item<'a, u64>,
+    /// Escrowed offers on tokens that need not be listed for sale
+    pub offers: IndexedMap<'a, u64, Offer, OfferIndexes<'a>>,
+    /// Tokens with an open bid, keyed by (bidder, token_id)
+    pub bids_by_bidder: Map<'a, (&'a str, &'a str), bool>,
+    /// Share units of fractionalized tokens, keyed by (token_id, holder)
+    pub shares: Map<'a, (&'a str, &'a str), Uint128>,
+    /// Rental income claimable by share holders, keyed by (holder, denom)
+    pub rewards: Map<'a, (&'a str, &'a str), Uint128>,
+    /// Rental income per share unit a fractionalized token earned so far, keyed by (token_id, denom)
+    pub reward_per_share: Map<'a, (&'a str, &'a str), Decimal256>,
+    /// `reward_per_share` up to which a holder was credited, keyed by (token_id, holder, denom)
+    pub reward_settled: Map<'a, (&'a str, &'a str, &'a str), Decimal256>,
+    /// Damage deposits of short-term stays, keyed by (token_id, check-in)
+    pub damage_deposits: Map<'a, (&'a str, u64), DamageDeposit>,
+    pub group_count: Item<'a, u64>,
+    /// Short-term reservations still being funded by their payers
+    pub group_reservations: Map<'a, u64, GroupReservation>,
+    pub host_policy: Item<'a, HostPolicy>,
+    /// Bonds hosts post to cover penalties for cancelling approved stays, keyed by host
+    pub host_bonds: Map<'a, &'a str, Coin>,
+    pub host_records: Map<'a, &'a str, HostRecord>,
+    /// Finished stays open for review, keyed by (token_id, check-in)
+    pub stays: Map<'a, (&'a str, u64), CompletedStay>,
+    pub review_count: Item<'a, u64>,
+    pub reviews: IndexedMap<'a, u64, Review, ReviewIndexes<'a>>,
+    /// Ratings travelers gave stays on a token
+    pub token_ratings: Map<'a, &'a str, Rating>,
+    /// Ratings travelers gave a host
+    pub host_ratings: Map<'a, &'a str, Rating>,
+    /// Ratings hosts gave a traveler
+    pub traveler_ratings: Map<'a, &'a str, Rating>,
+    pub promo_codes: Map<'a, &'a str, PromoCode>,
+    /// KYC attestations and finished stays of travelers, for instant-book rules
+    pub traveler_profiles: Map<'a, &'a str, TravelerProfile>,
+    /// Hosts a traveler finished a counted stay with, keyed by (traveler, host)
+    pub stayed_with: Map<'a, (&'a str, &'a str), Empty>,
 
     pub(crate) _custom_response: PhantomData<C>,
     pub(crate) _custom_query: PhantomData<Q>,
@@ -40,6 +87,9 @@ where
 {
 }
 
+const OFFERS_KEY: &str = "offers";
+const REVIEWS_KEY: &str = "reviews";
+
 impl<T, C, E, Q> Default for Cw721Contract<'static, T, C, E, Q>
 where
     T: Serialize + DeserializeOwned + Clone,
@@ -77,13 +127,44 @@ where
         let indexes = TokenIndexes {
             owner: MultiIndex::new(token_owner_idx, tokens_key, tokens_owner_key),
         };
+        let offer_indexes = OfferIndexes {
+            token: MultiIndex::new(offer_token_idx, OFFERS_KEY, "offers__token"),
+        };
+        let review_indexes = ReviewIndexes {
+            token: MultiIndex::new(review_token_idx, REVIEWS_KEY, "reviews__token"),
+            subject: MultiIndex::new(review_subject_idx, REVIEWS_KEY, "reviews__subject"),
+        };
         Self {
             contract_info: Item::new(contract_key),
             token_count: Item::new(token_count_key),
             fee: Item::new(fee_key),
+            default_royalty: Item::new("default_royalty"),
             operators: Map::new(operator_key),
             balances: Map::new(balance_key),
+            cw20_tokens: Map::new("cw20_tokens"),
             tokens: IndexedMap::new(tokens_key, indexes),
+            offer_count: Item::new("offer_count"),
+            offers: IndexedMap::new(OFFERS_KEY, offer_indexes),
+            bids_by_bidder: Map::new("bids_by_bidder"),
+            shares: Map::new("shares"),
+            rewards: Map::new("rewards"),
+            reward_per_share: Map::new("reward_per_share"),
+            reward_settled: Map::new("reward_settled"),
+            damage_deposits: Map::new("damage_deposits"),
+            group_count: Item::new("group_count"),
+            group_reservations: Map::new("group_reservations"),
+            host_policy: Item::new("host_policy"),
+            host_bonds: Map::new("host_bonds"),
+            host_records: Map::new("host_records"),
+            stays: Map::new("stays"),
+            review_count: Item::new("review_count"),
+            reviews: IndexedMap::new(REVIEWS_KEY, review_indexes),
+            token_ratings: Map::new("token_ratings"),
+            host_ratings: Map::new("host_ratings"),
+            traveler_ratings: Map::new("traveler_ratings"),
+            promo_codes: Map::new("promo_codes"),
+            traveler_profiles: Map::new("traveler_profiles"),
+            stayed_with: Map::new("stayed_with"),
             _custom_response: PhantomData,
             _custom_execute: PhantomData,
             _custom_query: PhantomData,
@@ -176,6 +257,16 @@ pub struct TokenInfo<T> {
     pub rentals: Vec<Rental>,
     pub bids: Vec<Bid>,
     pub sell: Sell,
+    #[serde(default)]
+    pub auction: Auction,
+    #[serde(default)]
+    pub dutch_auction: DutchAuction,
+    /// Staged sale in progress
+    pub escrow: Option<EscrowSale>,
+    /// Paid on every sale of this token, overrides the collection royalty
+    pub royalty: Option<Royalty>,
+    /// Share units issued against the token while it is fractionalized
+    pub total_shares: Option<Uint128>,
 
     pub token_uri: Option<String>,
 
@@ -196,6 +287,42 @@ impl Approval {
     }
 }
 
+pub struct OfferIndexes<'a> {
+    pub token: MultiIndex<'a, String, Offer, u64>,
+}
+
+impl<'a> IndexList<Offer> for OfferIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Offer>> + '_> {
+        let v: Vec<&dyn Index<Offer>> = vec![&self.token];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Collection-wide offers are indexed under the empty token id
+pub fn offer_token_idx(_pk: &[u8], d: &Offer) -> String {
+    d.token_id.clone().unwrap_or_default()
+}
+
+pub struct ReviewIndexes<'a> {
+    pub token: MultiIndex<'a, String, Review, u64>,
+    pub subject: MultiIndex<'a, String, Review, u64>,
+}
+
+impl<'a> IndexList<Review> for ReviewIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Review>> + '_> {
+        let v: Vec<&dyn Index<Review>> = vec![&self.token, &self.subject];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn review_token_idx(_pk: &[u8], d: &Review) -> String {
+    d.token_id.clone()
+}
+
+pub fn review_subject_idx(_pk: &[u8], d: &Review) -> String {
+    d.subject.clone()
+}
+
 pub struct TokenIndexes<'a, T>
 where
     T: Serialize + DeserializeOwned + Clone,
@@ -214,5 +341,5 @@ where
 }
 
 pub fn token_owner_idx<T>(_pk: &[u8], d: &TokenInfo<T>) -> String {
-    return d.owner.address.clone();
+    d.owner.address.clone()
 }
@@ -6,11 +6,22 @@ mod traits;
 pub use cw_utils::Expiration;
 
 pub use crate::msg::Cw721ExecuteMsg;
+pub use crate::query::{civil_date, fees_total, refund_percentage, refund_schedule, weekday};
 pub use crate::query::{
-    AllNftInfoResponse, Approval, ApprovalResponse, ApprovalsResponse, Bid, BidsResponse,
-    CancellationItem, ContractInfoResponse, Cw721QueryMsg, FeeValueResponse, LongTermRental,
-    NftInfoResponse, NumTokensResponse, OperatorResponse, OperatorsResponse, OwnerOfResponse,
-    Rental, RentalsResponse, Sell, ShortTermRental, TokensResponse,
+    AllNftInfoResponse, Approval, ApprovalResponse, ApprovalsResponse, Auction,
+    AuctionStatusResponse, AvailabilityCalendar, AvailabilityResponse, Bid, BidderBid,
+    BidderBidsResponse, BidsResponse, BookingWindow, CancellationItem, ChargedFee, CompletedStay,
+    ContractInfoResponse, CurrentPriceResponse, Cw721QueryMsg, DamageDeposit,
+    DamageDepositsResponse, DateRange, DutchAuction, EscrowSale, EscrowSaleResponse,
+    ExpiredGroupReservationsResponse, ExpiredReservation, ExpiredReservationsResponse, FeeCharge,
+    FeeKind, FeeLine, FeeRecipient, FeeRefund, FeeValueResponse, GroupReservation, HostPolicy,
+    HostRecord, HostRecordResponse, InstantBookRules, LongTermQuote, LongTermRental,
+    NftInfoResponse, NightlyPrice, NumTokensResponse, Offer, OffersResponse, OperatorResponse,
+    OperatorsResponse, OwnerOfResponse, PayerShare, PriceAdjustment, PriceCondition, PriceRule,
+    PromoCode, PromoDiscount, Rating, RatingResponse, RecurringClosure, RefundStep, Rental,
+    RentalsResponse, Review, ReviewsResponse, RewardsResponse, RoyaltiesInfoResponse, Royalty,
+    Sell, ShareHolder, ShareHoldersResponse, SharesResponse, ShortTermQuote, ShortTermRental,
+    TokensResponse, Trait, TravelerProfile, REVIEW_WINDOW,
 };
 pub use crate::receiver::Cw721ReceiveMsg;
 pub use crate::traits::{Cw721, Cw721Execute, Cw721Query};
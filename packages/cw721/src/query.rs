@@ -1,6 +1,7 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Binary, BlockInfo, Coin, Decimal, Uint128};
 use cw_utils::Expiration;
+use std::cmp::Reverse;
 #[cw_serde]
 pub enum Cw721QueryMsg {
     /// Return the owner of the given token, error if token does not exist
@@ -98,6 +99,17 @@ pub struct Bid {
     /// price offer
     pub offer: Uint128,
     pub denom: String,
+    /// Once expired anyone can send the offer back to the bidder. Never expires if unset
+    pub expires: Option<Expiration>,
+    /// Price asked back by the owner, the bidder buys at it by topping up the difference
+    pub counter_offer: Option<Uint128>,
+}
+
+impl Bid {
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        self.expires
+            .is_some_and(|expires| expires.is_expired(block))
+    }
 }
 
 // #[cw_serde]
@@ -146,6 +158,93 @@ pub struct Rental {
     pub approved: bool,
     pub approved_date: Option<String>,
     pub guests: usize,
+    /// Shares of a group reservation, refunds are split among the payers in proportion
+    #[serde(default)]
+    pub payers: Vec<PayerShare>,
+    /// Platform fee paid on top of a short-term rent, held until the stay can no longer be
+    /// refunded. Unset once credited to the platform, and for reservations made before
+    /// fees were held.
+    #[serde(default)]
+    pub platform_fee: Option<Uint128>,
+    /// Host approval deadline, after which anyone can expire the reservation
+    #[serde(default)]
+    pub respond_by: Option<u64>,
+    /// Length-of-stay and promo code discounts taken off the rent
+    #[serde(default)]
+    pub discount: Uint128,
+    #[serde(default)]
+    pub promo_code: Option<String>,
+    /// Terms of `promo_code` as redeemed, a moved reservation is repriced with them
+    #[serde(default)]
+    pub promo_discount: Option<PromoDiscount>,
+    /// Fee lines collected on top of the rent, kept parts are routed at finalize
+    #[serde(default)]
+    pub fees: Vec<ChargedFee>,
+    /// Door codes and directions encrypted to the traveler's public key by the host. They
+    /// are left out of `NftRentals`, but contract state is public and only the encryption
+    /// keeps them secret.
+    #[serde(default)]
+    pub access_instructions: Option<Binary>,
+}
+
+impl Rental {
+    /// Still waiting for the host, long-term rentals are approved with an `approved_date`
+    pub fn is_pending(&self) -> bool {
+        !self.cancelled && !self.approved && (!self.rental_type || self.approved_date.is_none())
+    }
+}
+
+/// Pending reservation past its host approval deadline
+#[cw_serde]
+pub struct ExpiredReservation {
+    pub token_id: String,
+    pub address: String,
+    pub rental_type: bool,
+    pub renting_period: Vec<u64>,
+    pub respond_by: u64,
+}
+
+#[cw_serde]
+pub struct ExpiredReservationsResponse {
+    pub reservations: Vec<ExpiredReservation>,
+    /// Last token looked at, `start_after` of the next page
+    pub last_token_id: Option<String>,
+}
+
+/// Short-term reservation split among several payers, booked once every share is paid
+#[cw_serde]
+pub struct GroupReservation {
+    pub id: u64,
+    pub token_id: String,
+    /// Traveler the booking is made for
+    pub organizer: Addr,
+    pub renting_period: Vec<String>,
+    pub guests: usize,
+    pub denom: String,
+    pub shares: Vec<PayerShare>,
+    /// Every paid share is refunded if the reservation is not fully funded by then
+    pub deadline: u64,
+}
+
+impl GroupReservation {
+    pub fn is_funded(&self) -> bool {
+        self.shares.iter().all(|share| share.paid)
+    }
+}
+
+#[cw_serde]
+pub struct ExpiredGroupReservationsResponse {
+    pub groups: Vec<GroupReservation>,
+    /// Last group looked at, `start_after` of the next page
+    pub last_group_id: Option<u64>,
+}
+
+#[cw_serde]
+pub struct PayerShare {
+    pub payer: String,
+    pub amount: Uint128,
+    #[serde(default)]
+    pub paid: bool,
 }
 
 #[cw_serde]
@@ -159,6 +258,81 @@ pub struct LongTermRental {
     pub withdrawn_amount: Uint128,
     pub cancellation: Vec<CancellationItem>,
     pub minimum_stay: u64,
+    /// Unlimited if unset
+    #[serde(default)]
+    pub max_guests: Option<usize>,
+    /// Guests covered by `price_per_month`
+    #[serde(default)]
+    pub included_guests: usize,
+    /// Added to the monthly price for every guest above `included_guests`
+    #[serde(default)]
+    pub extra_guest_fee: u128,
+    /// Seconds the host has to approve a reservation, unlimited if unset
+    #[serde(default)]
+    pub response_window: Option<u64>,
+    /// Charged once on top of the first month's rent
+    #[serde(default)]
+    pub fee_lines: Vec<FeeLine>,
+    /// Limits `auto_approve` to reservations meeting them
+    #[serde(default)]
+    pub instant_book: Option<InstantBookRules>,
+    #[serde(default)]
+    pub booking_window: BookingWindow,
+}
+
+impl LongTermRental {
+    /// Monthly price including the extra-guest fee
+    pub fn monthly_price(&self, guests: usize) -> u128 {
+        self.price_per_month + extra_guest_fee(guests, self.included_guests, self.extra_guest_fee)
+    }
+}
+
+fn extra_guest_fee(guests: usize, included_guests: usize, fee: u128) -> u128 {
+    guests.saturating_sub(included_guests) as u128 * fee
+}
+
+/// Conditions a reservation has to meet to be approved instantly on a listing with
+/// `auto_approve`, other reservations wait for the host
+#[cw_serde]
+#[derive(Default)]
+pub struct InstantBookRules {
+    /// Traveler needs a KYC attestation from the contract owner
+    pub require_kyc: bool,
+    /// Hosts the traveler needs to have finished a stay with
+    pub min_completed_stays: u32,
+    pub min_nights: Option<u64>,
+    pub max_nights: Option<u64>,
+    pub max_guests: Option<usize>,
+}
+
+impl InstantBookRules {
+    pub fn allows(&self, profile: &TravelerProfile, nights: u64, guests: usize) -> bool {
+        (profile.kyc_attested_at.is_some() || !self.require_kyc)
+            && profile.completed_stays >= self.min_completed_stays
+            && self.min_nights.map_or(true, |min| nights >= min)
+            && self.max_nights.map_or(true, |max| nights <= max)
+            && self.max_guests.map_or(true, |max| guests <= max)
+    }
+}
+
+/// How far ahead a stay can be booked and how much turnover time it leaves around others
+#[cw_serde]
+#[derive(Default)]
+pub struct BookingWindow {
+    /// Seconds the check-in has to be away at booking time
+    pub advance_notice: u64,
+    /// Seconds ahead the check-in can be at most, unlimited if unset
+    pub booking_horizon: Option<u64>,
+    /// Days to keep free between a stay and the ones before and after it
+    pub buffer_days: u64,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct TravelerProfile {
+    pub kyc_attested_at: Option<u64>,
+    /// Distinct hosts the traveler finished a paid stay with
+    pub completed_stays: u32,
 }
 
 #[cw_serde]
@@ -167,6 +341,58 @@ pub struct CancellationItem {
     pub percentage: u64,
 }
 
+/// Cancelling an approved stay up to `cancel_by` refunds `percentage` of the rent
+#[cw_serde]
+pub struct RefundStep {
+    pub cancel_by: u64,
+    pub percentage: u64,
+    /// Rent and fee lines refunded, a damage deposit is always returned in full
+    pub refund: Uint128,
+}
+
+/// Refund steps of a stay checking in at `check_in`, latest deadline last. A cancellation
+/// more than `deadline` whole days before check-in refunds the highest percentage it
+/// qualifies for, an empty policy refunds everything until check-in.
+pub fn refund_schedule(
+    cancellation: &[CancellationItem],
+    check_in: u64,
+    rent: Uint128,
+    fees: &[ChargedFee],
+) -> Vec<RefundStep> {
+    let step = |cancel_by: u64, percentage: u64| RefundStep {
+        cancel_by,
+        percentage,
+        refund: rent.multiply_ratio(percentage, 100u64)
+            + fees
+                .iter()
+                .map(|fee| fee.refund_amount(percentage))
+                .sum::<Uint128>(),
+    };
+    if cancellation.is_empty() {
+        return vec![step(check_in.saturating_sub(1), 100)];
+    }
+    let mut items = cancellation.to_vec();
+    items.sort_by_key(|item| Reverse(item.percentage));
+    let mut steps: Vec<RefundStep> = vec![];
+    for item in items {
+        let cancel_by =
+            check_in.saturating_sub(item.deadline.saturating_add(1).saturating_mul(86400));
+        // a lower percentage only counts if it extends the window
+        if steps.last().map_or(true, |last| cancel_by > last.cancel_by) {
+            steps.push(step(cancel_by, item.percentage));
+        }
+    }
+    steps
+}
+
+/// Percentage of the rent refunded when cancelling at `now`
+pub fn refund_percentage(cancellation: &[CancellationItem], check_in: u64, now: u64) -> u64 {
+    refund_schedule(cancellation, check_in, Uint128::new(0), &[])
+        .iter()
+        .find(|step| now <= step.cancel_by)
+        .map_or(0, |step| step.percentage)
+}
+
 #[cw_serde]
 pub struct ShortTermRental {
     pub islisted: Option<bool>,
@@ -178,6 +404,389 @@ pub struct ShortTermRental {
     pub withdrawn_amount: Uint128,
     pub cancellation: Vec<CancellationItem>,
     pub minimum_stay: u64,
+    #[serde(default)]
+    pub availability: AvailabilityCalendar,
+    /// Nightly price rules, nights no rule matches cost `price_per_day`
+    #[serde(default)]
+    pub price_rules: Vec<PriceRule>,
+    /// Unlimited if unset
+    #[serde(default)]
+    pub max_guests: Option<usize>,
+    /// Guests covered by the nightly price
+    #[serde(default)]
+    pub included_guests: usize,
+    /// Added to the nightly price for every guest above `included_guests`
+    #[serde(default)]
+    pub extra_guest_fee: u128,
+    /// Refundable security deposit collected with every reservation
+    #[serde(default)]
+    pub damage_deposit: u128,
+    /// Seconds after check-out in which the host can claim against the damage deposit
+    #[serde(default)]
+    pub claim_window: u64,
+    /// Seconds the host has to approve a reservation, unlimited if unset
+    #[serde(default)]
+    pub response_window: Option<u64>,
+    /// Discount in basis points for stays of 7 nights or more
+    #[serde(default)]
+    pub weekly_discount: u64,
+    /// Discount in basis points for stays of 28 nights or more, replaces the weekly one
+    #[serde(default)]
+    pub monthly_discount: u64,
+    /// Charged on top of the rent
+    #[serde(default)]
+    pub fee_lines: Vec<FeeLine>,
+    /// Whether platform promo codes can be redeemed, their discount comes off the rent
+    #[serde(default)]
+    pub accepts_platform_promos: bool,
+    /// Limits `auto_approve` to reservations meeting them
+    #[serde(default)]
+    pub instant_book: Option<InstantBookRules>,
+    #[serde(default)]
+    pub booking_window: BookingWindow,
+}
+
+impl ShortTermRental {
+    /// Length-of-stay discount in basis points
+    pub fn stay_discount(&self, nights: usize) -> u64 {
+        if nights >= 28 && self.monthly_discount > 0 {
+            self.monthly_discount
+        } else if nights >= 7 {
+            self.weekly_discount
+        } else {
+            0
+        }
+    }
+
+    /// Price of every night of a stay, by the highest-priority rule matching it.
+    /// A trailing partial night is charged pro rata.
+    pub fn nightly_prices(
+        &self,
+        check_in: u64,
+        check_out: u64,
+        booked_at: u64,
+        guests: usize,
+    ) -> Vec<NightlyPrice> {
+        let guest_fee = extra_guest_fee(guests, self.included_guests, self.extra_guest_fee);
+        let mut nights = vec![];
+        let mut night = check_in;
+        while night < check_out {
+            let length = (check_out - night).min(86400);
+            let price = self
+                .price_rules
+                .iter()
+                .filter(|rule| rule.condition.matches(night, check_in, booked_at))
+                // the first of equally ranked rules wins
+                .rev()
+                .max_by_key(|rule| rule.priority)
+                .map(|rule| rule.adjustment.apply(self.price_per_day))
+                .unwrap_or(self.price_per_day);
+            nights.push(NightlyPrice {
+                date: night,
+                price: Uint128::new(price * u128::from(length) / 86400),
+                guest_fee: Uint128::new(guest_fee * u128::from(length) / 86400),
+            });
+            night += length;
+        }
+        nights
+    }
+}
+
+#[cw_serde]
+pub enum PriceCondition {
+    /// Nights inside the range, for seasons and holidays
+    Dates { range: DateRange },
+    /// Nights on these days of the week, 0 is Monday
+    Weekdays { weekdays: Vec<u8> },
+    /// Bookings made less than `days` before check-in
+    LastMinute { days: u64 },
+    /// Bookings made at least `days` before check-in
+    EarlyBird { days: u64 },
+}
+
+impl PriceCondition {
+    pub fn matches(&self, night: u64, check_in: u64, booked_at: u64) -> bool {
+        let lead_time = check_in.saturating_sub(booked_at);
+        match self {
+            PriceCondition::Dates { range } => range.overlaps(night, night + 1),
+            PriceCondition::Weekdays { weekdays } => weekdays.contains(&weekday(night)),
+            PriceCondition::LastMinute { days } => lead_time < days.saturating_mul(86400),
+            PriceCondition::EarlyBird { days } => lead_time >= days.saturating_mul(86400),
+        }
+    }
+}
+
+#[cw_serde]
+pub enum PriceAdjustment {
+    /// Replaces the nightly price
+    Fixed { price: u128 },
+    /// Scales the nightly price, in basis points of `price_per_day`
+    Percent { bps: u64 },
+}
+
+impl PriceAdjustment {
+    pub fn apply(&self, price_per_day: u128) -> u128 {
+        match self {
+            PriceAdjustment::Fixed { price } => *price,
+            PriceAdjustment::Percent { bps } => price_per_day * u128::from(*bps) / 10000,
+        }
+    }
+}
+
+#[cw_serde]
+pub struct PriceRule {
+    /// Higher priorities win when several rules match a night
+    pub priority: u32,
+    pub condition: PriceCondition,
+    pub adjustment: PriceAdjustment,
+}
+
+#[cw_serde]
+pub struct NightlyPrice {
+    pub date: u64,
+    pub price: Uint128,
+    /// Extra-guest fee charged on top of `price`
+    pub guest_fee: Uint128,
+}
+
+#[cw_serde]
+pub struct ShortTermQuote {
+    pub nights: Vec<NightlyPrice>,
+    /// Nightly prices and extra-guest fees of the whole stay, less `discount`
+    pub rent_amount: Uint128,
+    /// Length-of-stay and promo code discounts
+    #[serde(default)]
+    pub discount: Uint128,
+    /// Platform fee paid on top of the rent
+    pub fee: Uint128,
+    /// Refundable, held apart from the rent until the claim window closes
+    #[serde(default)]
+    pub damage_deposit: Uint128,
+    #[serde(default)]
+    pub fees: Vec<ChargedFee>,
+    pub total: Uint128,
+    #[serde(default)]
+    pub denom: String,
+    #[serde(default)]
+    pub refund_schedule: Vec<RefundStep>,
+}
+
+#[cw_serde]
+pub struct LongTermQuote {
+    /// First month's rent, the least a reservation has to deposit
+    pub rent_amount: Uint128,
+    /// Platform fee on the first month, taken from the landlord's payout rather than added
+    /// to `total`
+    pub fee: Uint128,
+    pub fees: Vec<ChargedFee>,
+    pub total: Uint128,
+    pub denom: String,
+    /// The rent is not refundable, the single step refunds the fee lines as their
+    /// `FeeRefund` allows
+    pub refund_schedule: Vec<RefundStep>,
+}
+
+#[cw_serde]
+pub enum FeeKind {
+    Cleaning,
+    Service,
+    Tax,
+}
+
+#[cw_serde]
+pub enum FeeRecipient {
+    /// Paid out with the rent
+    Host,
+    /// Added to the contract balance like the platform fee
+    Platform,
+    /// A tax authority or other third party
+    Address { address: String },
+}
+
+#[cw_serde]
+pub enum FeeCharge {
+    /// Once per stay
+    Flat { amount: Uint128 },
+    /// For every started night of the stay
+    PerNight { amount: Uint128 },
+    /// Share of the rent in basis points, of the first month for long-term stays
+    Percent { bps: u64 },
+}
+
+/// What the traveler gets back of a fee line when cancelling an approved stay.
+/// Rejected, expired and host-cancelled reservations are always refunded in full.
+#[cw_serde]
+pub enum FeeRefund {
+    Refundable,
+    NonRefundable,
+    /// The same share as the rent under the cancellation policy
+    AsRent,
+}
+
+#[cw_serde]
+pub struct FeeLine {
+    pub kind: FeeKind,
+    pub name: String,
+    pub charge: FeeCharge,
+    pub recipient: FeeRecipient,
+    pub refund: FeeRefund,
+}
+
+impl FeeLine {
+    pub fn charged(&self, rent: Uint128, nights: u64) -> ChargedFee {
+        let amount = match &self.charge {
+            FeeCharge::Flat { amount } => *amount,
+            FeeCharge::PerNight { amount } => *amount * Uint128::from(nights),
+            FeeCharge::Percent { bps } => rent.multiply_ratio(*bps, 10000u64),
+        };
+        ChargedFee {
+            kind: self.kind.clone(),
+            name: self.name.clone(),
+            amount,
+            recipient: self.recipient.clone(),
+            refund: self.refund.clone(),
+        }
+    }
+}
+
+/// Fee line as charged on a reservation
+#[cw_serde]
+pub struct ChargedFee {
+    pub kind: FeeKind,
+    pub name: String,
+    pub amount: Uint128,
+    pub recipient: FeeRecipient,
+    pub refund: FeeRefund,
+}
+
+impl ChargedFee {
+    /// Part refunded on a cancellation refunding `percentage` of the rent
+    pub fn refund_amount(&self, percentage: u64) -> Uint128 {
+        match self.refund {
+            FeeRefund::Refundable => self.amount,
+            FeeRefund::NonRefundable => Uint128::new(0),
+            FeeRefund::AsRent => self.amount.multiply_ratio(percentage, 100u64),
+        }
+    }
+}
+
+/// Sum of the charged fee lines
+pub fn fees_total(fees: &[ChargedFee]) -> Uint128 {
+    fees.iter().map(|fee| fee.amount).sum()
+}
+
+/// Time range in seconds, `start` inclusive and `end` exclusive
+#[cw_serde]
+pub struct DateRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl DateRange {
+    pub fn overlaps(&self, start: u64, end: u64) -> bool {
+        self.start < end && start < self.end
+    }
+}
+
+#[cw_serde]
+pub enum RecurringClosure {
+    /// Closed on these days of every week, 0 is Monday
+    Weekly { weekdays: Vec<u8> },
+    /// Closed every year between two dates, both inclusive, wrapping over new year if needed
+    Yearly {
+        from_month: u32,
+        from_day: u32,
+        to_month: u32,
+        to_day: u32,
+    },
+}
+
+impl RecurringClosure {
+    pub fn is_closed(&self, time: u64) -> bool {
+        match self {
+            RecurringClosure::Weekly { weekdays } => weekdays.contains(&weekday(time)),
+            RecurringClosure::Yearly {
+                from_month,
+                from_day,
+                to_month,
+                to_day,
+            } => {
+                let (_, month, day) = civil_date(time);
+                let date = month * 100 + day;
+                let from = from_month * 100 + from_day;
+                let to = to_month * 100 + to_day;
+                if from <= to {
+                    from <= date && date <= to
+                } else {
+                    date >= from || date <= to
+                }
+            }
+        }
+    }
+}
+
+/// Days a short-term rental can be booked on
+#[cw_serde]
+#[derive(Default)]
+pub struct AvailabilityCalendar {
+    /// Bookable ranges, the property is open at all times if empty
+    pub open: Vec<DateRange>,
+    /// Ranges blocked by the host
+    pub blocked: Vec<DateRange>,
+    pub closures: Vec<RecurringClosure>,
+}
+
+impl AvailabilityCalendar {
+    /// Whether every night between `start` and `end` can be booked
+    pub fn is_available(&self, start: u64, end: u64) -> bool {
+        if !self.open.is_empty()
+            && !self
+                .open
+                .iter()
+                .any(|range| range.start <= start && end <= range.end)
+        {
+            return false;
+        }
+        if self.blocked.iter().any(|range| range.overlaps(start, end)) {
+            return false;
+        }
+        let mut night = start;
+        while night < end {
+            if self.closures.iter().any(|closure| closure.is_closed(night)) {
+                return false;
+            }
+            night += 86400;
+        }
+        true
+    }
+}
+
+/// Day of the week of a unix timestamp, 0 is Monday
+pub fn weekday(time: u64) -> u8 {
+    // 1970-01-01 was a Thursday
+    ((time / 86400 + 3) % 7) as u8
+}
+
+/// (year, month, day) of a unix timestamp in UTC
+pub fn civil_date(time: u64) -> (i64, u32, u32) {
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = (time / 86400) as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cw_serde]
+pub struct AvailabilityResponse {
+    pub free: Vec<DateRange>,
+    /// Closed by the host or already reserved
+    pub blocked: Vec<DateRange>,
 }
 
 #[cw_serde]
@@ -188,6 +797,364 @@ pub struct Sell {
     pub denom: String,
 }
 
+#[cw_serde]
+#[derive(Default)]
+pub struct Auction {
+    pub islisted: Option<bool>,
+    pub denom: String,
+    /// The first bid has to reach at least this amount
+    pub reserve_price: u128,
+    pub start_time: u64,
+    pub end_time: u64,
+    /// Every new bid has to beat the current highest bid by at least this amount
+    pub minimum_increment: u128,
+    /// Bids placed within this many seconds of `end_time` push the end back by the same window
+    pub extension_window: u64,
+    pub highest_bid: Option<Bid>,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct DutchAuction {
+    pub islisted: Option<bool>,
+    pub denom: String,
+    pub start_price: u128,
+    /// The price never drops below this amount
+    pub floor_price: u128,
+    pub start_time: u64,
+    /// Time at which the price reaches `floor_price`
+    pub end_time: u64,
+    /// Seconds between price drops, 0 for a linear decay
+    pub step_interval: u64,
+}
+
+impl DutchAuction {
+    /// Price asked at `time`, decaying from `start_price` to `floor_price`
+    /// between `start_time` and `end_time`
+    pub fn price_at(&self, time: u64) -> u128 {
+        if time <= self.start_time || self.end_time <= self.start_time {
+            return self.start_price;
+        }
+        if time >= self.end_time {
+            return self.floor_price;
+        }
+
+        let mut elapsed = time - self.start_time;
+        if self.step_interval > 0 {
+            elapsed -= elapsed % self.step_interval;
+        }
+        let drop = Uint128::new(self.start_price - self.floor_price)
+            .multiply_ratio(elapsed, self.end_time - self.start_time);
+        self.start_price - drop.u128()
+    }
+}
+
+/// Staged sale: the buyer escrows an earnest deposit with the offer, can back out during
+/// the inspection window, then pays the balance to close.
+#[cw_serde]
+pub struct EscrowSale {
+    pub buyer: String,
+    pub denom: String,
+    /// Full purchase price, including the deposit
+    pub price: Uint128,
+    /// Earnest money escrowed with the offer
+    pub deposit: Uint128,
+    /// Seconds after acceptance in which the buyer can withdraw without penalty
+    pub inspection_period: u64,
+    /// Seconds after the inspection window to pay the balance and close
+    pub closing_period: u64,
+    /// Share of the deposit in basis points forfeited to the seller
+    /// when the buyer backs out after the inspection window
+    pub penalty: u64,
+    /// Set once the seller accepts the offer
+    pub accepted_at: Option<u64>,
+}
+
+impl EscrowSale {
+    pub fn inspection_end(&self) -> Option<u64> {
+        self.accepted_at
+            .map(|time| time.saturating_add(self.inspection_period))
+    }
+
+    pub fn closing_deadline(&self) -> Option<u64> {
+        self.inspection_end()
+            .map(|time| time.saturating_add(self.closing_period))
+    }
+
+    pub fn balance_due(&self) -> Uint128 {
+        self.price - self.deposit
+    }
+
+    /// Part of the deposit the buyer forfeits by cancelling at `time`
+    pub fn penalty_at(&self, time: u64) -> Uint128 {
+        match self.inspection_end() {
+            Some(end) if time > end => self.deposit.multiply_ratio(self.penalty, 10000u64),
+            _ => Uint128::zero(),
+        }
+    }
+}
+
+#[cw_serde]
+pub struct Trait {
+    pub key: String,
+    pub value: String,
+}
+
+/// Funds escrowed by a bidder to buy a token, listed for sale or not
+#[cw_serde]
+pub struct Offer {
+    pub id: u64,
+    pub bidder: String,
+    pub denom: String,
+    /// Escrowed amount, paid to the owner on acceptance
+    pub amount: Uint128,
+    /// Token the offer is made on, unset for an offer on any token matching `traits`
+    pub token_id: Option<String>,
+    pub traits: Vec<Trait>,
+    pub expires: Option<Expiration>,
+}
+
+impl Offer {
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        self.expires.is_some_and(|e| e.is_expired(block))
+    }
+}
+
+#[cw_serde]
+pub struct EscrowSaleResponse {
+    pub escrow: Option<EscrowSale>,
+    pub inspection_end: Option<u64>,
+    pub closing_deadline: Option<u64>,
+    pub balance_due: Uint128,
+    /// Deposit the buyer would forfeit by cancelling now
+    pub cancellation_penalty: Uint128,
+}
+
+/// Seconds after a stay is finalized during which its host and traveler can review it
+pub const REVIEW_WINDOW: u64 = 14 * 86400;
+
+/// Finished stay whose host and traveler can each review the other once
+#[cw_serde]
+pub struct CompletedStay {
+    pub token_id: String,
+    pub host: String,
+    pub traveler: String,
+    pub rental_type: bool,
+    pub renting_period: Vec<u64>,
+    pub host_reviewed: bool,
+    pub traveler_reviewed: bool,
+    /// End of the review window
+    pub review_by: u64,
+}
+
+#[cw_serde]
+pub struct Review {
+    pub id: u64,
+    pub token_id: String,
+    pub renting_period: Vec<u64>,
+    pub author: String,
+    /// The traveler for reviews by the host, the host otherwise
+    pub subject: String,
+    pub by_host: bool,
+    /// From 1 to 5
+    pub rating: u8,
+    /// Hash of the review text stored off-chain
+    pub review_hash: String,
+    pub created_at: u64,
+}
+
+#[cw_serde]
+pub struct ReviewsResponse {
+    pub reviews: Vec<Review>,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct Rating {
+    pub count: u64,
+    /// Sum of all ratings
+    pub total: u64,
+}
+
+impl Rating {
+    pub fn add(&mut self, rating: u8) {
+        self.count += 1;
+        self.total += u64::from(rating);
+    }
+
+    pub fn average(&self) -> Decimal {
+        if self.count == 0 {
+            return Decimal::zero();
+        }
+        Decimal::from_ratio(self.total, self.count)
+    }
+}
+
+#[cw_serde]
+pub struct RatingResponse {
+    pub count: u64,
+    pub average: Decimal,
+}
+
+/// Contract-wide rules for hosts cancelling approved stays
+#[cw_serde]
+#[derive(Default)]
+pub struct HostPolicy {
+    /// Share of the cancelled stay's deposit in basis points paid to the traveler
+    /// out of the host bond
+    pub cancellation_penalty: u64,
+    /// Hosts with more cancellations no longer auto-approve reservations
+    pub max_cancellations_for_auto_approve: Option<u32>,
+    /// Hosts with more cancellations cannot list rentals or take new reservations
+    pub max_cancellations_for_listing: Option<u32>,
+    /// Bond a host must hold to list a rental and take reservations
+    #[serde(default)]
+    pub min_bond: Option<Coin>,
+}
+
+impl HostPolicy {
+    pub fn can_auto_approve(&self, record: &HostRecord) -> bool {
+        match self.max_cancellations_for_auto_approve {
+            Some(max) => record.cancellations <= max,
+            None => true,
+        }
+    }
+
+    pub fn can_list(&self, record: &HostRecord) -> bool {
+        match self.max_cancellations_for_listing {
+            Some(max) => record.cancellations <= max,
+            None => true,
+        }
+    }
+
+    pub fn has_min_bond(&self, bond: Option<&Coin>) -> bool {
+        match (&self.min_bond, bond) {
+            (None, _) => true,
+            (Some(min), Some(bond)) => bond.denom == min.denom && bond.amount >= min.amount,
+            (Some(min), None) => min.amount.is_zero(),
+        }
+    }
+}
+
+#[cw_serde]
+pub enum PromoDiscount {
+    /// Share of the rent in basis points
+    Percent { bps: u64 },
+    /// Taken off the rent, at most the whole rent
+    Fixed { amount: Uint128 },
+}
+
+impl PromoDiscount {
+    pub fn apply(&self, rent: Uint128) -> Uint128 {
+        match self {
+            PromoDiscount::Percent { bps } => rent.multiply_ratio(*bps, 10000u64),
+            PromoDiscount::Fixed { amount } => rent.min(*amount),
+        }
+    }
+}
+
+/// Discount code redeemed on short-term reservations
+#[cw_serde]
+pub struct PromoCode {
+    pub code: String,
+    pub issuer: String,
+    /// Token the code is valid for, platform codes without one are valid for any token
+    /// whose host accepts platform promo codes
+    pub token_id: Option<String>,
+    pub discount: PromoDiscount,
+    /// Unlimited if unset
+    pub max_uses: Option<u32>,
+    pub uses: u32,
+    pub expires: Option<Expiration>,
+}
+
+impl PromoCode {
+    pub fn is_redeemable(&self, block: &BlockInfo, token_id: &str) -> bool {
+        let for_token = match &self.token_id {
+            Some(id) => id == token_id,
+            None => true,
+        };
+        let used_up = match self.max_uses {
+            Some(max) => self.uses >= max,
+            None => false,
+        };
+        let expired = match &self.expires {
+            Some(expires) => expires.is_expired(block),
+            None => false,
+        };
+        for_token && !used_up && !expired
+    }
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct HostRecord {
+    /// Approved stays the host cancelled
+    pub cancellations: u32,
+    pub last_cancellation: Option<u64>,
+    /// Latest check-out of a stay booked at the host's properties, the host bond
+    /// cannot be withdrawn before it
+    #[serde(default)]
+    pub bond_locked_until: u64,
+}
+
+#[cw_serde]
+pub struct HostRecordResponse {
+    pub record: HostRecord,
+    pub bond: Option<Coin>,
+    pub auto_approve_eligible: bool,
+    pub listing_eligible: bool,
+}
+
+/// Security deposit of a short-term stay, held until the host's claim window closes
+#[cw_serde]
+pub struct DamageDeposit {
+    pub token_id: String,
+    pub traveler: String,
+    pub renting_period: Vec<u64>,
+    pub denom: String,
+    pub amount: Uint128,
+    /// Deduction claimed by the host
+    pub claimed: Uint128,
+    pub reason: Option<String>,
+    /// Set once the traveler disputes the claim, which then awaits the contract owner
+    pub disputed: bool,
+    /// End of the claim window, after which the deposit can be released
+    pub claim_deadline: u64,
+    /// Time of the host's latest claim, open to dispute for a claim window from then
+    pub claimed_at: Option<u64>,
+    /// Shares of a group reservation, the refund is split among the payers in proportion
+    #[serde(default)]
+    pub payers: Vec<PayerShare>,
+}
+
+impl DamageDeposit {
+    pub fn refundable(&self) -> Uint128 {
+        self.amount - self.claimed
+    }
+
+    /// Last moment the traveler can dispute the host's claim, if one was made
+    pub fn dispute_deadline(&self) -> Option<u64> {
+        let claim_window = self.claim_deadline.saturating_sub(self.renting_period[1]);
+        self.claimed_at
+            .map(|claimed_at| claimed_at.saturating_add(claim_window))
+    }
+}
+
+#[cw_serde]
+pub struct DamageDepositsResponse {
+    pub deposits: Vec<DamageDeposit>,
+}
+
+#[cw_serde]
+pub struct Royalty {
+    pub recipient: String,
+    /// Share of the sale price in basis points
+    pub share: u64,
+    /// Also take the royalty from rental income paid out to the owner
+    pub on_rentals: bool,
+}
+
 #[cw_serde]
 pub struct ApprovalResponse {
     pub approval: Approval,
@@ -208,6 +1175,71 @@ pub struct BidsResponse {
     pub bids: Vec<Bid>,
 }
 
+#[cw_serde]
+pub struct BidderBid {
+    pub token_id: String,
+    pub offer: Uint128,
+    pub denom: String,
+    /// Sale listing status of the token
+    pub islisted: Option<bool>,
+    pub expires: Option<Expiration>,
+    pub counter_offer: Option<Uint128>,
+}
+
+#[cw_serde]
+pub struct BidderBidsResponse {
+    pub bids: Vec<BidderBid>,
+}
+
+#[cw_serde]
+pub struct OffersResponse {
+    pub offers: Vec<Offer>,
+}
+
+#[cw_serde]
+pub struct AuctionStatusResponse {
+    pub highest_bid: Option<Bid>,
+    /// Smallest amount the next bid has to send
+    pub minimum_bid: Uint128,
+    pub end_time: u64,
+    pub time_remaining: u64,
+}
+
+#[cw_serde]
+pub struct CurrentPriceResponse {
+    pub price: Uint128,
+    pub denom: String,
+}
+
+#[cw_serde]
+pub struct RoyaltiesInfoResponse {
+    pub address: String,
+    pub royalty_amount: Uint128,
+}
+
+#[cw_serde]
+pub struct SharesResponse {
+    pub shares: Uint128,
+    pub total_shares: Uint128,
+}
+
+#[cw_serde]
+pub struct ShareHolder {
+    pub address: String,
+    pub shares: Uint128,
+}
+
+#[cw_serde]
+pub struct ShareHoldersResponse {
+    pub holders: Vec<ShareHolder>,
+    pub total_shares: Uint128,
+}
+
+#[cw_serde]
+pub struct RewardsResponse {
+    pub rewards: Vec<Coin>,
+}
+
 #[cw_serde]
 pub struct OperatorResponse {
     pub approval: Approval,
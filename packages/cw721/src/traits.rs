@@ -2,9 +2,10 @@ use serde::de::DeserializeOwned;
 use serde::Serialize;
 
 use crate::{
-    AllNftInfoResponse, ApprovalResponse, ApprovalsResponse, BidsResponse, ContractInfoResponse,
-    LongTermRental, NftInfoResponse, NumTokensResponse, OperatorResponse, OperatorsResponse,
-    OwnerOfResponse, RentalsResponse, Sell, ShortTermRental, TokensResponse,
+    AllNftInfoResponse, ApprovalResponse, ApprovalsResponse, Auction, AuctionStatusResponse,
+    BidsResponse, ContractInfoResponse, CurrentPriceResponse, DutchAuction, LongTermRental,
+    NftInfoResponse, NumTokensResponse, OperatorResponse, OperatorsResponse, OwnerOfResponse,
+    RentalsResponse, Sell, ShortTermRental, TokensResponse,
 };
 use cosmwasm_std::{CustomMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
 use cw_utils::Expiration;
@@ -106,6 +107,23 @@ where
     fn nft_sell_info(&self, deps: Deps, token_id: String) -> StdResult<Sell>;
     fn nft_rentals_info(&self, deps: Deps, token_id: String) -> StdResult<RentalsResponse>;
     fn nft_bids_info(&self, deps: Deps, token_id: String) -> StdResult<BidsResponse>;
+    fn nft_auction_info(&self, deps: Deps, token_id: String) -> StdResult<Auction>;
+
+    fn auction_status(
+        &self,
+        deps: Deps,
+        env: Env,
+        token_id: String,
+    ) -> StdResult<AuctionStatusResponse>;
+
+    fn nft_dutch_auction_info(&self, deps: Deps, token_id: String) -> StdResult<DutchAuction>;
+
+    fn current_price(
+        &self,
+        deps: Deps,
+        env: Env,
+        token_id: String,
+    ) -> StdResult<CurrentPriceResponse>;
 
     fn owner_of(
         &self,